@@ -0,0 +1,186 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span};
+use quote::quote;
+use syn::{Data, DataStruct, DeriveInput, Fields, Index, Member, Type};
+
+struct KeyField {
+    member: Member,
+    var: Ident,
+    typ: Type,
+}
+
+fn get_struct_data(input: &DeriveInput) -> &DataStruct {
+    match &input.data {
+        Data::Struct(x) => x,
+        _ => panic!("BinaryKey can be derived for structs only."),
+    }
+}
+
+fn get_key_fields(data: &DataStruct) -> Vec<KeyField> {
+    if data.fields.iter().next().is_none() {
+        panic!("BinaryKey cannot be derived for structs without fields.");
+    }
+
+    data.fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let member = match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(Index::from(i)),
+            };
+            KeyField {
+                member,
+                var: Ident::new(&format!("field_{}", i), Span::call_site()),
+                typ: field.ty.clone(),
+            }
+        })
+        .collect()
+}
+
+fn implement_size(fields: &[KeyField]) -> impl quote::ToTokens {
+    let members = fields.iter().map(|f| &f.member);
+
+    quote! {
+        fn size(&self) -> usize {
+            let mut _size = 0;
+            #( _size += exonum_merkledb::BinaryKey::size(&self.#members); )*
+            _size
+        }
+    }
+}
+
+fn implement_write(fields: &[KeyField]) -> impl quote::ToTokens {
+    let members = fields.iter().map(|f| &f.member);
+
+    quote! {
+        fn write(&self, buffer: &mut [u8]) -> usize {
+            let mut _pos = 0;
+            #(
+                let _field = &self.#members;
+                let _size = exonum_merkledb::BinaryKey::size(_field);
+                exonum_merkledb::BinaryKey::write(_field, &mut buffer[_pos.._pos + _size]);
+                _pos += _size;
+            )*
+            _pos
+        }
+    }
+}
+
+fn implement_read(fields: &[KeyField], shape: &Fields) -> impl quote::ToTokens {
+    let vars = fields.iter().map(|f| &f.var).collect::<Vec<_>>();
+    let read_vars = vars.clone();
+    let size_vars = vars.clone();
+    let types = fields.iter().map(|f| &f.typ);
+    let constructor = match shape {
+        Fields::Named(_) => {
+            let members = fields.iter().map(|f| &f.member);
+            quote!(Self { #( #members: #vars, )* })
+        }
+        Fields::Unnamed(_) => quote!(Self( #( #vars, )* )),
+        Fields::Unit => unreachable!(),
+    };
+
+    // Each field is read from the rest of the buffer; its size is then determined
+    // from the read value.
+    quote! {
+        fn read(buffer: &[u8]) -> Self::Owned {
+            let mut _pos = 0;
+            #(
+                let #read_vars = <#types as exonum_merkledb::BinaryKey>::read(&buffer[_pos..]);
+                _pos += exonum_merkledb::BinaryKey::size(&#size_vars);
+            )*
+            #constructor
+        }
+    }
+}
+
+fn implement_try_read(fields: &[KeyField], shape: &Fields) -> impl quote::ToTokens {
+    let vars = fields.iter().map(|f| &f.var).collect::<Vec<_>>();
+    let read_vars = vars.clone();
+    let size_vars = vars.clone();
+    let types = fields.iter().map(|f| &f.typ).collect::<Vec<_>>();
+    let read_types = types.clone();
+    let constructor = match shape {
+        Fields::Named(_) => {
+            let members = fields.iter().map(|f| &f.member);
+            quote!(Self { #( #members: #vars, )* })
+        }
+        Fields::Unnamed(_) => quote!(Self( #( #vars, )* )),
+        Fields::Unit => unreachable!(),
+    };
+
+    // Fields of a fixed size are read from the corresponding part of the buffer,
+    // and a field of a variable size is read from the rest of the buffer.
+    quote! {
+        fn try_read(buffer: &[u8]) -> Result<Self::Owned, failure::Error> {
+            let mut _pos = 0;
+            #(
+                let _end = match <#types as exonum_merkledb::BinaryKey>::size_of_fixed_key() {
+                    Some(size) if _pos + size <= buffer.len() => _pos + size,
+                    Some(_) => return Err(failure::err_msg("Key is too short")),
+                    None => buffer.len(),
+                };
+                let #read_vars =
+                    <#read_types as exonum_merkledb::BinaryKey>::try_read(&buffer[_pos.._end])?;
+                _pos += exonum_merkledb::BinaryKey::size(&#size_vars);
+            )*
+            if _pos != buffer.len() {
+                return Err(failure::err_msg("Key has trailing bytes"));
+            }
+            Ok(#constructor)
+        }
+    }
+}
+
+fn implement_size_of_fixed_key(fields: &[KeyField]) -> impl quote::ToTokens {
+    let types = fields.iter().map(|f| &f.typ);
+
+    // The key has a fixed size only if all its fields have fixed sizes.
+    quote! {
+        fn size_of_fixed_key() -> Option<usize> {
+            let mut _size = 0;
+            #( _size += <#types as exonum_merkledb::BinaryKey>::size_of_fixed_key()?; )*
+            Some(_size)
+        }
+    }
+}
+
+pub fn implement_binary_key(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = syn::parse(input).unwrap();
+
+    let name = input.ident.clone();
+    let data = get_struct_data(&input);
+    let fields = get_key_fields(data);
+    let size_fn = implement_size(&fields);
+    let write_fn = implement_write(&fields);
+    let read_fn = implement_read(&fields, &data.fields);
+    let size_of_fixed_key_fn = implement_size_of_fixed_key(&fields);
+    let try_read_fn = implement_try_read(&fields, &data.fields);
+
+    let expanded = quote! {
+        impl exonum_merkledb::BinaryKey for #name {
+            #size_fn
+            #write_fn
+            #read_fn
+            #size_of_fixed_key_fn
+            #try_read_fn
+        }
+    };
+
+    expanded.into()
+}
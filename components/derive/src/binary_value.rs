@@ -0,0 +1,120 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span};
+use quote::quote;
+use syn::{Attribute, DeriveInput, Lit};
+
+use super::{get_exonum_name_value_attributes, get_exonum_types_prefix, CODEC_ATTRIBUTE};
+
+/// Encoding used for the values in the storage.
+enum Codec {
+    Protobuf,
+    Bincode,
+}
+
+fn get_codec(attrs: &[Attribute]) -> Codec {
+    let map_attrs = get_exonum_name_value_attributes(attrs);
+    let codec = map_attrs.into_iter().find_map(|nv| {
+        if nv.ident == CODEC_ATTRIBUTE {
+            match nv.lit {
+                Lit::Str(codec) => Some(codec.value()),
+                _ => panic!("{} attribute should be a string.", CODEC_ATTRIBUTE),
+            }
+        } else {
+            None
+        }
+    });
+
+    match codec.as_ref().map(String::as_str) {
+        None | Some("pb") => Codec::Protobuf,
+        Some("bincode") => Codec::Bincode,
+        Some(other) => panic!("Unknown codec: {}. Use `pb` or `bincode`.", other),
+    }
+}
+
+/// Implements `BinaryValue` via `ProtobufConvert`. Expects `ProtobufConvert` and
+/// `protobuf::Message` to be in scope.
+pub fn implement_pb_binary_value(name: &Ident) -> proc_macro2::TokenStream {
+    quote! {
+        // This trait assumes that we work with trusted data so we can unwrap here.
+        impl exonum_merkledb::BinaryValue for #name {
+            fn to_bytes(&self) -> Vec<u8> {
+                self.to_pb().write_to_bytes().expect(&format!(
+                    "Failed to serialize in BinaryValue for {}",
+                    stringify!(#name)
+                ))
+            }
+
+            fn from_bytes(value: std::borrow::Cow<[u8]>) -> Result<Self, failure::Error> {
+                let mut block = <Self as ProtobufConvert>::ProtoStruct::new();
+                block.merge_from_bytes(value.as_ref())?;
+                ProtobufConvert::from_pb(block)
+            }
+        }
+    }
+}
+
+fn implement_bincode_binary_value(name: &Ident) -> proc_macro2::TokenStream {
+    quote! {
+        extern crate bincode as _bincode;
+
+        impl exonum_merkledb::BinaryValue for #name {
+            fn to_bytes(&self) -> Vec<u8> {
+                _bincode::serialize(self).expect(&format!(
+                    "Failed to serialize in BinaryValue for {}",
+                    stringify!(#name)
+                ))
+            }
+
+            fn from_bytes(value: std::borrow::Cow<[u8]>) -> Result<Self, failure::Error> {
+                _bincode::deserialize(value.as_ref()).map_err(From::from)
+            }
+        }
+    }
+}
+
+pub fn implement_binary_value(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = syn::parse(input).unwrap();
+
+    let name = input.ident.clone();
+    let cr = get_exonum_types_prefix(&input.attrs);
+    let mod_name = Ident::new(&format!("binary_value_impl_{}", name), Span::call_site());
+
+    let binary_value = match get_codec(&input.attrs) {
+        Codec::Protobuf => {
+            let binary_value = implement_pb_binary_value(&name);
+            quote! {
+                extern crate protobuf as _protobuf_crate;
+
+                use self::_protobuf_crate::Message as _ProtobufMessage;
+                use #cr::proto::ProtobufConvert;
+
+                #binary_value
+            }
+        }
+        Codec::Bincode => implement_bincode_binary_value(&name),
+    };
+
+    let expanded = quote! {
+        mod #mod_name {
+            use super::*;
+
+            #binary_value
+        }
+    };
+
+    expanded.into()
+}
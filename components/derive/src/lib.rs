@@ -16,6 +16,8 @@
 
 extern crate proc_macro;
 
+mod binary_key;
+mod binary_value;
 mod pb_convert;
 mod tx_set;
 
@@ -28,6 +30,7 @@ use syn::{Attribute, Lit, Meta, MetaList, MetaNameValue, NestedMeta, Path};
 const CRATE_PATH_ATTRIBUTE: &str = "crate";
 const PB_CONVERT_ATTRIBUTE: &str = "pb";
 const SERDE_PB_CONVERT_ATTRIBUTE: &str = "serde_pb_convert";
+const CODEC_ATTRIBUTE: &str = "codec";
 
 /// Derives `ProtobufConvert` trait.
 ///
@@ -65,6 +68,109 @@ pub fn generate_protobuf_convert(input: TokenStream) -> TokenStream {
     pb_convert::implement_protobuf_convert(input)
 }
 
+/// Derives `BinaryKey` trait for a struct with named or unnamed fields.
+///
+/// The key is serialized as a concatenation of the serialized fields in the order
+/// of their declaration. If each field type preserves the order of its values in
+/// the serialized form, the derived key is ordered lexicographically by its fields,
+/// like tuples in Rust. All fields except the last one must have a fixed serialized size
+/// (e.g., integers, hashes and public keys); variable-sized types like `String`
+/// or `Vec<u8>` may only be placed last.
+///
+/// Since `BinaryKey` requires `ToOwned`, the struct should implement `Clone`.
+/// The derived `try_read` method validates each field with its own `try_read`,
+/// so the crate should depend on `failure`.
+///
+/// # Examples
+///
+/// ```
+/// use exonum::crypto::{self, PublicKey};
+/// use exonum_derive::BinaryKey;
+/// use exonum_merkledb::BinaryKey;
+///
+/// /// Key of a wallet history entry.
+/// #[derive(Debug, Clone, PartialEq, BinaryKey)]
+/// struct HistoryKey(PublicKey, u64);
+///
+/// #[derive(Debug, Clone, PartialEq, BinaryKey)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// # fn main() {
+/// let (public_key, _) = crypto::gen_keypair();
+/// let key = HistoryKey(public_key, 5);
+/// let mut buffer = vec![0; key.size()];
+/// key.write(&mut buffer);
+/// assert_eq!(HistoryKey::read(&buffer), key);
+/// assert_eq!(HistoryKey::try_read(&buffer).unwrap(), key);
+/// assert!(HistoryKey::try_read(&buffer[..10]).is_err());
+///
+/// // Keys are ordered by the first field, then by the second one.
+/// let (mut x, mut y) = (vec![0; 8], vec![0; 8]);
+/// Point { x: -1, y: 10 }.write(&mut x);
+/// Point { x: 1, y: -10 }.write(&mut y);
+/// assert!(x < y);
+/// # }
+/// ```
+#[proc_macro_derive(BinaryKey)]
+pub fn binary_key_derive(input: TokenStream) -> TokenStream {
+    binary_key::implement_binary_key(input)
+}
+
+/// Derives `BinaryValue` trait.
+///
+/// Note that `ProtobufConvert` derive already implements `BinaryValue`; this macro is useful
+/// for types with a manual `ProtobufConvert` implementation or for types serialized
+/// with `bincode`.
+///
+/// # Attributes
+///
+/// ```text
+/// #[exonum(codec = "value")]
+/// ```
+///
+/// Optional. `value` is either `"pb"` or `"bincode"`; defaults to `"pb"`.
+///
+/// - `pb`: the value is serialized via `ProtobufConvert`, which should be implemented for
+///   the type. The crate should depend on `protobuf`.
+/// - `bincode`: the value is serialized with `bincode` via `serde::{Serialize, Deserialize}`,
+///   which should be implemented for the type. The crate should depend on `bincode`.
+///
+/// ```text
+/// #[exonum(crate = "path")]
+/// ```
+///
+/// Optional. `path` is a prefix of types from the `exonum` crate (usually `"crate"`
+/// or `"exonum"`). Used with the `pb` codec only.
+///
+/// # Examples
+///
+/// ```
+/// use exonum::crypto::Hash;
+/// use exonum_derive::BinaryValue;
+/// use exonum_merkledb::BinaryValue;
+/// use serde_derive::{Deserialize, Serialize};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize, BinaryValue)]
+/// #[exonum(codec = "bincode")]
+/// struct Wallet {
+///     balance: u64,
+///     history_hash: Hash,
+/// }
+///
+/// # fn main() {
+/// let wallet = Wallet { balance: 100, history_hash: Hash::zero() };
+/// let bytes = wallet.to_bytes();
+/// assert_eq!(Wallet::from_bytes(bytes.into()).unwrap(), wallet);
+/// # }
+/// ```
+#[proc_macro_derive(BinaryValue, attributes(exonum))]
+pub fn binary_value_derive(input: TokenStream) -> TokenStream {
+    binary_value::implement_binary_value(input)
+}
+
 /// Derives `TransactionSet` trait for an enum. The enum should have transactions as variants.
 ///
 /// Also implements:
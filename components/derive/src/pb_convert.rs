@@ -18,8 +18,9 @@ use quote::quote;
 use syn::{Attribute, Data, DeriveInput, Lit, Path};
 
 use super::{
-    find_exonum_word_attribute, get_exonum_name_value_attributes, get_exonum_types_prefix,
-    PB_CONVERT_ATTRIBUTE, SERDE_PB_CONVERT_ATTRIBUTE,
+    binary_value::implement_pb_binary_value, find_exonum_word_attribute,
+    get_exonum_name_value_attributes, get_exonum_types_prefix, PB_CONVERT_ATTRIBUTE,
+    SERDE_PB_CONVERT_ATTRIBUTE,
 };
 
 fn get_protobuf_struct_path(attrs: &[Attribute]) -> Path {
@@ -98,6 +99,8 @@ fn implement_protobuf_convert_trait(
 }
 
 fn implement_storage_traits(name: &Ident, cr: &dyn quote::ToTokens) -> impl quote::ToTokens {
+    let binary_value = implement_pb_binary_value(name);
+
     quote! {
         impl #cr::crypto::CryptoHash for #name {
             fn hash(&self) -> #cr::crypto::Hash {
@@ -113,21 +116,7 @@ fn implement_storage_traits(name: &Ident, cr: &dyn quote::ToTokens) -> impl quot
              }
         }
 
-        // This trait assumes that we work with trusted data so we can unwrap here.
-        impl exonum_merkledb::BinaryValue for #name {
-            fn to_bytes(&self) -> Vec<u8> {
-                self.to_pb().write_to_bytes().expect(&format!(
-                    "Failed to serialize in BinaryValue for {}",
-                    stringify!(#name)
-                ))
-            }
-
-            fn from_bytes(value: std::borrow::Cow<[u8]>) -> Result<Self, failure::Error> {
-                let mut block = <Self as ProtobufConvert>::ProtoStruct::new();
-                block.merge_from_bytes(value.as_ref())?;
-                ProtobufConvert::from_pb(block)
-            }
-        }
+        #binary_value
     }
 }
 
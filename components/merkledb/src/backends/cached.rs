@@ -0,0 +1,571 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An implementation of `CachedDB`, a read-through caching wrapper over a database.
+
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap, Bound, HashMap},
+    fmt,
+    hash::{Hash, Hasher},
+    path::Path,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{Database, DbStats, Iter, Patch, Result, Snapshot};
+
+/// Statistics of a [`CachedDB`] cache.
+///
+/// [`CachedDB`]: struct.CachedDB.html
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheStats {
+    /// Number of reads served from the cache.
+    pub hits: u64,
+    /// Number of reads passed to the underlying database.
+    pub misses: u64,
+    /// Number of entries in the cache.
+    pub entries: usize,
+    /// Total size of the cached entries in bytes.
+    pub size: usize,
+    /// Maximal total size of the cached entries in bytes.
+    pub capacity: usize,
+}
+
+/// Maximal number of shards of the cache.
+const MAX_SHARDS: usize = 16;
+/// Minimal capacity of a cache shard in bytes. Smaller caches have fewer shards.
+const MIN_SHARD_CAPACITY: usize = 1 << 20;
+
+/// Cached value of a key; `None` means that the key is absent in the database.
+struct CacheEntry {
+    value: Option<Vec<u8>>,
+    tick: u64,
+}
+
+impl CacheEntry {
+    fn size(&self, name: &str, key: &[u8]) -> usize {
+        name.len() + key.len() + self.value.as_ref().map_or(0, Vec::len)
+    }
+}
+
+/// LRU cache of the database entries limited by the total size of the entries.
+struct LruCache {
+    capacity: usize,
+    size: usize,
+    /// Number of merges into the database. Values read by snapshots created before
+    /// the latest merge are neither cached nor taken from the cache.
+    epoch: u64,
+    /// Counter used to track the order in which the entries were accessed.
+    tick: u64,
+    families: HashMap<String, HashMap<Vec<u8>, CacheEntry>>,
+    /// Entries of the cache ordered from the least recently used to the most recently used.
+    order: BTreeMap<u64, (String, Vec<u8>)>,
+    hits: u64,
+    misses: u64,
+}
+
+impl LruCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            size: 0,
+            epoch: 0,
+            tick: 0,
+            families: HashMap::new(),
+            order: BTreeMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    /// Returns the cached value of the key, or `None` if the key is not cached.
+    fn get(&mut self, epoch: u64, name: &str, key: &[u8]) -> Option<Option<Vec<u8>>> {
+        if epoch != self.epoch {
+            self.misses += 1;
+            return None;
+        }
+
+        let tick = self.next_tick();
+        let entry = self
+            .families
+            .get_mut(name)
+            .and_then(|family| family.get_mut(key));
+        match entry {
+            Some(entry) => {
+                let position = self
+                    .order
+                    .remove(&entry.tick)
+                    .expect("Cache entry is missing in the access order");
+                self.order.insert(tick, position);
+                entry.tick = tick;
+                self.hits += 1;
+                Some(entry.value.clone())
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn insert(&mut self, epoch: u64, name: &str, key: &[u8], value: Option<Vec<u8>>) {
+        if epoch != self.epoch {
+            return;
+        }
+
+        let entry = CacheEntry { value, tick: 0 };
+        let entry_size = entry.size(name, key);
+        if entry_size > self.capacity {
+            return;
+        }
+
+        self.remove(name, key);
+        let tick = self.next_tick();
+        self.families
+            .entry(name.to_owned())
+            .or_insert_with(HashMap::new)
+            .insert(key.to_vec(), CacheEntry { tick, ..entry });
+        self.order.insert(tick, (name.to_owned(), key.to_vec()));
+        self.size += entry_size;
+
+        while self.size > self.capacity {
+            let oldest = *self.order.keys().next().expect("Cache is empty");
+            let (name, key) = self.order.remove(&oldest).unwrap();
+            self.remove(&name, &key);
+        }
+    }
+
+    fn remove(&mut self, name: &str, key: &[u8]) {
+        if let Some(entry) = self
+            .families
+            .get_mut(name)
+            .and_then(|family| family.remove(key))
+        {
+            self.order.remove(&entry.tick);
+            self.size -= entry.size(name, key);
+        }
+    }
+
+    fn remove_prefix(&mut self, name: &str, prefix: &[u8]) {
+        let Self {
+            families,
+            order,
+            size,
+            ..
+        } = self;
+
+        if let Some(family) = families.get_mut(name) {
+            family.retain(|key, entry| {
+                if key.starts_with(prefix) {
+                    order.remove(&entry.tick);
+                    *size -= entry.size(name, key);
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+    }
+
+    /// Removes the entries changed by the patch from the cache.
+    fn invalidate(&mut self, patch: &Patch) {
+        for (name, changes) in patch.iter() {
+            for prefix in changes.prefixes_to_remove() {
+                self.remove_prefix(name, prefix);
+            }
+            for (key, _) in changes.iter() {
+                self.remove(name, key);
+            }
+        }
+    }
+
+    fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.order.len(),
+            size: self.size,
+            capacity: self.capacity,
+        }
+    }
+}
+
+/// Cache split into several LRU shards by the hash of the key, so that concurrent
+/// reads of different keys rarely contend for the same lock.
+struct ShardedCache {
+    shards: Vec<Mutex<LruCache>>,
+}
+
+impl ShardedCache {
+    fn new(capacity: usize) -> Self {
+        let count = (capacity / MIN_SHARD_CAPACITY).max(1).min(MAX_SHARDS);
+        let shards = (0..count)
+            .map(|i| {
+                // The remainder of the capacity is distributed among the first shards.
+                let extra = if i < capacity % count { 1 } else { 0 };
+                Mutex::new(LruCache::new(capacity / count + extra))
+            })
+            .collect();
+        Self { shards }
+    }
+
+    fn shard(&self, name: &str, key: &[u8]) -> MutexGuard<LruCache> {
+        let index = if self.shards.len() == 1 {
+            0
+        } else {
+            let mut hasher = DefaultHasher::new();
+            name.hash(&mut hasher);
+            key.hash(&mut hasher);
+            hasher.finish() as usize % self.shards.len()
+        };
+        self.shards[index].lock().unwrap()
+    }
+
+    /// Locks all the shards in the same order, so that concurrent calls cannot deadlock.
+    fn lock_all(&self) -> Vec<MutexGuard<LruCache>> {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().unwrap())
+            .collect()
+    }
+
+    fn stats(&self) -> CacheStats {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().unwrap().stats())
+            .fold(CacheStats::default(), |total, stats| CacheStats {
+                hits: total.hits + stats.hits,
+                misses: total.misses + stats.misses,
+                entries: total.entries + stats.entries,
+                size: total.size + stats.size,
+                capacity: total.capacity + stats.capacity,
+            })
+    }
+}
+
+/// Database wrapper that caches values read from the underlying database.
+///
+/// The values are cached in an LRU cache, the capacity of which is specified as the total
+/// size of the cached keys and values in bytes. Both present and absent keys are cached;
+/// iterators are not cached and are always served by the underlying database.
+///
+/// Cached values are invalidated when a patch changing them is merged into the database.
+/// Snapshots created before the latest merge bypass the cache, so the read isolation
+/// of snapshots is preserved.
+///
+/// Large caches are split into up to 16 shards of at least 1 MiB each, which are selected
+/// by the hash of the key and locked independently. The least recently used entries
+/// are evicted within each shard.
+///
+/// The cache is enabled for the node database if the `cache_size` option is set
+/// in the `database` section of the node configuration.
+///
+/// # Examples
+///
+/// ```
+/// use exonum_merkledb::{CachedDB, Database, MapIndex, MemoryDB};
+///
+/// let db = CachedDB::new(MemoryDB::new(), 1 << 20);
+/// let fork = db.fork();
+/// MapIndex::new("index", &fork).put(&1_u8, 10_u64);
+/// db.merge(fork.into_patch()).unwrap();
+///
+/// for _ in 0..3 {
+///     let snapshot = db.snapshot();
+///     let index: MapIndex<_, u8, u64> = MapIndex::new("index", &snapshot);
+///     assert_eq!(index.get(&1), Some(10));
+/// }
+/// assert!(db.stats().hits >= 2);
+/// ```
+pub struct CachedDB<T> {
+    inner: T,
+    cache: Arc<ShardedCache>,
+}
+
+/// A snapshot of a `CachedDB`.
+struct CachedSnapshot {
+    inner: Box<dyn Snapshot>,
+    cache: Arc<ShardedCache>,
+    epoch: u64,
+}
+
+impl<T: Database> CachedDB<T> {
+    /// Wraps the database into a cache with the specified capacity in bytes.
+    pub fn new(inner: T, capacity: usize) -> Self {
+        Self {
+            inner,
+            cache: Arc::new(ShardedCache::new(capacity)),
+        }
+    }
+
+    /// Returns a reference to the underlying database.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns the current statistics of the cache.
+    pub fn stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    fn merge_with<F>(&self, patch: Patch, merge: F) -> Result<()>
+    where
+        F: FnOnce(&T, Patch) -> Result<()>,
+    {
+        // The cache is locked during the merge, so that no snapshot of the new database state
+        // is created until the changed entries are invalidated.
+        let mut shards = self.cache.lock_all();
+        for shard in &mut shards {
+            shard.invalidate(&patch);
+        }
+        let result = merge(&self.inner, patch);
+        for shard in &mut shards {
+            shard.epoch += 1;
+        }
+        result
+    }
+}
+
+impl<T: Database> Database for CachedDB<T> {
+    fn snapshot(&self) -> Box<dyn Snapshot> {
+        // All the shards have the same epoch, which is only changed while all of them
+        // are locked. The first shard is locked while the inner snapshot is created,
+        // so that the snapshot and the epoch correspond to the same database state.
+        let shard = self.cache.shards[0].lock().unwrap();
+        Box::new(CachedSnapshot {
+            inner: self.inner.snapshot(),
+            cache: Arc::clone(&self.cache),
+            epoch: shard.epoch,
+        })
+    }
+
+    fn merge(&self, patch: Patch) -> Result<()> {
+        self.merge_with(patch, T::merge)
+    }
+
+    fn merge_sync(&self, patch: Patch) -> Result<()> {
+        self.merge_with(patch, T::merge_sync)
+    }
+
+    fn storage_stats(&self) -> Result<DbStats> {
+        self.inner.storage_stats()
+    }
+
+    fn create_checkpoint(&self, path: &Path) -> Result<()> {
+        self.inner.create_checkpoint(path)
+    }
+
+    fn cache_stats(&self) -> Result<CacheStats> {
+        Ok(self.stats())
+    }
+}
+
+impl Snapshot for CachedSnapshot {
+    fn get(&self, name: &str, key: &[u8]) -> Option<Vec<u8>> {
+        if let Some(value) = self.cache.shard(name, key).get(self.epoch, name, key) {
+            return value;
+        }
+
+        // The shard is not locked while the underlying database is read.
+        let value = self.inner.get(name, key);
+        self.cache
+            .shard(name, key)
+            .insert(self.epoch, name, key, value.clone());
+        value
+    }
+
+    fn contains(&self, name: &str, key: &[u8]) -> bool {
+        match self.cache.shard(name, key).get(self.epoch, name, key) {
+            Some(value) => value.is_some(),
+            None => self.inner.contains(name, key),
+        }
+    }
+
+    fn iter(&self, name: &str, from: &[u8]) -> Iter {
+        self.inner.iter(name, from)
+    }
+
+    fn iter_rev(&self, name: &str, to: Bound<&[u8]>) -> Iter {
+        self.inner.iter_rev(name, to)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CachedDB<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CachedDB")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl fmt::Debug for CachedSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CachedSnapshot")
+            .field("epoch", &self.epoch)
+            .finish()
+    }
+}
+
+#[allow(clippy::use_self)]
+impl<T: Database> From<CachedDB<T>> for Arc<dyn Database> {
+    fn from(db: CachedDB<T>) -> Self {
+        Arc::new(db)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CacheStats, CachedDB, MAX_SHARDS, MIN_SHARD_CAPACITY};
+    use crate::{Database, MapIndex, MemoryDB};
+
+    fn fill(db: &CachedDB<MemoryDB>, entries: &[(u8, u64)]) {
+        let fork = db.fork();
+        {
+            let mut index = MapIndex::new("index", &fork);
+            for (key, value) in entries {
+                index.put(key, *value);
+            }
+        }
+        db.merge(fork.into_patch()).unwrap();
+    }
+
+    #[test]
+    fn cache_hits_and_misses() {
+        let db = CachedDB::new(MemoryDB::new(), 1 << 10);
+        fill(&db, &[(1, 10), (2, 20)]);
+
+        let snapshot = db.snapshot();
+        let index: MapIndex<_, u8, u64> = MapIndex::new("index", &snapshot);
+        let stats = db.stats();
+        assert_eq!(index.get(&1), Some(10));
+        assert_eq!(index.get(&1), Some(10));
+        assert_eq!(index.get(&3), None);
+        assert!(!index.contains(&3));
+
+        let new_stats = db.stats();
+        assert_eq!(new_stats.hits - stats.hits, 2);
+        assert_eq!(new_stats.misses - stats.misses, 2);
+
+        // Statistics are available via the `Database` trait.
+        let boxed: Box<dyn Database> = Box::new(db);
+        assert_eq!(boxed.cache_stats().unwrap(), new_stats);
+        assert!(MemoryDB::new().cache_stats().is_err());
+    }
+
+    #[test]
+    fn cache_invalidation_on_merge() {
+        let db = CachedDB::new(MemoryDB::new(), 1 << 10);
+        fill(&db, &[(1, 10), (2, 20)]);
+
+        let old_snapshot = db.snapshot();
+        let old_index: MapIndex<_, u8, u64> = MapIndex::new("index", &old_snapshot);
+        assert_eq!(old_index.get(&1), Some(10));
+        assert_eq!(old_index.get(&3), None);
+
+        fill(&db, &[(1, 11), (3, 30)]);
+        let snapshot = db.snapshot();
+        let index: MapIndex<_, u8, u64> = MapIndex::new("index", &snapshot);
+        assert_eq!(index.get(&1), Some(11));
+        assert_eq!(index.get(&3), Some(30));
+        assert_eq!(index.get(&2), Some(20));
+
+        // Values read by the new snapshot do not leak into the old one and vice versa.
+        assert_eq!(old_index.get(&1), Some(10));
+        assert_eq!(old_index.get(&3), None);
+        assert_eq!(index.get(&1), Some(11));
+
+        // Clearing the index removes all its keys from the cache.
+        let fork = db.fork();
+        MapIndex::<_, u8, u64>::new("index", &fork).clear();
+        db.merge(fork.into_patch()).unwrap();
+        let snapshot = db.snapshot();
+        let index: MapIndex<_, u8, u64> = MapIndex::new("index", &snapshot);
+        assert_eq!(index.get(&1), None);
+        assert_eq!(index.get(&2), None);
+    }
+
+    #[test]
+    fn cache_eviction() {
+        let db = CachedDB::new(MemoryDB::new(), 128);
+        let entries: Vec<_> = (0..32).map(|i| (i, u64::from(i))).collect();
+        fill(&db, &entries);
+
+        let snapshot = db.snapshot();
+        let index: MapIndex<_, u8, u64> = MapIndex::new("index", &snapshot);
+        for (key, value) in &entries {
+            assert_eq!(index.get(key), Some(*value));
+        }
+
+        let CacheStats {
+            entries: cached_entries,
+            size,
+            capacity,
+            ..
+        } = db.stats();
+        assert!(size <= capacity);
+        assert!(cached_entries > 0 && cached_entries < entries.len());
+
+        // The most recently used entry is still cached, while the first one is evicted.
+        let stats = db.stats();
+        assert_eq!(index.get(&31), Some(31));
+        assert_eq!(index.get(&0), Some(0));
+        let new_stats = db.stats();
+        assert_eq!(new_stats.hits - stats.hits, 1);
+        assert_eq!(new_stats.misses - stats.misses, 1);
+    }
+
+    #[test]
+    fn sharded_cache() {
+        let capacity = MIN_SHARD_CAPACITY * 4 + 3;
+        let db = CachedDB::new(MemoryDB::new(), capacity);
+        assert_eq!(db.cache.shards.len(), 4);
+        assert_eq!(db.stats().capacity, capacity);
+        let db = CachedDB::new(MemoryDB::new(), MIN_SHARD_CAPACITY * 100);
+        assert_eq!(db.cache.shards.len(), MAX_SHARDS);
+
+        let entries: Vec<_> = (0..=255).map(|i| (i, u64::from(i))).collect();
+        fill(&db, &entries);
+        let snapshot = db.snapshot();
+        let index: MapIndex<_, u8, u64> = MapIndex::new("index", &snapshot);
+        for (key, value) in &entries {
+            assert_eq!(index.get(key), Some(*value));
+        }
+        let stats = db.stats();
+        for (key, value) in &entries {
+            assert_eq!(index.get(key), Some(*value));
+        }
+        let new_stats = db.stats();
+        assert_eq!(new_stats.hits - stats.hits, entries.len() as u64);
+        assert_eq!(new_stats.misses, stats.misses);
+        assert!(new_stats.entries >= entries.len());
+
+        // Merges invalidate the changed entries in all shards.
+        let new_entries: Vec<_> = entries
+            .iter()
+            .map(|&(key, value)| (key, value + 1))
+            .collect();
+        fill(&db, &new_entries);
+        let snapshot = db.snapshot();
+        let index: MapIndex<_, u8, u64> = MapIndex::new("index", &snapshot);
+        for (key, value) in &new_entries {
+            assert_eq!(index.get(key), Some(*value));
+        }
+    }
+}
@@ -0,0 +1,268 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An implementation of `MemoryDB` database.
+
+use std::{
+    collections::{
+        btree_map::BTreeMap,
+        Bound::{self, Included, Unbounded},
+        HashMap,
+    },
+    fmt,
+    iter::{Iterator as StdIterator, Peekable},
+    sync::{Arc, RwLock},
+};
+
+use crate::{
+    db::{check_database, remove_keys_with_prefix, Change},
+    ColumnFamilyStats, Database, DbStats, Iter, Iterator, Patch, Result, Snapshot,
+};
+
+/// Contents of a single column family.
+type ColumnFamily = BTreeMap<Vec<u8>, Vec<u8>>;
+/// Column families of the database. Each family is shared between the database
+/// and its snapshots, and is copied on write only if it is still referenced by a snapshot.
+type ColumnFamilies = HashMap<String, Arc<ColumnFamily>>;
+
+/// Database implementation that stores all the data in memory.
+///
+/// Each column family is represented as a `BTreeMap`. Snapshots share column families
+/// with the database, so creating a snapshot does not copy any data; a column family
+/// is copied only if it is modified while a snapshot referencing it is alive.
+///
+/// This database is only used for testing and experimenting; the data is lost
+/// when the database is dropped.
+pub struct MemoryDB {
+    families: RwLock<ColumnFamilies>,
+}
+
+/// A snapshot of a `MemoryDB`.
+pub struct MemoryDBSnapshot {
+    families: ColumnFamilies,
+}
+
+/// An iterator over the entries of a `MemoryDB`.
+struct MemoryDBIterator<I: StdIterator> {
+    inner: Option<Peekable<I>>,
+}
+
+impl MemoryDB {
+    /// Creates a new, empty database.
+    pub fn new() -> Self {
+        let mut db = Self {
+            families: RwLock::new(HashMap::new()),
+        };
+        check_database(&mut db).unwrap();
+        db
+    }
+}
+
+impl Database for MemoryDB {
+    fn snapshot(&self) -> Box<dyn Snapshot> {
+        Box::new(MemoryDBSnapshot {
+            families: self.families.read().unwrap().clone(),
+        })
+    }
+
+    fn merge(&self, patch: Patch) -> Result<()> {
+        // The write lock is held for the whole merge, so concurrent readers observe
+        // either none or all of the changes from the patch.
+        let mut families = self.families.write().unwrap();
+        for (cf_name, changes) in patch {
+            let family = Arc::make_mut(
+                families
+                    .entry(cf_name)
+                    .or_insert_with(|| Arc::new(BTreeMap::new())),
+            );
+
+            for prefix in changes.prefixes_to_remove() {
+                remove_keys_with_prefix(family, prefix);
+            }
+
+            for (key, change) in changes {
+                match change {
+                    Change::Put(value) => {
+                        family.insert(key, value);
+                    }
+                    Change::Delete => {
+                        family.remove(&key);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn merge_sync(&self, patch: Patch) -> Result<()> {
+        self.merge(patch)
+    }
+
+    // Statistics of `MemoryDB` are exact. As all the data is stored in memory,
+    // the whole size of a column family is reported as its memtables size.
+    fn storage_stats(&self) -> Result<DbStats> {
+        let families = self.families.read().unwrap();
+        let mut column_families = families
+            .iter()
+            .map(|(name, family)| ColumnFamilyStats {
+                name: name.clone(),
+                memtables_size: family
+                    .iter()
+                    .map(|(key, value)| (key.len() + value.len()) as u64)
+                    .sum(),
+                estimated_keys: family.len() as u64,
+                ..ColumnFamilyStats::default()
+            })
+            .collect::<Vec<_>>();
+        column_families.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(DbStats { column_families })
+    }
+}
+
+impl Snapshot for MemoryDBSnapshot {
+    fn get(&self, name: &str, key: &[u8]) -> Option<Vec<u8>> {
+        self.families
+            .get(name)
+            .and_then(|family| family.get(key).cloned())
+    }
+
+    fn contains(&self, name: &str, key: &[u8]) -> bool {
+        self.families
+            .get(name)
+            .map_or(false, |family| family.contains_key(key))
+    }
+
+    fn iter(&self, name: &str, from: &[u8]) -> Iter {
+        let range = (Included(from), Unbounded);
+        Box::new(MemoryDBIterator {
+            inner: self
+                .families
+                .get(name)
+                .map(|family| family.range::<[u8], _>(range).peekable()),
+        })
+    }
+
+    fn iter_rev(&self, name: &str, to: Bound<&[u8]>) -> Iter {
+        Box::new(MemoryDBIterator {
+            inner: self
+                .families
+                .get(name)
+                .map(|family| family.range::<[u8], _>((Unbounded, to)).rev().peekable()),
+        })
+    }
+}
+
+impl<'a, I> Iterator for MemoryDBIterator<I>
+where
+    I: StdIterator<Item = (&'a Vec<u8>, &'a Vec<u8>)>,
+{
+    fn next(&mut self) -> Option<(&[u8], &[u8])> {
+        self.inner
+            .as_mut()?
+            .next()
+            .map(|(key, value)| (key.as_slice(), value.as_slice()))
+    }
+
+    fn peek(&mut self) -> Option<(&[u8], &[u8])> {
+        self.inner
+            .as_mut()?
+            .peek()
+            .map(|&(key, value)| (key.as_slice(), value.as_slice()))
+    }
+}
+
+impl Default for MemoryDB {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for MemoryDB {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MemoryDB").finish()
+    }
+}
+
+impl fmt::Debug for MemoryDBSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MemoryDBSnapshot").finish()
+    }
+}
+
+#[allow(clippy::use_self)]
+impl From<MemoryDB> for Arc<dyn Database> {
+    fn from(db: MemoryDB) -> Self {
+        Arc::new(db)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MemoryDB;
+    use crate::{Database, DbStats, MapIndex};
+
+    #[test]
+    fn snapshot_isolation() {
+        let db = MemoryDB::new();
+        let fork = db.fork();
+        {
+            let mut index = MapIndex::new("index", &fork);
+            index.put(&1_u8, 10_u64);
+            index.put(&2_u8, 20_u64);
+        }
+        db.merge(fork.into_patch()).unwrap();
+
+        let snapshot = db.snapshot();
+        let fork = db.fork();
+        {
+            let mut index = MapIndex::new("index", &fork);
+            index.put(&1_u8, 11_u64);
+            index.remove(&2_u8);
+            index.put(&3_u8, 30_u64);
+        }
+        db.merge(fork.into_patch()).unwrap();
+
+        let old_index: MapIndex<_, u8, u64> = MapIndex::new("index", &snapshot);
+        assert_eq!(old_index.iter().collect::<Vec<_>>(), vec![(1, 10), (2, 20)]);
+
+        let snapshot = db.snapshot();
+        let new_index: MapIndex<_, u8, u64> = MapIndex::new("index", &snapshot);
+        assert_eq!(new_index.iter().collect::<Vec<_>>(), vec![(1, 11), (3, 30)]);
+    }
+
+    #[test]
+    fn storage_stats() {
+        let db = MemoryDB::new();
+        let fork = db.fork();
+        MapIndex::new("index", &fork).put(&1_u8, 10_u64);
+        db.merge(fork.into_patch()).unwrap();
+        let old_stats = db.storage_stats().unwrap();
+
+        let fork = db.fork();
+        MapIndex::new("index", &fork).put(&2_u8, 20_u64);
+        db.merge(fork.into_patch()).unwrap();
+        let new_stats = db.storage_stats().unwrap();
+
+        let total_keys = |stats: &DbStats| -> u64 {
+            stats
+                .column_families
+                .iter()
+                .map(|cf| cf.estimated_keys)
+                .sum()
+        };
+        assert_eq!(total_keys(&new_stats), total_keys(&old_stats) + 1);
+        assert!(new_stats.total_memtables_size() > old_stats.total_memtables_size());
+        assert_eq!(new_stats.total_sst_files_size(), 0);
+    }
+}
@@ -12,5 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod cached;
+pub mod memorydb;
 pub mod rocksdb;
 pub mod temporarydb;
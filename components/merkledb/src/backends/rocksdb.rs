@@ -18,13 +18,24 @@
 
 pub use rocksdb::{BlockBasedOptions as RocksBlockOptions, WriteOptions as RocksDBWriteOptions};
 
-use std::{fmt, iter::Peekable, mem, path::Path, sync::Arc};
+use std::{
+    collections::Bound::{self, Excluded, Included, Unbounded},
+    fmt,
+    iter::Peekable,
+    mem,
+    path::Path,
+    sync::Arc,
+};
 
-use rocksdb::{self, ColumnFamily, DBIterator, Options as RocksDbOptions, WriteBatch};
+use rocksdb::{
+    self, checkpoint::Checkpoint, ColumnFamily, ColumnFamilyDescriptor, DBCompressionType,
+    DBIterator, Options as RocksDbOptions, WriteBatch,
+};
 
 use crate::{
-    db::{check_database, Change},
-    Database, DbOptions, Iter, Iterator, Patch, Snapshot,
+    db::{check_database, next_prefix, Change},
+    options::{ColumnFamilyOptions, CompressionType},
+    ColumnFamilyStats, Database, DbOptions, DbStats, Iter, Iterator, Patch, Snapshot,
 };
 
 /// Database implementation on top of [`RocksDB`](https://rocksdb.org)
@@ -35,6 +46,7 @@ use crate::{
 /// use different databases.
 pub struct RocksDB {
     db: Arc<rocksdb::DB>,
+    options: DbOptions,
 }
 
 impl From<DbOptions> for RocksDbOptions {
@@ -48,10 +60,61 @@ impl From<&DbOptions> for RocksDbOptions {
         let mut defaults = Self::default();
         defaults.create_if_missing(opts.create_if_missing);
         defaults.set_max_open_files(opts.max_open_files.unwrap_or(-1));
+        if let Some(max_total_wal_size) = opts.max_total_wal_size {
+            defaults.set_max_total_wal_size(max_total_wal_size);
+        }
+        if let Some(ref wal_dir) = opts.wal_dir {
+            defaults.set_wal_dir(wal_dir);
+        }
+        // Tuning parameters set for the whole database apply to the default column family.
+        apply_column_family_options(&mut defaults, &opts.column_family_options("default"));
         defaults
     }
 }
 
+impl From<CompressionType> for DBCompressionType {
+    fn from(compression_type: CompressionType) -> Self {
+        match compression_type {
+            CompressionType::None => DBCompressionType::None,
+            CompressionType::Snappy => DBCompressionType::Snappy,
+            CompressionType::Lz4 => DBCompressionType::Lz4,
+            CompressionType::Lz4hc => DBCompressionType::Lz4hc,
+            CompressionType::Zstd => DBCompressionType::Zstd,
+            CompressionType::Zlib => DBCompressionType::Zlib,
+            CompressionType::Bz2 => DBCompressionType::Bz2,
+        }
+    }
+}
+
+// Applies tuning parameters of a column family to the `RocksDB` options.
+fn apply_column_family_options(rocksdb_options: &mut RocksDbOptions, opts: &ColumnFamilyOptions) {
+    if let Some(compression_type) = opts.compression_type {
+        rocksdb_options.set_compression_type(compression_type.into());
+    }
+    if let Some(write_buffer_size) = opts.write_buffer_size {
+        rocksdb_options.set_write_buffer_size(write_buffer_size);
+    }
+    if opts.block_cache_size.is_some() || opts.bloom_filter_bits.is_some() {
+        let mut block_options = RocksBlockOptions::default();
+        if let Some(block_cache_size) = opts.block_cache_size {
+            // Each column family gets a cache of its own, since the `rocksdb` crate
+            // does not allow to share a cache among column families.
+            block_options.set_lru_cache(block_cache_size);
+        }
+        if let Some(bloom_filter_bits) = opts.bloom_filter_bits {
+            block_options.set_bloom_filter(bloom_filter_bits, false);
+        }
+        rocksdb_options.set_block_based_table_factory(&block_options);
+    }
+}
+
+// Creates `RocksDB` options for the specified column family.
+fn column_family_options(opts: &DbOptions, cf_name: &str) -> RocksDbOptions {
+    let mut rocksdb_options = RocksDbOptions::default();
+    apply_column_family_options(&mut rocksdb_options, &opts.column_family_options(cf_name));
+    rocksdb_options
+}
+
 /// A snapshot of a `RocksDB`.
 pub struct RocksDBSnapshot {
     snapshot: rocksdb::Snapshot<'static>,
@@ -74,19 +137,107 @@ impl RocksDB {
     pub fn open<P: AsRef<Path>>(path: P, options: &DbOptions) -> crate::Result<Self> {
         let inner = {
             if let Ok(names) = rocksdb::DB::list_cf(&RocksDbOptions::default(), &path) {
-                let cf_names = names.iter().map(String::as_str).collect::<Vec<_>>();
-                rocksdb::DB::open_cf(&options.into(), path, cf_names)?
+                let cf_descriptors = names
+                    .iter()
+                    .map(|name| {
+                        ColumnFamilyDescriptor::new(
+                            name.as_str(),
+                            column_family_options(options, name),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                rocksdb::DB::open_cf_descriptors(&options.into(), path, cf_descriptors)?
             } else {
                 rocksdb::DB::open(&options.into(), path)?
             }
         };
         let mut db = Self {
             db: Arc::new(inner),
+            options: options.clone(),
         };
         check_database(&mut db)?;
         Ok(db)
     }
 
+    /// Creates a checkpoint of the database at the specified path.
+    ///
+    /// A checkpoint is a consistent snapshot of the database, which can be opened
+    /// as a separate database. If the checkpoint is located on the same file system
+    /// as the database, its SST files are hard-linked rather than copied, so creating
+    /// a checkpoint is cheap. The database remains fully operational during the process,
+    /// which makes checkpoints suitable for online backups.
+    ///
+    /// The directory at the specified path must not exist; it is created by this method.
+    pub fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> crate::Result<()> {
+        let checkpoint = Checkpoint::new(&*self.db)?;
+        checkpoint.create_checkpoint(path)?;
+        Ok(())
+    }
+
+    /// Returns storage statistics of the column families of the database.
+    ///
+    /// The statistics are taken from the properties maintained by `RocksDB`; most of them
+    /// are estimates, which are cheap to compute and do not require scanning the data.
+    pub fn stats(&self) -> crate::Result<DbStats> {
+        let mut names = rocksdb::DB::list_cf(&RocksDbOptions::default(), self.db.path())?;
+        names.sort();
+        let column_families = names
+            .into_iter()
+            .filter_map(|name| self.db.cf_handle(&name).map(|cf| (name, cf)))
+            .map(|(name, cf)| {
+                Ok(ColumnFamilyStats {
+                    sst_files_size: self.int_property(cf, "rocksdb.total-sst-files-size")?,
+                    memtables_size: self.int_property(cf, "rocksdb.cur-size-all-mem-tables")?,
+                    pending_compaction_bytes: self
+                        .int_property(cf, "rocksdb.estimate-pending-compaction-bytes")?,
+                    estimated_keys: self.int_property(cf, "rocksdb.estimate-num-keys")?,
+                    name,
+                })
+            })
+            .collect::<crate::Result<_>>()?;
+        Ok(DbStats { column_families })
+    }
+
+    /// Compacts the keys in the specified range of a column family, or of all column
+    /// families if `cf_name` is `None`.
+    ///
+    /// The range bounds are inclusive; `None` stands for an unbounded range end.
+    /// Compaction discards deleted and overwritten values and reorganizes the SST files,
+    /// which reclaims disk space and speeds up reads after massive deletions (for example,
+    /// after dropping large indexes). The method blocks until the compaction is finished.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the column family with the specified name does not exist.
+    pub fn compact_range(
+        &self,
+        cf_name: Option<&str>,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+    ) -> crate::Result<()> {
+        let names = match cf_name {
+            Some(name) => vec![name.to_owned()],
+            None => rocksdb::DB::list_cf(&RocksDbOptions::default(), self.db.path())?,
+        };
+        for name in names {
+            let cf = self.db.cf_handle(&name).ok_or_else(|| {
+                crate::Error::new(format!("Column family `{}` does not exist", name))
+            })?;
+            self.db.compact_range_cf(cf, start, end);
+        }
+        Ok(())
+    }
+
+    // Reads an integer property of a column family.
+    fn int_property(&self, cf: ColumnFamily, property: &str) -> crate::Result<u64> {
+        let value = self.db.property_value_cf(cf, property)?.ok_or_else(|| {
+            crate::Error::new(format!("Property `{}` is not supported", property))
+        })?;
+        value.parse().map_err(|e| {
+            crate::Error::new(format!("Invalid value of property `{}`: {}", property, e))
+        })
+    }
+
     fn do_merge(&self, patch: Patch, w_opts: &RocksDBWriteOptions) -> crate::Result<()> {
         let mut batch = WriteBatch::default();
         for (cf_name, changes) in patch {
@@ -94,7 +245,7 @@ impl RocksDB {
                 Some(cf) => cf,
                 None => self
                     .db
-                    .create_cf(&cf_name, &DbOptions::default().into())
+                    .create_cf(&cf_name, &column_family_options(&self.options, &cf_name))
                     .unwrap(),
             };
 
@@ -120,6 +271,16 @@ impl RocksDB {
         cf_name: &str,
         prefix: &[u8],
     ) -> crate::Result<()> {
+        // Removing a range is much cheaper than removing keys one by one,
+        // but the range cannot be expressed for an empty prefix or a prefix
+        // consisting of `0xff` bytes only.
+        if !prefix.is_empty() {
+            if let Some(next_prefix) = next_prefix(prefix) {
+                batch.delete_range_cf(cf, prefix, &next_prefix)?;
+                return Ok(());
+            }
+        }
+
         let snapshot = self.snapshot();
         let mut iterator = snapshot.iter(cf_name, prefix);
         while let Some((key, ..)) = iterator.next() {
@@ -152,6 +313,14 @@ impl Database for RocksDB {
         w_opts.set_sync(true);
         self.do_merge(patch, &w_opts)
     }
+
+    fn storage_stats(&self) -> crate::Result<DbStats> {
+        self.stats()
+    }
+
+    fn create_checkpoint(&self, path: &Path) -> crate::Result<()> {
+        RocksDB::create_checkpoint(self, path)
+    }
 }
 
 impl Snapshot for RocksDBSnapshot {
@@ -181,6 +350,35 @@ impl Snapshot for RocksDBSnapshot {
             value: None,
         })
     }
+
+    fn iter_rev<'a>(&'a self, name: &str, to: Bound<&[u8]>) -> Iter<'a> {
+        use rocksdb::{Direction, IteratorMode};
+        let mode = match to {
+            Included(key) | Excluded(key) => IteratorMode::From(key, Direction::Reverse),
+            Unbounded => IteratorMode::End,
+        };
+        let iter = match self.db.cf_handle(name) {
+            Some(cf) => self.snapshot.iterator_cf(cf, mode).unwrap(),
+            None => self.snapshot.iterator(IteratorMode::End),
+        };
+
+        let mut iter = iter.peekable();
+        // The iterator starts from the greatest key not exceeding the bound,
+        // so the bound itself is skipped if it is excluded.
+        if let Excluded(key) = to {
+            if iter
+                .peek()
+                .map_or(false, |(first, _)| first.as_ref() == key)
+            {
+                iter.next();
+            }
+        }
+        Box::new(RocksDBIterator {
+            iter,
+            key: None,
+            value: None,
+        })
+    }
 }
 
 impl<'a> Iterator for RocksDBIterator<'a> {
@@ -218,3 +416,79 @@ impl fmt::Debug for RocksDBSnapshot {
         f.debug_struct("RocksDBSnapshot").finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::RocksDB;
+    use crate::{Database, DbOptions, ListIndex};
+
+    #[test]
+    fn checkpoint() {
+        let dir = TempDir::new().unwrap();
+        let checkpoint_path = dir.path().join("checkpoint");
+        let db = RocksDB::open(dir.path().join("db"), &DbOptions::default()).unwrap();
+
+        let fork = db.fork();
+        ListIndex::new("list", &fork).extend(vec![1_u32, 2, 3]);
+        db.merge_sync(fork.into_patch()).unwrap();
+        db.create_checkpoint(&checkpoint_path).unwrap();
+
+        // Changes made after the checkpoint creation should not affect the checkpoint.
+        let fork = db.fork();
+        ListIndex::new("list", &fork).push(4_u32);
+        db.merge_sync(fork.into_patch()).unwrap();
+
+        let checkpoint = RocksDB::open(&checkpoint_path, &DbOptions::default()).unwrap();
+        let snapshot = checkpoint.snapshot();
+        let list: ListIndex<_, u32> = ListIndex::new("list", &snapshot);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        // Checkpoint cannot be created in an existing directory.
+        assert!(db.create_checkpoint(&checkpoint_path).is_err());
+
+        // Checkpoints can be created through the `Database` trait.
+        let db: &dyn Database = &db;
+        let other_path = dir.path().join("other_checkpoint");
+        db.create_checkpoint(&other_path).unwrap();
+        let checkpoint = RocksDB::open(&other_path, &DbOptions::default()).unwrap();
+        let snapshot = checkpoint.snapshot();
+        let list: ListIndex<_, u32> = ListIndex::new("list", &snapshot);
+        assert_eq!(list.len(), 4);
+    }
+
+    #[test]
+    fn stats_and_compaction() {
+        let dir = TempDir::new().unwrap();
+        let db = RocksDB::open(dir.path().join("db"), &DbOptions::default()).unwrap();
+
+        let fork = db.fork();
+        ListIndex::new("list", &fork).extend(0_u64..1_000);
+        db.merge_sync(fork.into_patch()).unwrap();
+
+        let stats = db.stats().unwrap();
+        assert!(stats.column_family("default").is_some());
+        assert!(stats.column_family("unknown").is_none());
+        let names: Vec<_> = stats
+            .column_families
+            .iter()
+            .map(|cf| cf.name.as_str())
+            .collect();
+        let mut sorted_names = names.clone();
+        sorted_names.sort();
+        assert_eq!(names, sorted_names);
+        assert!(stats.total_sst_files_size() + stats.total_memtables_size() > 0);
+        assert_eq!(db.storage_stats().unwrap(), stats);
+
+        // Compaction must not change the contents of the database.
+        db.compact_range(None, None, None).unwrap();
+        db.compact_range(Some("default"), Some(&[0]), Some(&[0xff]))
+            .unwrap();
+        assert!(db.compact_range(Some("unknown"), None, None).is_err());
+
+        let snapshot = db.snapshot();
+        let list: ListIndex<_, u64> = ListIndex::new("list", &snapshot);
+        assert_eq!(list.len(), 1_000);
+    }
+}
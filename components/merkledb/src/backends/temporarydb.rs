@@ -17,8 +17,8 @@
 use tempfile::TempDir;
 
 use super::rocksdb::RocksDB;
-use crate::{Database, DbOptions, Patch, Result, Snapshot};
-use std::sync::Arc;
+use crate::{Database, DbOptions, DbStats, Patch, Result, Snapshot};
+use std::{path::Path, sync::Arc};
 
 /// Wrapper over the `RocksDB` backend which stores data in the temporary directory
 /// using the `tempfile` crate.
@@ -53,6 +53,14 @@ impl Database for TemporaryDB {
     fn merge_sync(&self, patch: Patch) -> Result<()> {
         self.inner.merge_sync(patch)
     }
+
+    fn storage_stats(&self) -> Result<DbStats> {
+        self.inner.storage_stats()
+    }
+
+    fn create_checkpoint(&self, path: &Path) -> Result<()> {
+        self.inner.create_checkpoint(path)
+    }
 }
 
 impl Default for TemporaryDB {
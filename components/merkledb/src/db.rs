@@ -13,25 +13,41 @@
 // limitations under the License.
 
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
     cmp::Ordering::{Equal, Greater, Less},
     collections::{
         btree_map::{BTreeMap, IntoIter as BtmIntoIter, Iter as BtmIter},
         hash_map::{IntoIter as HmIntoIter, Iter as HmIter},
-        Bound::{Included, Unbounded},
+        Bound::{self, Included, Unbounded},
         HashMap,
     },
     fmt,
-    iter::{FromIterator, Iterator as StdIterator, Peekable},
+    io::{self, Read, Write},
+    iter::{Iterator as StdIterator, Peekable},
     mem,
     ops::{Deref, DerefMut},
+    path::Path,
 };
 
+use exonum_crypto::{Hash, HashStream, HASH_SIZE};
+use leb128;
+
 use crate::{
+    read_set::{ReadSet, TrackedIter},
     views::{IndexAccess, IndexAddress, View},
-    Error, Result,
+    CacheStats, DbStats, Error, Result,
 };
 
+/// Magic bytes at the start of a serialized patch.
+const PATCH_MAGIC: &[u8; 4] = b"EXPT";
+/// The current version of the binary layout of patches.
+pub const PATCH_FORMAT_VERSION: u8 = 1;
+
+/// Tag of `Change::Put` in a serialized patch.
+const PUT_CHANGE: u8 = 0;
+/// Tag of `Change::Delete` in a serialized patch.
+const DELETE_CHANGE: u8 = 1;
+
 /// Finds a prefix immediately following the supplied one.
 pub fn next_prefix(prefix: &[u8]) -> Option<Vec<u8>> {
     let change_idx = prefix.iter().rposition(|&byte| byte < u8::max_value());
@@ -64,7 +80,7 @@ pub fn remove_keys_with_prefix<V>(table: &mut BTreeMap<Vec<u8>, V>, prefix: &[u8
 /// Map containing changes with a corresponding key.
 #[derive(Debug, Clone)]
 pub struct Changes {
-    data: BTreeMap<Vec<u8>, Change>,
+    pub(super) data: BTreeMap<Vec<u8>, Change>,
     prefixes_to_remove: Vec<Vec<u8>>,
 }
 
@@ -151,6 +167,7 @@ pub struct Patch {
 #[derive(Debug, Default)]
 pub struct WorkingPatch {
     changes: RefCell<HashMap<IndexAddress, Option<ViewChanges>>>,
+    savepoints: RefCell<Vec<(u64, HashMap<IndexAddress, ViewChanges>)>>,
 }
 
 /// `RefMut`, but dumber.
@@ -196,6 +213,7 @@ impl WorkingPatch {
     fn new() -> Self {
         Self {
             changes: RefCell::new(HashMap::new()),
+            savepoints: RefCell::new(Vec::new()),
         }
     }
 
@@ -236,6 +254,56 @@ impl WorkingPatch {
         change.and_modify(|v| *v = None);
     }
 
+    /// Saves a copy of the current changes as a savepoint with the specified identifier.
+    fn push_savepoint(&self, id: u64) {
+        let changes = self
+            .changes
+            .borrow()
+            .iter()
+            .map(|(address, changes)| {
+                let changes = changes.clone().unwrap_or_else(|| {
+                    panic!("changes are still borrowed at address {:?}", address);
+                });
+                (address.clone(), changes)
+            })
+            .collect();
+        self.savepoints.borrow_mut().push((id, changes));
+    }
+
+    /// Returns the position of the savepoint with the specified identifier in the stack.
+    fn savepoint_position(&self, id: u64) -> usize {
+        self.savepoints
+            .borrow()
+            .iter()
+            .position(|(savepoint_id, _)| *savepoint_id == id)
+            .unwrap_or_else(|| panic!("savepoint has already been released or rolled back"))
+    }
+
+    /// Restores the changes saved in the savepoint with the specified identifier
+    /// and removes it from the stack together with all the subsequent savepoints.
+    fn rollback_to(&self, id: u64) {
+        let position = self.savepoint_position(id);
+        let mut changes = self.changes.borrow_mut();
+        if let Some((address, _)) = changes.iter().find(|(_, changes)| changes.is_none()) {
+            panic!("changes are still borrowed at address {:?}", address);
+        }
+
+        let mut savepoints = self.savepoints.borrow_mut();
+        savepoints.truncate(position + 1);
+        let (_, saved_changes) = savepoints.pop().unwrap();
+        *changes = saved_changes
+            .into_iter()
+            .map(|(address, changes)| (address, Some(changes)))
+            .collect();
+    }
+
+    /// Removes the savepoint with the specified identifier from the stack together with
+    /// all the subsequent savepoints.
+    fn release(&self, id: u64) {
+        let position = self.savepoint_position(id);
+        self.savepoints.borrow_mut().truncate(position);
+    }
+
     // TODO: verify that this method updates `Change`s already in the `Patch` [ECR-2834]
     fn merge_into(self, patch: &mut Patch) {
         for (address, changes) in self.changes.into_inner() {
@@ -297,16 +365,31 @@ impl Patch {
 
     /// Produces a patch that would reverse the effects of this patch for a given
     /// snapshot.
+    ///
+    /// The snapshot should correspond to the database state before the patch is merged.
+    /// The produced patch never contains prefixes to remove; keys removed by the prefix
+    /// are restored one by one.
     pub fn undo(self, snapshot: &dyn Snapshot) -> Self {
         let mut rev_patch = Self::new();
 
         for (name, changes) in self {
-            let rev_changes = BTreeMap::from_iter(changes.into_iter().map(|(key, ..)| {
-                match snapshot.get(&name, &key) {
+            let mut rev_changes = BTreeMap::new();
+            for prefix in changes.prefixes_to_remove() {
+                let mut iter = snapshot.iter(&name, prefix);
+                while let Some((key, value)) = iter.next() {
+                    if !key.starts_with(prefix) {
+                        break;
+                    }
+                    rev_changes.insert(key.to_vec(), Change::Put(value.to_vec()));
+                }
+            }
+
+            rev_changes.extend(changes.into_iter().map(
+                |(key, ..)| match snapshot.get(&name, &key) {
                     Some(value) => (key, Change::Put(value)),
                     None => (key, Change::Delete),
-                }
-            }));
+                },
+            ));
 
             rev_patch.changes.insert(
                 name,
@@ -319,6 +402,155 @@ impl Patch {
 
         rev_patch
     }
+
+    /// Inserts a change for the specified key into the patch, replacing the previous
+    /// change for this key, if any.
+    pub fn insert(&mut self, name: &str, key: Vec<u8>, change: Change) {
+        if !self.changes.contains_key(name) {
+            self.changes.insert(name.to_owned(), Changes::new());
+        }
+        self.changes.get_mut(name).unwrap().data.insert(key, change);
+    }
+
+    /// Returns a read-only snapshot representing the state of the `base` snapshot
+    /// with this patch applied to it. The database itself is not modified.
+    ///
+    /// This method can be used, for example, to reconstruct a past state of the database
+    /// from its current snapshot and a patch produced by [`undo`].
+    ///
+    /// [`undo`]: #method.undo
+    pub fn overlay(mut self, base: Box<dyn Snapshot>) -> Box<dyn Snapshot> {
+        // Prefixes to remove are not taken into account by `FlushedFork`, so we replace
+        // them with explicit deletions of the affected keys.
+        for (name, changes) in &mut self.changes {
+            for prefix in mem::replace(&mut changes.prefixes_to_remove, vec![]) {
+                let mut iter = base.iter(name, &prefix);
+                while let Some((key, ..)) = iter.next() {
+                    if !key.starts_with(&prefix) {
+                        break;
+                    }
+                    changes.data.entry(key.to_vec()).or_insert(Change::Delete);
+                }
+            }
+        }
+
+        Box::new(FlushedFork::new(base, self))
+    }
+
+    /// Writes the patch to the `writer` in the versioned binary format.
+    ///
+    /// The serialized patch starts with the magic bytes `EXPT` and the format version
+    /// (see [`PATCH_FORMAT_VERSION`]), followed by the column families sorted by name.
+    /// Each family contains its name, the prefixes to remove and the changes sorted by key;
+    /// lengths and counts are encoded with the `leb128` algorithm. The patch ends with
+    /// the SHA-256 checksum of all the preceding bytes.
+    ///
+    /// The output is deterministic, i.e., equal patches always produce the same bytes.
+    /// Several patches may be written to the same stream one after another.
+    ///
+    /// [`PATCH_FORMAT_VERSION`]: constant.PATCH_FORMAT_VERSION.html
+    pub fn write_to<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut writer = Checksummed::new(writer);
+        writer.write_all(PATCH_MAGIC)?;
+        writer.write_all(&[PATCH_FORMAT_VERSION])?;
+
+        let mut families = self.changes.iter().collect::<Vec<_>>();
+        families.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        write_len(&mut writer, families.len())?;
+        for (name, changes) in families {
+            write_bytes(&mut writer, name.as_bytes())?;
+            write_len(&mut writer, changes.prefixes_to_remove.len())?;
+            for prefix in &changes.prefixes_to_remove {
+                write_bytes(&mut writer, prefix)?;
+            }
+            write_len(&mut writer, changes.data.len())?;
+            for (key, change) in &changes.data {
+                write_bytes(&mut writer, key)?;
+                match change {
+                    Change::Put(value) => {
+                        writer.write_all(&[PUT_CHANGE])?;
+                        write_bytes(&mut writer, value)?;
+                    }
+                    Change::Delete => writer.write_all(&[DELETE_CHANGE])?,
+                }
+            }
+        }
+
+        let (mut writer, checksum) = writer.finish();
+        writer.write_all(checksum.as_ref())
+    }
+
+    /// Reads a patch written by [`write_to`] from the `reader`.
+    ///
+    /// The reader is consumed exactly up to the end of the patch, so the next patch
+    /// written to the same stream can be read right after this one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream cannot be read, has an unsupported format version,
+    /// is malformed or its checksum does not match the content.
+    ///
+    /// [`write_to`]: #method.write_to
+    pub fn read_from<R: Read>(reader: R) -> std::result::Result<Self, failure::Error> {
+        let mut reader = Checksummed::new(reader);
+        let mut header = [0_u8; 5];
+        reader.read_exact(&mut header)?;
+        ensure!(
+            header[..4] == PATCH_MAGIC[..],
+            "Invalid magic bytes of the serialized patch"
+        );
+        ensure!(
+            header[4] == PATCH_FORMAT_VERSION,
+            "Unsupported patch format version: {}",
+            header[4]
+        );
+
+        let mut patch = Self::new();
+        let families_count = read_len(&mut reader)?;
+        for _ in 0..families_count {
+            let name = String::from_utf8(read_bytes(&mut reader)?)?;
+            let mut changes = Changes::new();
+            let prefixes_count = read_len(&mut reader)?;
+            for _ in 0..prefixes_count {
+                changes.prefixes_to_remove.push(read_bytes(&mut reader)?);
+            }
+            let changes_count = read_len(&mut reader)?;
+            for _ in 0..changes_count {
+                let key = read_bytes(&mut reader)?;
+                let change = match read_u8(&mut reader)? {
+                    PUT_CHANGE => Change::Put(read_bytes(&mut reader)?),
+                    DELETE_CHANGE => Change::Delete,
+                    tag => bail!("Invalid change tag in the serialized patch: {}", tag),
+                };
+                ensure!(
+                    changes.data.insert(key, change).is_none(),
+                    "Duplicate key in the column family `{}` of the serialized patch",
+                    name
+                );
+            }
+            ensure!(
+                !patch.changes.contains_key(&name),
+                "Duplicate column family `{}` in the serialized patch",
+                name
+            );
+            patch.changes.insert(name, changes);
+        }
+
+        let (mut reader, expected_checksum) = reader.finish();
+        let mut checksum = [0_u8; HASH_SIZE];
+        reader.read_exact(&mut checksum)?;
+        ensure!(
+            checksum[..] == expected_checksum.as_ref()[..],
+            "Checksum mismatch in the serialized patch"
+        );
+        Ok(patch)
+    }
+}
+
+impl Default for Patch {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Iterator over the `Patch` data.
@@ -346,6 +578,82 @@ impl IntoIterator for Patch {
     }
 }
 
+/// Stream adapter computing the checksum of the data passing through it.
+#[derive(Debug)]
+struct Checksummed<T> {
+    inner: T,
+    hasher: HashStream,
+}
+
+impl<T> Checksummed<T> {
+    fn new(inner: T) -> Self {
+        Self {
+            inner,
+            hasher: HashStream::new(),
+        }
+    }
+
+    fn update(&mut self, chunk: &[u8]) {
+        let hasher = mem::replace(&mut self.hasher, HashStream::new());
+        self.hasher = hasher.update(chunk);
+    }
+
+    /// Returns the underlying stream and the checksum of the data passed through the adapter.
+    fn finish(self) -> (T, Hash) {
+        (self.inner, self.hasher.hash())
+    }
+}
+
+impl<W: Write> Write for Checksummed<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<R: Read> Read for Checksummed<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+fn write_len<W: Write>(writer: &mut W, len: usize) -> io::Result<()> {
+    leb128::write::unsigned(writer, len as u64).map(drop)
+}
+
+fn write_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> io::Result<()> {
+    write_len(writer, bytes.len())?;
+    writer.write_all(bytes)
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> std::result::Result<u8, failure::Error> {
+    let mut byte = [0_u8];
+    reader.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+fn read_len<R: Read>(reader: &mut R) -> std::result::Result<usize, failure::Error> {
+    let len = leb128::read::unsigned(reader)?;
+    Ok(len as usize)
+}
+
+/// Reads a length-prefixed byte string. The buffer grows as the data is actually read,
+/// so a malformed length does not lead to a huge allocation.
+fn read_bytes<R: Read>(reader: &mut R) -> std::result::Result<Vec<u8>, failure::Error> {
+    let len = read_len(reader)?;
+    let mut bytes = Vec::new();
+    reader.by_ref().take(len as u64).read_to_end(&mut bytes)?;
+    ensure!(bytes.len() == len, "Unexpected end of the serialized patch");
+    Ok(bytes)
+}
+
 /// A generalized iterator over the storage views.
 pub type Iter<'a> = Box<dyn Iterator + 'a>;
 
@@ -375,6 +683,11 @@ pub enum Change {
 /// [`rollback`] methods), which allows rolling back some of the latest changes (e.g., after
 /// a runtime error). Checkpoint is created automatically after calling the `flush` method.
 ///
+/// Within the changes made after the latest checkpoint, [`savepoint`]s allow to undo only
+/// a part of the work. Savepoints form a stack: rolling back to a savepoint with
+/// [`rollback_to`] or releasing it with [`release`] also discards all savepoints
+/// created after it.
+///
 /// `Fork` implements the [`Snapshot`] trait and provides methods for both reading and
 /// writing data. Thus, `&mut Fork` is used as a storage view for creating
 /// read-write indices representation.
@@ -391,21 +704,42 @@ pub enum Change {
 /// [`merge`]: trait.Database.html#tymethod.merge
 /// [`commit`]: #method.commit
 /// [`rollback`]: #method.rollback
+/// [`savepoint`]: #method.savepoint
+/// [`rollback_to`]: #method.rollback_to
+/// [`release`]: #method.release
 #[derive(Debug)]
 pub struct Fork {
     flushed: FlushedFork,
     working_patch: WorkingPatch,
+    next_savepoint_id: Cell<u64>,
+}
+
+/// A savepoint within the unflushed changes of a [`Fork`].
+///
+/// A savepoint is created by [`Fork::savepoint`] and consumed either by
+/// [`Fork::rollback_to`] or by [`Fork::release`].
+///
+/// [`Fork`]: struct.Fork.html
+/// [`Fork::savepoint`]: struct.Fork.html#method.savepoint
+/// [`Fork::rollback_to`]: struct.Fork.html#method.rollback_to
+/// [`Fork::release`]: struct.Fork.html#method.release
+#[derive(Debug, PartialEq, Eq)]
+#[must_use = "savepoint should be either rolled back to or released"]
+pub struct Savepoint {
+    id: u64,
 }
 
 #[derive(Debug)]
 pub struct FlushedFork {
     snapshot: Box<dyn Snapshot>,
     patch: Patch,
+    reads: RefCell<Option<ReadSet>>,
 }
 
 pub(super) struct ForkIter<'a, T: StdIterator> {
     snapshot: Iter<'a>,
     changes: Option<Peekable<T>>,
+    reversed: bool,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -458,11 +792,9 @@ pub trait Database: Send + Sync + 'static {
     /// Creates a new fork of the database from its current state.
     fn fork(&self) -> Fork {
         Fork {
-            flushed: FlushedFork {
-                snapshot: self.snapshot(),
-                patch: Patch::new(),
-            },
+            flushed: FlushedFork::new(self.snapshot(), Patch::new()),
             working_patch: WorkingPatch::new(),
+            next_savepoint_id: Cell::new(0),
         }
     }
 
@@ -489,6 +821,46 @@ pub trait Database: Send + Sync + 'static {
     /// will be returned. In case of an error, the method guarantees no changes are applied to
     /// the database.
     fn merge_sync(&self, patch: Patch) -> Result<()>;
+
+    /// Returns storage statistics of the database, such as sizes of the column families
+    /// and the amount of pending compaction work.
+    ///
+    /// The default implementation returns an error, meaning that the database does not
+    /// collect statistics. [`RocksDB`] provides the statistics of the underlying engine
+    /// (see [`RocksDB::stats`]), and [`MemoryDB`] reports exact sizes of its column families.
+    ///
+    /// [`RocksDB`]: struct.RocksDB.html
+    /// [`RocksDB::stats`]: struct.RocksDB.html#method.stats
+    /// [`MemoryDB`]: struct.MemoryDB.html
+    fn storage_stats(&self) -> Result<DbStats> {
+        Err(Error::new(
+            "Storage statistics are not supported by the database",
+        ))
+    }
+
+    /// Creates a consistent copy of the database in the directory at the specified path,
+    /// which must not exist. Unlike copying the database files, the method can be used
+    /// while the database is being modified.
+    ///
+    /// The default implementation returns an error, meaning that the database cannot be
+    /// copied. [`RocksDB`] creates a checkpoint (see [`RocksDB::create_checkpoint`]).
+    ///
+    /// [`RocksDB`]: struct.RocksDB.html
+    /// [`RocksDB::create_checkpoint`]: struct.RocksDB.html#method.create_checkpoint
+    fn create_checkpoint(&self, _path: &Path) -> Result<()> {
+        Err(Error::new("Checkpoints are not supported by the database"))
+    }
+
+    /// Returns statistics of the in-memory cache of values read from the database.
+    ///
+    /// The default implementation returns an error, meaning that the values are not cached.
+    /// [`CachedDB`] reports the statistics of its cache (see [`CachedDB::stats`]).
+    ///
+    /// [`CachedDB`]: struct.CachedDB.html
+    /// [`CachedDB::stats`]: struct.CachedDB.html#method.stats
+    fn cache_stats(&self) -> Result<CacheStats> {
+        Err(Error::new("Values are not cached by the database"))
+    }
 }
 
 /// A read-only snapshot of a storage backend.
@@ -514,6 +886,10 @@ pub trait Snapshot: 'static {
     /// Returns an iterator over the entries of the snapshot in ascending order starting from
     /// the specified key. The iterator element type is `(&[u8], &[u8])`.
     fn iter(&self, name: &str, from: &[u8]) -> Iter;
+
+    /// Returns an iterator over the entries of the snapshot in descending order starting from
+    /// the specified upper bound. The iterator element type is `(&[u8], &[u8])`.
+    fn iter_rev(&self, name: &str, to: Bound<&[u8]>) -> Iter;
 }
 
 /// A trait that defines a streaming iterator over storage view entries. Unlike
@@ -527,8 +903,39 @@ pub trait Iterator {
     fn peek(&mut self) -> Option<(&[u8], &[u8])>;
 }
 
+impl FlushedFork {
+    fn new(snapshot: Box<dyn Snapshot>, patch: Patch) -> Self {
+        Self {
+            snapshot,
+            patch,
+            reads: RefCell::new(None),
+        }
+    }
+
+    fn track_key(&self, name: &str, key: &[u8]) {
+        if let Some(reads) = self.reads.borrow_mut().as_mut() {
+            reads.add_key(name, key);
+        }
+    }
+
+    fn track_iter<'a>(
+        &'a self,
+        iter: Iter<'a>,
+        name: &str,
+        bound: Bound<&[u8]>,
+        reversed: bool,
+    ) -> Iter<'a> {
+        if self.reads.borrow().is_some() {
+            Box::new(TrackedIter::new(iter, &self.reads, name, bound, reversed))
+        } else {
+            iter
+        }
+    }
+}
+
 impl Snapshot for FlushedFork {
     fn get(&self, name: &str, key: &[u8]) -> Option<Vec<u8>> {
+        self.track_key(name, key);
         if let Some(changes) = self.patch.changes.get(name) {
             if let Some(change) = changes.data.get(key) {
                 match *change {
@@ -541,6 +948,7 @@ impl Snapshot for FlushedFork {
     }
 
     fn contains(&self, name: &str, key: &[u8]) -> bool {
+        self.track_key(name, key);
         if let Some(changes) = self.patch.changes.get(name) {
             if let Some(change) = changes.data.get(key) {
                 match *change {
@@ -555,14 +963,22 @@ impl Snapshot for FlushedFork {
     fn iter(&self, name: &str, from: &[u8]) -> Iter {
         let range = (Included(from), Unbounded);
         let changes = match self.patch.changes.get(name) {
-            Some(changes) => Some(changes.data.range::<[u8], _>(range).peekable()),
+            Some(changes) => Some(changes.data.range::<[u8], _>(range)),
             None => None,
         };
 
-        Box::new(ForkIter {
-            snapshot: self.snapshot.iter(name, from),
-            changes,
-        })
+        let iter = Box::new(ForkIter::new(self.snapshot.iter(name, from), changes));
+        self.track_iter(iter, name, Included(from), false)
+    }
+
+    fn iter_rev(&self, name: &str, to: Bound<&[u8]>) -> Iter {
+        let changes = match self.patch.changes.get(name) {
+            Some(changes) => Some(changes.data.range::<[u8], _>((Unbounded, to)).rev()),
+            None => None,
+        };
+
+        let iter = Box::new(ForkIter::new_rev(self.snapshot.iter_rev(name, to), changes));
+        self.track_iter(iter, name, to, true)
     }
 }
 
@@ -581,6 +997,62 @@ impl Fork {
         self.working_patch = WorkingPatch::new();
     }
 
+    /// Creates a savepoint capturing the changes made after the latest execution of
+    /// the `flush` method. The changes made after the savepoint can be discarded with
+    /// [`rollback_to`](#method.rollback_to).
+    ///
+    /// Savepoints are discarded by `flush` and `rollback`. Each savepoint holds a copy
+    /// of the unflushed changes, so savepoints should be released once they are no longer
+    /// needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index created from the fork is still alive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{Database, ListIndex, TemporaryDB};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// ListIndex::new("list", &fork).push(1_u64);
+    ///
+    /// let savepoint = fork.savepoint();
+    /// ListIndex::new("list", &fork).push(2_u64);
+    /// fork.rollback_to(savepoint);
+    ///
+    /// let list = ListIndex::<_, u64>::new("list", &fork);
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec![1]);
+    /// ```
+    pub fn savepoint(&self) -> Savepoint {
+        let id = self.next_savepoint_id.get();
+        self.working_patch.push_savepoint(id);
+        self.next_savepoint_id.set(id + 1);
+        Savepoint { id }
+    }
+
+    /// Discards the changes made after the specified savepoint. The savepoint and all
+    /// the savepoints created after it are removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the savepoint has already been removed, or if an index created from
+    /// the fork is still alive.
+    pub fn rollback_to(&self, savepoint: Savepoint) {
+        self.working_patch.rollback_to(savepoint.id);
+    }
+
+    /// Removes the specified savepoint and all the savepoints created after it, keeping
+    /// the changes made after them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the savepoint has already been removed.
+    pub fn release(&self, savepoint: Savepoint) {
+        self.working_patch.release(savepoint.id);
+    }
+
     /// Converts the fork into `Patch` consuming the fork instance.
     pub fn into_patch(mut self) -> Patch {
         self.flush();
@@ -619,6 +1091,45 @@ impl Fork {
     pub fn working_patch(&self) -> &WorkingPatch {
         &self.working_patch
     }
+
+    /// Patch containing the changes flushed to this fork.
+    pub fn flushed_patch(&self) -> &Patch {
+        &self.flushed.patch
+    }
+
+    /// Starts tracking the data read through the fork, discarding the previously
+    /// tracked reads, if any.
+    ///
+    /// Reads served from the unflushed changes of the fork are not tracked, since they
+    /// do not depend on the state of the database. See [`ReadSet`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{Database, MapIndex, TemporaryDB};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// fork.start_read_tracking();
+    /// let map: MapIndex<_, u64, u64> = MapIndex::new("map", &fork);
+    /// assert_eq!(map.get(&1), None);
+    /// let reads = fork.finish_read_tracking();
+    ///
+    /// let other_fork = db.fork();
+    /// MapIndex::new("map", &other_fork).put(&1_u64, 10_u64);
+    /// assert!(reads.is_affected_by(&other_fork.into_patch()));
+    /// ```
+    ///
+    /// [`ReadSet`]: struct.ReadSet.html
+    pub fn start_read_tracking(&self) {
+        *self.flushed.reads.borrow_mut() = Some(ReadSet::new());
+    }
+
+    /// Stops tracking the data read through the fork and returns the tracked reads.
+    /// Returns an empty read set if the tracking was not started.
+    pub fn finish_read_tracking(&self) -> ReadSet {
+        self.flushed.reads.borrow_mut().take().unwrap_or_default()
+    }
 }
 
 impl<'a> IndexAccess for &'a Fork {
@@ -653,20 +1164,40 @@ where
         ForkIter {
             snapshot,
             changes: changes.map(StdIterator::peekable),
+            reversed: false,
+        }
+    }
+
+    /// Creates an iterator over entries in descending order. Both `snapshot` and `changes`
+    /// must yield entries in descending order as well.
+    pub fn new_rev(snapshot: Iter<'a>, changes: Option<T>) -> Self {
+        ForkIter {
+            reversed: true,
+            ..Self::new(snapshot, changes)
         }
     }
 
     fn step(&mut self) -> NextIterValue {
+        let reversed = self.reversed;
+        // Compares keys in the order of iteration.
+        let cmp = |a: &[u8], b: &[u8]| {
+            if reversed {
+                b.cmp(a)
+            } else {
+                a.cmp(b)
+            }
+        };
+
         if let Some(ref mut changes) = self.changes {
             match changes.peek() {
                 Some(&(k, change)) => match self.snapshot.peek() {
                     Some((key, ..)) => match *change {
-                        Change::Put(..) => match k[..].cmp(key) {
+                        Change::Put(..) => match cmp(&k[..], key) {
                             Equal => NextIterValue::Replaced,
                             Less => NextIterValue::Inserted,
                             Greater => NextIterValue::Stored,
                         },
-                        Change::Delete => match k[..].cmp(key) {
+                        Change::Delete => match cmp(&k[..], key) {
                             Equal => NextIterValue::Deleted,
                             Less => NextIterValue::MissDeleted,
                             Greater => NextIterValue::Stored,
@@ -0,0 +1,129 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Building blocks of the compact binary encoding of proofs.
+//!
+//! An encoded proof starts with a byte specifying the version of the layout.
+//! Lengths of variable-sized items and numbers of items in sequences are encoded
+//! with the `leb128` algorithm; hashes are written as is.
+
+use leb128;
+
+use exonum_crypto::{Hash, HASH_SIZE};
+
+use crate::BinaryKey;
+
+/// The current version of the binary layout of proofs.
+pub(crate) const PROOF_FORMAT_VERSION: u8 = 1;
+
+/// Writes the version of the binary layout.
+pub(crate) fn write_version(buffer: &mut Vec<u8>) {
+    buffer.push(PROOF_FORMAT_VERSION);
+}
+
+/// Reads the version of the binary layout and checks that it is supported.
+pub(crate) fn read_version(reader: &mut &[u8]) -> Result<(), failure::Error> {
+    let version = read_u8(reader)?;
+    ensure!(
+        version == PROOF_FORMAT_VERSION,
+        "Unsupported proof format version: {}",
+        version
+    );
+    Ok(())
+}
+
+pub(crate) fn write_u8(buffer: &mut Vec<u8>, value: u8) {
+    buffer.push(value);
+}
+
+pub(crate) fn read_u8(reader: &mut &[u8]) -> Result<u8, failure::Error> {
+    let (&value, rest) = reader
+        .split_first()
+        .ok_or_else(|| format_err!("Unexpected end of the encoded proof"))?;
+    *reader = rest;
+    Ok(value)
+}
+
+pub(crate) fn write_u64(buffer: &mut Vec<u8>, value: u64) {
+    leb128::write::unsigned(buffer, value).expect("Writing to a vector never fails");
+}
+
+pub(crate) fn read_u64(reader: &mut &[u8]) -> Result<u64, failure::Error> {
+    leb128::read::unsigned(reader).map_err(From::from)
+}
+
+/// Writes the length of a sequence or a byte string.
+pub(crate) fn write_len(buffer: &mut Vec<u8>, len: usize) {
+    write_u64(buffer, len as u64);
+}
+
+/// Reads the length of a sequence or a byte string. As each item takes at least one byte,
+/// the length cannot exceed the number of remaining bytes; this protects from allocating
+/// huge buffers while decoding malformed data.
+pub(crate) fn read_len(reader: &mut &[u8]) -> Result<usize, failure::Error> {
+    let len = read_u64(reader)?;
+    ensure!(
+        len <= reader.len() as u64,
+        "Length {} exceeds the size of the encoded proof",
+        len
+    );
+    Ok(len as usize)
+}
+
+/// Writes a length-prefixed byte string.
+pub(crate) fn write_bytes(buffer: &mut Vec<u8>, bytes: &[u8]) {
+    write_len(buffer, bytes.len());
+    buffer.extend_from_slice(bytes);
+}
+
+/// Reads a length-prefixed byte string.
+pub(crate) fn read_bytes<'a>(reader: &mut &'a [u8]) -> Result<&'a [u8], failure::Error> {
+    let len = read_len(reader)?;
+    let (bytes, rest) = reader.split_at(len);
+    *reader = rest;
+    Ok(bytes)
+}
+
+/// Reads a length-prefixed key. The key bytes are validated with `BinaryKey::try_read`.
+pub(crate) fn read_key<K>(reader: &mut &[u8]) -> Result<K, failure::Error>
+where
+    K: BinaryKey + ToOwned<Owned = K>,
+{
+    let bytes = read_bytes(reader)?;
+    K::try_read(bytes).map_err(|e| format_err!("Invalid key in the encoded proof: {}", e))
+}
+
+pub(crate) fn write_hash(buffer: &mut Vec<u8>, hash: &Hash) {
+    buffer.extend_from_slice(hash.as_ref());
+}
+
+pub(crate) fn read_hash(reader: &mut &[u8]) -> Result<Hash, failure::Error> {
+    ensure!(
+        reader.len() >= HASH_SIZE,
+        "Unexpected end of the encoded proof"
+    );
+    let (bytes, rest) = reader.split_at(HASH_SIZE);
+    *reader = rest;
+    Ok(Hash::from_slice(bytes).expect("Slice has the size of a hash"))
+}
+
+/// Checks that the encoded proof has been read completely.
+pub(crate) fn read_end(reader: &[u8]) -> Result<(), failure::Error> {
+    ensure!(
+        reader.is_empty(),
+        "Unexpected {} trailing bytes in the encoded proof",
+        reader.len()
+    );
+    Ok(())
+}
@@ -35,6 +35,8 @@ pub enum HashTag {
     MapNode = 3,
     /// Hash prefix of the map branch node object.
     MapBranchNode = 4,
+    /// Hash prefix of the Merkle mountain range object.
+    MountainRangeNode = 5,
 }
 
 /// Calculate hash value with the specified prefix.
@@ -111,6 +113,22 @@ impl HashTag {
         Self::hash_list_node(hashes.len() as u64, root_hash(hashes))
     }
 
+    /// Hash of the Merkle mountain range object.
+    ///
+    /// ```text
+    /// h = sha-256( HashTag::MountainRangeNode || len as u64 || peaks_root )
+    /// ```
+    pub fn hash_mountain_range_node(len: u64, peaks_root: Hash) -> Hash {
+        let mut len_bytes = [0; 8];
+        LittleEndian::write_u64(&mut len_bytes, len);
+
+        HashStream::new()
+            .update(&[HashTag::MountainRangeNode as u8])
+            .update(&len_bytes)
+            .update(peaks_root.as_ref())
+            .hash()
+    }
+
     /// Hash of the map object.
     ///
     /// ```text
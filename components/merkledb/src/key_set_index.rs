@@ -18,7 +18,7 @@
 //! The given section contains information on the methods related to `KeySetIndex`
 //! and the iterator over the items of this set.
 
-use std::{borrow::Borrow, marker::PhantomData};
+use std::{borrow::Borrow, marker::PhantomData, ops::RangeBounds};
 
 use crate::views::IndexAddress;
 use crate::{
@@ -41,11 +41,14 @@ pub struct KeySetIndex<T: IndexAccess, K> {
 
 /// Returns an iterator over the items of a `KeySetIndex`.
 ///
-/// This struct is created by the [`iter`] or
-/// [`iter_from`] method on [`KeySetIndex`]. See its documentation for details.
+/// This struct is created by the [`iter`], [`iter_from`], [`iter_rev`], [`range`]
+/// or [`range_rev`] method on [`KeySetIndex`]. See its documentation for details.
 ///
 /// [`iter`]: struct.KeySetIndex.html#method.iter
 /// [`iter_from`]: struct.KeySetIndex.html#method.iter_from
+/// [`iter_rev`]: struct.KeySetIndex.html#method.iter_rev
+/// [`range`]: struct.KeySetIndex.html#method.range
+/// [`range_rev`]: struct.KeySetIndex.html#method.range_rev
 /// [`KeySetIndex`]: struct.KeySetIndex.html
 #[derive(Debug)]
 pub struct KeySetIndexIter<'a, K> {
@@ -239,6 +242,77 @@ where
         }
     }
 
+    /// Returns an iterator visiting all elements in descending order. The iterator element
+    /// type is K.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, KeySetIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = KeySetIndex::new(name, &fork);
+    /// index.insert(1_u8);
+    /// index.insert(2_u8);
+    ///
+    /// assert_eq!(index.iter_rev().collect::<Vec<_>>(), vec![2, 1]);
+    /// ```
+    pub fn iter_rev(&self) -> KeySetIndexIter<K> {
+        KeySetIndexIter {
+            base_iter: self.base.iter_rev(&()),
+        }
+    }
+
+    /// Returns an iterator visiting elements within the specified range in ascending order.
+    /// The iterator element type is K.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, KeySetIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = KeySetIndex::new(name, &fork);
+    /// for i in 1_u8..5 {
+    ///     index.insert(i);
+    /// }
+    ///
+    /// assert_eq!(index.range(2_u8..).collect::<Vec<_>>(), vec![2, 3, 4]);
+    /// ```
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> KeySetIndexIter<K> {
+        KeySetIndexIter {
+            base_iter: self.base.range(&(), range),
+        }
+    }
+
+    /// Returns an iterator visiting elements within the specified range in descending order.
+    /// The iterator element type is K.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, KeySetIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = KeySetIndex::new(name, &fork);
+    /// for i in 1_u8..5 {
+    ///     index.insert(i);
+    /// }
+    ///
+    /// assert_eq!(index.range_rev(..3_u8).collect::<Vec<_>>(), vec![2, 1]);
+    /// ```
+    pub fn range_rev<R: RangeBounds<K>>(&self, range: R) -> KeySetIndexIter<K> {
+        KeySetIndexIter {
+            base_iter: self.base.range_rev(&(), range),
+        }
+    }
+
     /// Adds a key to the set.
     ///
     /// # Examples
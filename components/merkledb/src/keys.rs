@@ -16,6 +16,8 @@
 
 //! A definition of `BinaryKey` trait and implementations for common types.
 
+use std::{borrow::Borrow, panic};
+
 use byteorder::{BigEndian, ByteOrder};
 use chrono::{DateTime, NaiveDateTime, Utc};
 use rust_decimal::Decimal;
@@ -83,8 +85,58 @@ pub trait BinaryKey: ToOwned {
     fn write(&self, buffer: &mut [u8]) -> usize;
 
     /// Deserializes the key from the specified buffer of bytes.
+    ///
+    /// Keys of a fixed size should be read from the beginning of the buffer ignoring
+    /// any trailing bytes. This allows to use them as parts of composite keys, such as
+    /// the ones derived with `#[derive(BinaryKey)]` from the `exonum-derive` crate.
     // TODO: Should be unsafe? (ECR-174)
     fn read(buffer: &[u8]) -> Self::Owned;
+
+    /// Returns the size of the serialized key if it is the same for all values of the type,
+    /// or `None` otherwise.
+    ///
+    /// Keys read from untrusted input, such as encoded proofs, are checked to have
+    /// this size, since `read` may panic if the buffer is too short.
+    fn size_of_fixed_key() -> Option<usize>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// Deserializes the key from untrusted input, such as an encoded proof. Unlike `read`,
+    /// the whole buffer must be a valid serialization of the key; otherwise, an error
+    /// is returned.
+    ///
+    /// The default implementation checks the size of keys of a fixed size before reading them.
+    /// Keys of a variable size are read with panics converted into errors, and are checked
+    /// to occupy the whole buffer. Key types, which bytes may be invalid regardless
+    /// of their size (e.g., strings, which must be valid UTF-8), should override this method.
+    fn try_read(buffer: &[u8]) -> Result<Self::Owned, failure::Error>
+    where
+        Self: Sized,
+    {
+        if let Some(size) = Self::size_of_fixed_key() {
+            ensure!(
+                buffer.len() == size,
+                "Invalid size of a key: expected {}, got {}",
+                size,
+                buffer.len()
+            );
+            return Ok(Self::read(buffer));
+        }
+
+        let key = panic::catch_unwind(|| Self::read(buffer))
+            .map_err(|_| format_err!("Invalid serialization of a key"))?;
+        let size = key.borrow().size();
+        ensure!(
+            size == buffer.len(),
+            "Invalid size of a key: expected {}, got {}",
+            size,
+            buffer.len()
+        );
+        Ok(key)
+    }
 }
 
 /// No-op implementation.
@@ -99,6 +151,10 @@ impl BinaryKey for () {
     }
 
     fn read(_buffer: &[u8]) -> Self::Owned {}
+
+    fn size_of_fixed_key() -> Option<usize> {
+        Some(0)
+    }
 }
 
 impl BinaryKey for u8 {
@@ -114,6 +170,10 @@ impl BinaryKey for u8 {
     fn read(buffer: &[u8]) -> Self::Owned {
         buffer[0]
     }
+
+    fn size_of_fixed_key() -> Option<usize> {
+        Some(1)
+    }
 }
 
 /// Uses encoding with the values mapped to `u8`
@@ -131,6 +191,10 @@ impl BinaryKey for i8 {
     fn read(buffer: &[u8]) -> Self::Owned {
         buffer[0].wrapping_sub(Self::min_value() as u8) as Self
     }
+
+    fn size_of_fixed_key() -> Option<usize> {
+        Some(1)
+    }
 }
 
 // spell-checker:ignore utype, itype, vals, ints
@@ -151,6 +215,10 @@ macro_rules! storage_key_for_ints {
             fn read(buffer: &[u8]) -> Self {
                 BigEndian::$read_method(buffer)
             }
+
+            fn size_of_fixed_key() -> Option<usize> {
+                Some($size)
+            }
         }
 
         /// Uses big-endian encoding with the values mapped to the unsigned format
@@ -168,6 +236,10 @@ macro_rules! storage_key_for_ints {
             fn read(buffer: &[u8]) -> Self {
                 BigEndian::$read_method(buffer).wrapping_sub(Self::min_value() as $utype) as Self
             }
+
+            fn size_of_fixed_key() -> Option<usize> {
+                Some($size)
+            }
         }
     };
 }
@@ -190,7 +262,11 @@ macro_rules! storage_key_for_crypto_types {
             }
 
             fn read(buffer: &[u8]) -> Self {
-                Self::from_slice(buffer).unwrap()
+                Self::from_slice(&buffer[..$size]).unwrap()
+            }
+
+            fn size_of_fixed_key() -> Option<usize> {
+                Some($size)
             }
         }
     };
@@ -242,9 +318,13 @@ impl BinaryKey for [u8; 32] {
 
     fn read(buffer: &[u8]) -> Self::Owned {
         let mut value = [0_u8; 32];
-        value.copy_from_slice(buffer);
+        value.copy_from_slice(&buffer[..32]);
         value
     }
+
+    fn size_of_fixed_key() -> Option<usize> {
+        Some(32)
+    }
 }
 
 /// Uses UTF-8 string serialization.
@@ -261,6 +341,10 @@ impl BinaryKey for String {
     fn read(buffer: &[u8]) -> Self::Owned {
         unsafe { ::std::str::from_utf8_unchecked(buffer).to_string() }
     }
+
+    fn try_read(buffer: &[u8]) -> Result<Self, failure::Error> {
+        Ok(::std::str::from_utf8(buffer)?.to_owned())
+    }
 }
 
 impl BinaryKey for str {
@@ -300,6 +384,23 @@ impl BinaryKey for DateTime<Utc> {
         let nanos = u32::read(&buffer[8..12]);
         Self::from_utc(NaiveDateTime::from_timestamp(secs, nanos), Utc)
     }
+
+    fn size_of_fixed_key() -> Option<usize> {
+        Some(12)
+    }
+
+    fn try_read(buffer: &[u8]) -> Result<Self, failure::Error> {
+        ensure!(
+            buffer.len() == 12,
+            "Invalid size of a key: expected 12, got {}",
+            buffer.len()
+        );
+        let secs = i64::read(&buffer[0..8]);
+        let nanos = u32::read(&buffer[8..12]);
+        let datetime = NaiveDateTime::from_timestamp_opt(secs, nanos)
+            .ok_or_else(|| format_err!("Invalid timestamp: {} s, {} ns", secs, nanos))?;
+        Ok(Self::from_utc(datetime, Utc))
+    }
 }
 
 impl BinaryKey for Uuid {
@@ -313,7 +414,11 @@ impl BinaryKey for Uuid {
     }
 
     fn read(buffer: &[u8]) -> Self::Owned {
-        Self::from_slice(buffer).unwrap()
+        Self::from_slice(&buffer[..16]).unwrap()
+    }
+
+    fn size_of_fixed_key() -> Option<usize> {
+        Some(16)
     }
 }
 
@@ -329,9 +434,13 @@ impl BinaryKey for Decimal {
 
     fn read(buffer: &[u8]) -> Self::Owned {
         let mut bytes = [0_u8; 16];
-        bytes.copy_from_slice(buffer);
+        bytes.copy_from_slice(&buffer[..16]);
         Self::deserialize(bytes)
     }
+
+    fn size_of_fixed_key() -> Option<usize> {
+        Some(16)
+    }
 }
 
 #[cfg(test)]
@@ -662,6 +771,47 @@ mod tests {
         assert_round_trip_eq(&decimals);
     }
 
+    #[test]
+    fn test_fixed_size_keys_with_trailing_bytes() {
+        fn assert_read_prefix<T>(value: T)
+        where
+            T: BinaryKey<Owned = T> + PartialEq + Debug,
+        {
+            let mut buffer = get_buffer(&value);
+            value.write(&mut buffer);
+            buffer.extend_from_slice(&[0xff; 40]);
+            assert_eq!(T::read(&buffer), value);
+        }
+
+        assert_read_prefix(-5_i64);
+        assert_read_prefix(12_u32);
+        assert_read_prefix([7_u8; 32]);
+        assert_read_prefix(Hash::zero());
+        assert_read_prefix(
+            PublicKey::from_hex("1e38d80b8a9786648a471b11a9624a9519215743df7321938d70bac73dae3b84")
+                .unwrap(),
+        );
+        assert_read_prefix(Uuid::parse_str("936DA01F9ABD4d9d80C702AF85C822A8").unwrap());
+        assert_read_prefix(Decimal::from_str("3.14").unwrap());
+        assert_read_prefix(Utc.timestamp(1_500_000_000, 12));
+    }
+
+    #[test]
+    fn test_try_read() {
+        assert_eq!(String::try_read(b"key").unwrap(), "key");
+        assert!(String::try_read(&[0xff, 0xfe, 0xfd]).is_err());
+        assert_eq!(u32::try_read(&[0, 0, 0, 7]).unwrap(), 7);
+        assert!(u32::try_read(&[0, 0, 7]).is_err());
+        assert!(Hash::try_read(&[0; 31]).is_err());
+
+        let time = Utc.timestamp(1_500_000_000, 12);
+        let mut buffer = get_buffer(&time);
+        time.write(&mut buffer);
+        assert_eq!(DateTime::<Utc>::try_read(&buffer).unwrap(), time);
+        buffer[8..].copy_from_slice(&[0xff; 4]);
+        assert!(DateTime::<Utc>::try_read(&buffer).is_err());
+    }
+
     fn assert_round_trip_eq<T>(values: &[T])
     where
         T: BinaryKey + PartialEq<<T as ToOwned>::Owned> + Debug,
@@ -24,7 +24,11 @@
 //! that is, the Exonum process has exclusive access to the DB during blockchain operation.
 //! You can interact with the `Database` from multiple threads by cloning its instance.
 //!
-//! Exonum provides two database types: [`RocksDB`] and [`TemporaryDB`].
+//! Exonum provides three database types: [`RocksDB`], [`TemporaryDB`] and [`MemoryDB`].
+//! `TemporaryDB` and `MemoryDB` are intended for testing; the former stores data
+//! in a temporary `RocksDB` instance, while the latter keeps all data in memory.
+//! Any of these databases can be wrapped into a [`CachedDB`], which caches frequently
+//! read values in memory.
 //!
 //! # Snapshot and Fork
 //!
@@ -78,10 +82,16 @@
 //!   proofs of existence and is implemented as a binary Merkle Patricia tree.
 //! - [`KeySetIndex`] and [`ValueSetIndex`] are sets of items, similar to [`BTreeSet`] and
 //!   [`HashSet`] accordingly.
+//! - [`ProofKeySetIndex`] is a Merkelized version of `KeySetIndex` that supports cryptographic
+//!   proofs of membership and non-membership for its items.
+//! - [`MountainRangeIndex`] is an append-only Merkelized list implemented as a Merkle mountain
+//!   range. It supports proofs of inclusion into the previous states of the list and proofs
+//!   that a previous state of the list is a prefix of the current one.
 //!
 //! [`Database`]: trait.Database.html
 //! [`RocksDB`]: struct.RocksDB.html
 //! [`TemporaryDB`]: struct.TemporaryDB.html
+//! [`MemoryDB`]: struct.MemoryDB.html
 //! [`Snapshot`]: trait.Snapshot.html
 //! [`Fork`]: struct.Fork.html
 //! [`Patch`]: struct.Patch.html
@@ -97,8 +107,11 @@
 //! [`ProofListIndex`]: proof_list_index/struct.ProofListIndex.html
 //! [`ProofMapIndex`]: proof_map_index/struct.ProofMapIndex.html
 //! [`KeySetIndex`]: key_set_index/struct.KeySetIndex.html
+//! [`ProofKeySetIndex`]: proof_key_set_index/struct.ProofKeySetIndex.html
+//! [`MountainRangeIndex`]: mountain_range_index/struct.MountainRangeIndex.html
 //! [`ValueSetIndex`]: value_set_index/struct.ValueSetIndex.html
 //! [doc:storage]: https://exonum.com/doc/architecture/storage
+//! [`CachedDB`]: struct.CachedDB.html
 //! [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
 //! [`Box`]: https://doc.rust-lang.org/std/boxed/struct.Box.html
 //! [`Vec`]: https://doc.rust-lang.org/std/vec/struct.Vec.html
@@ -122,12 +135,17 @@
 )]
 
 #[doc(no_inline)]
-pub use self::proof_map_index::{MapProof, ProofMapIndex};
+pub use self::proof_map_index::{MapProof, ProofMapIndex, RawProofMapIndex};
 pub use self::{
-    backends::{rocksdb::RocksDB, temporarydb::TemporaryDB},
+    backends::{
+        cached::{CacheStats, CachedDB},
+        memorydb::MemoryDB,
+        rocksdb::RocksDB,
+        temporarydb::TemporaryDB,
+    },
     db::{
         Change, Changes, ChangesIterator, Database, Fork, Iter, Iterator, Patch, PatchIterator,
-        Snapshot,
+        Savepoint, Snapshot, PATCH_FORMAT_VERSION,
     },
     entry::Entry,
     error::Error,
@@ -136,12 +154,23 @@ pub use self::{
     keys::BinaryKey,
     list_index::ListIndex,
     map_index::MapIndex,
-    options::DbOptions,
-    proof_list_index::{ListProof, ProofListIndex},
+    migration::{
+        schema_version, Migration, MigrationContext, MigrationProgress, MigrationReport,
+        MigrationSet, MigrationStage,
+    },
+    mountain_range_index::{MountainRangeIndex, MountainRangeProof},
+    options::{ColumnFamilyOptions, CompressionType, DbOptions},
+    proof_key_set_index::ProofKeySetIndex,
+    proof_list_index::{ListConsistencyProof, ListProof, ProofListIndex},
+    read_set::ReadSet,
     sparse_list_index::SparseListIndex,
+    stats::{ColumnFamilyStats, DbStats},
     value_set_index::ValueSetIndex,
     values::BinaryValue,
-    views::{IndexAccess, IndexAddress, IndexBuilder, ObjectAccess, Ref, RefMut, View},
+    views::{
+        IndexAccess, IndexAddress, IndexBuilder, IndexInfo, IndexType, ObjectAccess, Ref, RefMut,
+        View,
+    },
 };
 
 #[macro_use]
@@ -154,17 +183,23 @@ pub type Result<T> = ::std::result::Result<T, Error>;
 mod macros;
 mod backends;
 mod db;
+mod encoding;
 mod entry;
 mod error;
 mod hash;
 mod keys;
+mod migration;
 mod options;
+mod read_set;
+mod stats;
 mod values;
 mod views;
 
 pub mod key_set_index;
 pub mod list_index;
 pub mod map_index;
+pub mod mountain_range_index;
+pub mod proof_key_set_index;
 pub mod proof_list_index;
 pub mod proof_map_index;
 pub mod sparse_list_index;
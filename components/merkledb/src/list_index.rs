@@ -17,7 +17,7 @@
 //! The given section contains methods related to `ListIndex` and the iterator
 //! over the items of this list.
 
-use std::marker::PhantomData;
+use std::{marker::PhantomData, ops::RangeBounds};
 
 use crate::{
     views::{
@@ -45,11 +45,14 @@ pub struct ListIndex<T: IndexAccess, V> {
 
 /// Returns an iterator over the items of a `ListIndex`.
 ///
-/// This struct is created by the [`iter`] or
-/// [`iter_from`] method on [`ListIndex`]. See its documentation for details.
+/// This struct is created by the [`iter`], [`iter_from`], [`iter_rev`], [`range`]
+/// or [`range_rev`] method on [`ListIndex`]. See its documentation for details.
 ///
 /// [`iter`]: struct.ListIndex.html#method.iter
 /// [`iter_from`]: struct.ListIndex.html#method.iter_from
+/// [`iter_rev`]: struct.ListIndex.html#method.iter_rev
+/// [`range`]: struct.ListIndex.html#method.range
+/// [`range_rev`]: struct.ListIndex.html#method.range_rev
 /// [`ListIndex`]: struct.ListIndex.html
 #[derive(Debug)]
 pub struct ListIndexIter<'a, V> {
@@ -310,6 +313,77 @@ where
         }
     }
 
+    /// Returns an iterator over the list in reverse order, i.e., starting from the last
+    /// element. The iterator element type is V.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, ListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = ListIndex::new(name, &fork);
+    ///
+    /// index.extend([1, 2, 3, 4, 5].iter().cloned());
+    ///
+    /// // Three latest elements.
+    /// let latest = index.iter_rev().take(3).collect::<Vec<i32>>();
+    /// assert_eq!(latest, vec![5, 4, 3]);
+    /// ```
+    pub fn iter_rev(&self) -> ListIndexIter<V> {
+        ListIndexIter {
+            base_iter: self.base.iter_rev(&()),
+        }
+    }
+
+    /// Returns an iterator over the elements of the list with indices within the specified
+    /// range. The iterator element type is V.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, ListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = ListIndex::new(name, &fork);
+    ///
+    /// index.extend([1, 2, 3, 4, 5].iter().cloned());
+    ///
+    /// assert_eq!(index.range(1..3).collect::<Vec<i32>>(), vec![2, 3]);
+    /// ```
+    pub fn range<R: RangeBounds<u64>>(&self, range: R) -> ListIndexIter<V> {
+        ListIndexIter {
+            base_iter: self.base.range(&(), range),
+        }
+    }
+
+    /// Returns an iterator over the elements of the list with indices within the specified
+    /// range in reverse order. The iterator element type is V.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, ListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = ListIndex::new(name, &fork);
+    ///
+    /// index.extend([1, 2, 3, 4, 5].iter().cloned());
+    ///
+    /// assert_eq!(index.range_rev(..=3).collect::<Vec<i32>>(), vec![4, 3, 2, 1]);
+    /// ```
+    pub fn range_rev<R: RangeBounds<u64>>(&self, range: R) -> ListIndexIter<V> {
+        ListIndexIter {
+            base_iter: self.base.range_rev(&(), range),
+        }
+    }
+
     /// Appends an element to the back of the list.
     ///
     /// # Examples
@@ -18,7 +18,7 @@
 //! the [`BinaryValue`] trait. The given section contains methods related to
 //! `MapIndex` and iterators over the items of this map.
 
-use std::{borrow::Borrow, marker::PhantomData};
+use std::{borrow::Borrow, marker::PhantomData, ops::RangeBounds};
 
 use super::{
     views::{AnyObject, IndexAccess, IndexBuilder, IndexType, Iter as ViewIter, View},
@@ -43,11 +43,14 @@ pub struct MapIndex<T: IndexAccess, K, V> {
 
 /// Returns an iterator over the entries of a `MapIndex`.
 ///
-/// This struct is created by the [`iter`] or
-/// [`iter_from`] method on [`MapIndex`]. See its documentation for additional details.
+/// This struct is created by the [`iter`], [`iter_from`], [`iter_rev`], [`range`]
+/// or [`range_rev`] method on [`MapIndex`]. See its documentation for additional details.
 ///
 /// [`iter`]: struct.MapIndex.html#method.iter
 /// [`iter_from`]: struct.MapIndex.html#method.iter_from
+/// [`iter_rev`]: struct.MapIndex.html#method.iter_rev
+/// [`range`]: struct.MapIndex.html#method.range
+/// [`range_rev`]: struct.MapIndex.html#method.range_rev
 /// [`MapIndex`]: struct.MapIndex.html
 #[derive(Debug)]
 pub struct MapIndexIter<'a, K, V> {
@@ -401,6 +404,89 @@ where
         }
     }
 
+    /// Returns an iterator over the entries of the map in descending order. The iterator element
+    /// type is (K, V).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, MapIndex};
+    ///
+    /// let db = TemporaryDB::default();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = MapIndex::new(name, &fork);
+    /// index.put(&1_u8, 10_u8);
+    /// index.put(&2_u8, 20_u8);
+    ///
+    /// assert_eq!(index.iter_rev().collect::<Vec<_>>(), vec![(2, 20), (1, 10)]);
+    /// ```
+    pub fn iter_rev(&self) -> MapIndexIter<K, V> {
+        MapIndexIter {
+            base_iter: self.base.iter_rev(&()),
+        }
+    }
+
+    /// Returns an iterator over the entries of the map with keys within the specified range
+    /// in ascending order. The iterator element type is (K, V).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, MapIndex};
+    ///
+    /// let db = TemporaryDB::default();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = MapIndex::new(name, &fork);
+    /// for i in 0_u8..10 {
+    ///     index.put(&i, i * 10);
+    /// }
+    ///
+    /// let keys = index.range(3_u8..6).map(|(k, _)| k).collect::<Vec<_>>();
+    /// assert_eq!(keys, vec![3, 4, 5]);
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> MapIndexIter<K, V>
+    where
+        K: Borrow<Q>,
+        Q: BinaryKey + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        MapIndexIter {
+            base_iter: self.base.range(&(), range),
+        }
+    }
+
+    /// Returns an iterator over the entries of the map with keys within the specified range
+    /// in descending order. The iterator element type is (K, V).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, MapIndex};
+    ///
+    /// let db = TemporaryDB::default();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = MapIndex::new(name, &fork);
+    /// for i in 0_u8..10 {
+    ///     index.put(&i, i * 10);
+    /// }
+    ///
+    /// let keys = index.range_rev(3_u8..6).map(|(k, _)| k).collect::<Vec<_>>();
+    /// assert_eq!(keys, vec![5, 4, 3]);
+    /// ```
+    pub fn range_rev<Q, R>(&self, range: R) -> MapIndexIter<K, V>
+    where
+        K: Borrow<Q>,
+        Q: BinaryKey + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        MapIndexIter {
+            base_iter: self.base.range_rev(&(), range),
+        }
+    }
+
     /// Inserts a key-value pair into a map.
     ///
     /// # Examples
@@ -0,0 +1,447 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Migrations of the data layout of the indexes.
+//!
+//! Indexes are grouped into schemas identified by their namespaces (e.g., service names).
+//! Each schema has a version stored in the database metadata; a migration script
+//! transforms the indexes of the schema to the layout of the next version.
+
+use failure::{self, bail};
+
+use std::fmt;
+
+use crate::{views::View, Database, Fork, IndexAccess};
+
+/// Name of the column family used to store versions of the schemas.
+const SCHEMA_VERSIONS_NAME: &str = "__SCHEMA_VERSIONS__";
+
+type MigrationScript =
+    dyn Fn(&mut MigrationContext) -> Result<(), failure::Error> + Send + Sync + 'static;
+
+/// Returns the version of the schema with the specified namespace stored in the database.
+/// The version of a schema without applied migrations is zero.
+pub fn schema_version<T: IndexAccess>(index_access: T, namespace: &str) -> u32 {
+    View::new(index_access, SCHEMA_VERSIONS_NAME)
+        .get(namespace)
+        .unwrap_or_default()
+}
+
+fn set_schema_version(fork: &Fork, namespace: &str, version: u32) {
+    View::new(fork, SCHEMA_VERSIONS_NAME).put(namespace, version);
+}
+
+/// A single migration of a schema.
+///
+/// The migration script transforms the indexes of the schema from the previous version
+/// to the version of the migration. Changes made by the script are applied to a [`Fork`]
+/// together with the new version of the schema.
+///
+/// [`Fork`]: struct.Fork.html
+pub struct Migration {
+    version: u32,
+    description: String,
+    script: Box<MigrationScript>,
+}
+
+impl Migration {
+    /// Creates a new migration to the specified version of the schema.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `version` is zero, which corresponds to the schema without migrations.
+    pub fn new<S, F>(version: u32, description: S, script: F) -> Self
+    where
+        S: Into<String>,
+        F: Fn(&mut MigrationContext) -> Result<(), failure::Error> + Send + Sync + 'static,
+    {
+        assert!(version > 0, "Migration version should be positive");
+        Self {
+            version,
+            description: description.into(),
+            script: Box::new(script),
+        }
+    }
+
+    /// Returns the version of the schema after the migration.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns the human-readable description of the migration.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+impl fmt::Debug for Migration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Migration")
+            .field("version", &self.version)
+            .field("description", &self.description)
+            .finish()
+    }
+}
+
+/// Stage of a migration reported in [`MigrationProgress`].
+///
+/// [`MigrationProgress`]: struct.MigrationProgress.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationStage {
+    /// The migration script is about to be run.
+    Started,
+    /// The migration script has processed `done` items out of `total`.
+    InProgress {
+        /// Number of processed items.
+        done: u64,
+        /// Total number of items.
+        total: u64,
+    },
+    /// The migration script has finished successfully.
+    Finished,
+}
+
+/// Progress of a migration passed to the listener of [`MigrationSet`] methods.
+///
+/// [`MigrationSet`]: struct.MigrationSet.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MigrationProgress<'a> {
+    /// Namespace of the migrated schema.
+    pub namespace: &'a str,
+    /// Version of the migration.
+    pub version: u32,
+    /// Description of the migration.
+    pub description: &'a str,
+    /// Stage of the migration.
+    pub stage: MigrationStage,
+}
+
+/// Context of a running migration script.
+pub struct MigrationContext<'a> {
+    fork: &'a Fork,
+    namespace: &'a str,
+    migration: &'a Migration,
+    listener: &'a mut dyn FnMut(&MigrationProgress),
+}
+
+impl<'a> MigrationContext<'a> {
+    /// Returns the fork the migration is applied to.
+    pub fn fork(&self) -> &'a Fork {
+        self.fork
+    }
+
+    /// Returns the namespace of the migrated schema.
+    pub fn namespace(&self) -> &str {
+        self.namespace
+    }
+
+    /// Reports that the script has processed `done` items out of `total`.
+    pub fn report_progress(&mut self, done: u64, total: u64) {
+        self.report(MigrationStage::InProgress { done, total });
+    }
+
+    fn report(&mut self, stage: MigrationStage) {
+        (self.listener)(&MigrationProgress {
+            namespace: self.namespace,
+            version: self.migration.version,
+            description: &self.migration.description,
+            stage,
+        });
+    }
+}
+
+impl<'a> fmt::Debug for MigrationContext<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MigrationContext")
+            .field("namespace", &self.namespace)
+            .field("migration", &self.migration)
+            .finish()
+    }
+}
+
+/// Result of applying migrations to a schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationReport {
+    /// Namespace of the schema.
+    pub namespace: String,
+    /// Version of the schema before the migrations.
+    pub initial_version: u32,
+    /// Versions of the applied migrations in the order of application.
+    pub applied: Vec<u32>,
+}
+
+impl MigrationReport {
+    /// Returns the version of the schema after the migrations.
+    pub fn final_version(&self) -> u32 {
+        self.applied.last().cloned().unwrap_or(self.initial_version)
+    }
+}
+
+/// Ordered set of migrations of a schema with the specified namespace.
+///
+/// # Examples
+///
+/// ```
+/// use exonum_merkledb::{Database, MapIndex, Migration, MigrationSet, TemporaryDB};
+///
+/// let db = TemporaryDB::new();
+/// let fork = db.fork();
+/// MapIndex::new("wallets", &fork).put(&1_u8, 10_u32);
+/// db.merge(fork.into_patch()).unwrap();
+///
+/// let migrations = MigrationSet::new("wallets").add(Migration::new(
+///     1,
+///     "Convert balances to u64",
+///     |context| {
+///         let old: MapIndex<_, u8, u32> = MapIndex::new("wallets", context.fork());
+///         let balances: Vec<_> = old.iter().collect();
+///         let total = balances.len() as u64;
+///         let mut new = MapIndex::new("wallets.v1", context.fork());
+///         for (i, (key, balance)) in balances.into_iter().enumerate() {
+///             new.put(&key, u64::from(balance));
+///             context.report_progress(i as u64 + 1, total);
+///         }
+///         Ok(())
+///     },
+/// ));
+///
+/// // Dry run checks that the migrations succeed without changing the database.
+/// let report = migrations.dry_run(&db, |_| {}).unwrap();
+/// assert_eq!(report.applied, vec![1]);
+/// assert_eq!(migrations.pending(&db.snapshot()).count(), 1);
+///
+/// let report = migrations.run(&db, |progress| println!("{:?}", progress)).unwrap();
+/// assert_eq!(report.final_version(), 1);
+/// assert_eq!(migrations.pending(&db.snapshot()).count(), 0);
+/// ```
+#[derive(Debug)]
+pub struct MigrationSet {
+    namespace: String,
+    migrations: Vec<Migration>,
+}
+
+impl MigrationSet {
+    /// Creates an empty set of migrations for the schema with the specified namespace.
+    pub fn new<S: Into<String>>(namespace: S) -> Self {
+        Self {
+            namespace: namespace.into(),
+            migrations: Vec::new(),
+        }
+    }
+
+    /// Adds a migration to the set.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the version of the migration is not greater than the version of
+    ///   the previously added migration.
+    pub fn add(mut self, migration: Migration) -> Self {
+        assert!(
+            migration.version > self.latest_version(),
+            "Migrations should be added in the increasing order of versions"
+        );
+        self.migrations.push(migration);
+        self
+    }
+
+    /// Returns the namespace of the schema.
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// Returns the version of the schema after all migrations in the set, or zero
+    /// if the set is empty.
+    pub fn latest_version(&self) -> u32 {
+        self.migrations.last().map_or(0, Migration::version)
+    }
+
+    /// Returns migrations which have not been applied to the schema yet.
+    pub fn pending<T: IndexAccess>(&self, index_access: T) -> impl Iterator<Item = &Migration> {
+        let version = schema_version(index_access, &self.namespace);
+        self.migrations
+            .iter()
+            .filter(move |migration| migration.version > version)
+    }
+
+    /// Marks all migrations in the set as applied without running them. This method
+    /// should be used when the schema is created with the latest layout of the indexes.
+    pub fn set_latest_version(&self, fork: &Fork) {
+        set_schema_version(fork, &self.namespace, self.latest_version());
+    }
+
+    /// Applies pending migrations to the fork in the increasing order of their versions.
+    /// The listener is notified about the progress of the migrations.
+    ///
+    /// If a migration fails, the error is returned and the fork may contain changes
+    /// made by the failed migration, so it should be discarded (or rolled back).
+    pub fn apply<F>(&self, fork: &Fork, mut listener: F) -> Result<MigrationReport, failure::Error>
+    where
+        F: FnMut(&MigrationProgress),
+    {
+        let initial_version = schema_version(fork, &self.namespace);
+        if initial_version > self.latest_version() {
+            bail!(
+                "Version {} of schema `{}` is newer than the latest known version {}",
+                initial_version,
+                self.namespace,
+                self.latest_version()
+            );
+        }
+
+        let mut applied = Vec::new();
+        for migration in self.pending(fork) {
+            let mut context = MigrationContext {
+                fork,
+                namespace: &self.namespace,
+                migration,
+                listener: &mut listener,
+            };
+            context.report(MigrationStage::Started);
+            if let Err(e) = (migration.script)(&mut context) {
+                bail!(
+                    "Migration of schema `{}` to version {} failed: {}",
+                    self.namespace,
+                    migration.version,
+                    e
+                );
+            }
+            set_schema_version(fork, &self.namespace, migration.version);
+            context.report(MigrationStage::Finished);
+            applied.push(migration.version);
+        }
+
+        Ok(MigrationReport {
+            namespace: self.namespace.clone(),
+            initial_version,
+            applied,
+        })
+    }
+
+    /// Applies pending migrations to the database.
+    ///
+    /// The migrations are applied atomically: if any migration fails, no changes
+    /// are merged into the database.
+    pub fn run<F>(&self, db: &dyn Database, listener: F) -> Result<MigrationReport, failure::Error>
+    where
+        F: FnMut(&MigrationProgress),
+    {
+        let fork = db.fork();
+        let report = self.apply(&fork, listener)?;
+        db.merge(fork.into_patch())?;
+        Ok(report)
+    }
+
+    /// Runs pending migrations against a fork of the database without merging
+    /// the changes, so the database remains unchanged.
+    pub fn dry_run<F>(
+        &self,
+        db: &dyn Database,
+        listener: F,
+    ) -> Result<MigrationReport, failure::Error>
+    where
+        F: FnMut(&MigrationProgress),
+    {
+        self.apply(&db.fork(), listener)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use failure::bail;
+
+    use super::{schema_version, Migration, MigrationSet, MigrationStage};
+    use crate::{Database, Entry, ListIndex, TemporaryDB};
+
+    fn migrations() -> MigrationSet {
+        MigrationSet::new("schema")
+            .add(Migration::new(1, "Move values to a list", |context| {
+                let mut entry: Entry<_, u32> = Entry::new("schema.value", context.fork());
+                let value = entry.take().unwrap_or_default();
+                ListIndex::new("schema.values", context.fork()).push(value);
+                context.report_progress(1, 1);
+                Ok(())
+            }))
+            .add(Migration::new(3, "Double values", |context| {
+                let mut list: ListIndex<_, u32> = ListIndex::new("schema.values", context.fork());
+                let values: Vec<_> = list.iter().map(|value| value * 2).collect();
+                list.clear();
+                list.extend(values);
+                Ok(())
+            }))
+    }
+
+    #[test]
+    fn migrations_run_in_order() {
+        let db = TemporaryDB::new();
+        let fork = db.fork();
+        Entry::new("schema.value", &fork).set(5_u32);
+        db.merge(fork.into_patch()).unwrap();
+
+        let mut events = Vec::new();
+        let report = migrations()
+            .dry_run(&db, |progress| {
+                events.push((progress.version, progress.stage))
+            })
+            .unwrap();
+        assert_eq!(report.applied, vec![1, 3]);
+        assert_eq!(
+            events,
+            vec![
+                (1, MigrationStage::Started),
+                (1, MigrationStage::InProgress { done: 1, total: 1 }),
+                (1, MigrationStage::Finished),
+                (3, MigrationStage::Started),
+                (3, MigrationStage::Finished),
+            ]
+        );
+        assert_eq!(schema_version(&db.snapshot(), "schema"), 0);
+
+        let report = migrations().run(&db, |_| {}).unwrap();
+        assert_eq!((report.initial_version, report.final_version()), (0, 3));
+        let snapshot = db.snapshot();
+        assert_eq!(schema_version(&snapshot, "schema"), 3);
+        let list: ListIndex<_, u32> = ListIndex::new("schema.values", &snapshot);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![10]);
+
+        // Applied migrations are not run again.
+        let report = migrations().run(&db, |_| {}).unwrap();
+        assert!(report.applied.is_empty());
+    }
+
+    #[test]
+    fn failed_migration() {
+        let db = TemporaryDB::new();
+        let migrations = migrations().add(Migration::new(4, "Fail", |_| bail!("Oops")));
+        let err = migrations.run(&db, |_| {}).unwrap_err();
+        assert!(err.to_string().contains("to version 4 failed: Oops"));
+        assert_eq!(schema_version(&db.snapshot(), "schema"), 0);
+
+        let fork = db.fork();
+        migrations.set_latest_version(&fork);
+        db.merge(fork.into_patch()).unwrap();
+        assert_eq!(migrations.pending(&db.snapshot()).count(), 0);
+
+        // Downgrades are not supported.
+        let err = self::migrations().run(&db, |_| {}).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("newer than the latest known version"));
+    }
+
+    #[test]
+    #[should_panic(expected = "increasing order of versions")]
+    fn migrations_in_wrong_order() {
+        migrations().add(Migration::new(2, "Old migration", |_| Ok(())));
+    }
+}
@@ -0,0 +1,681 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An implementation of an append-only Merkelized list (Merkle mountain range).
+//!
+//! A Merkle mountain range (MMR) is a list of perfect binary Merkle trees (_peaks_)
+//! of strictly decreasing heights, which correspond to the binary representation
+//! of the number of elements in the list. Unlike nodes of a `ProofListIndex`,
+//! the nodes of the range never change once they are created, so the index can prove
+//! inclusion of elements into any previous state of the list, as well as the fact
+//! that a previous state of the list is a prefix of a newer one.
+
+pub use self::proof::{ConsistencyProof, MountainRangeProof, MountainRangeProofError};
+
+use std::marker::PhantomData;
+
+use exonum_crypto::Hash;
+
+use self::proof::bag_peaks;
+use crate::{
+    hash::HashTag,
+    proof_list_index::ProofListKey,
+    views::{
+        AnyObject, IndexAccess, IndexAddress, IndexBuilder, IndexState, IndexType,
+        Iter as ViewIter, View,
+    },
+    BinaryKey, BinaryValue, ObjectHash,
+};
+
+mod proof;
+#[cfg(test)]
+mod tests;
+
+/// An append-only Merkelized list implemented as a Merkle mountain range.
+///
+/// `MountainRangeIndex` stores elements in a sequential order and provides proofs
+/// of inclusion for the elements against the current or any previous state of the list,
+/// and consistency proofs, which assert that a previous state of the list is a prefix
+/// of a newer one. `MountainRangeIndex` requires that elements implement
+/// the [`BinaryValue`] trait.
+///
+/// Appending an element takes `O(log n)` time and never modifies existing nodes
+/// of the range. The hash of the index depends only on the number of elements
+/// and the hashes of the peaks of the range (see [`peaks`]).
+///
+/// [`BinaryValue`]: ../trait.BinaryValue.html
+/// [`peaks`]: #method.peaks
+#[derive(Debug)]
+pub struct MountainRangeIndex<T: IndexAccess, V> {
+    base: View<T>,
+    state: IndexState<T, u64>,
+    _v: PhantomData<V>,
+}
+
+/// An iterator over the items of a `MountainRangeIndex`.
+///
+/// This struct is created by the [`iter`] or [`iter_from`] method on [`MountainRangeIndex`].
+/// See its documentation for details.
+///
+/// [`iter`]: struct.MountainRangeIndex.html#method.iter
+/// [`iter_from`]: struct.MountainRangeIndex.html#method.iter_from
+/// [`MountainRangeIndex`]: struct.MountainRangeIndex.html
+#[derive(Debug)]
+pub struct MountainRangeIndexIter<'a, V> {
+    base_iter: ViewIter<'a, ProofListKey, V>,
+}
+
+impl<T, V> AnyObject<T> for MountainRangeIndex<T, V>
+where
+    T: IndexAccess,
+    V: BinaryValue,
+{
+    fn view(self) -> View<T> {
+        self.base
+    }
+
+    fn object_type(&self) -> IndexType {
+        IndexType::MountainRange
+    }
+
+    fn metadata(&self) -> Vec<u8> {
+        self.state.metadata().to_bytes()
+    }
+}
+
+impl<T, V> MountainRangeIndex<T, V>
+where
+    T: IndexAccess,
+    V: BinaryValue,
+{
+    /// Creates a new index representation based on the name and storage view.
+    ///
+    /// Storage view can be specified as [`&Snapshot`] or [`&mut Fork`]. In the first case, only
+    /// immutable methods are available. In the second case, both immutable and mutable methods are
+    /// available.
+    ///
+    /// [`&Snapshot`]: ../trait.Snapshot.html
+    /// [`&mut Fork`]: ../struct.Fork.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, MountainRangeIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    ///
+    /// let snapshot = db.snapshot();
+    /// let index: MountainRangeIndex<_, u8> = MountainRangeIndex::new(name, &snapshot);
+    ///
+    /// let fork = db.fork();
+    /// let mut mut_index: MountainRangeIndex<_, u8> = MountainRangeIndex::new(name, &fork);
+    /// ```
+    pub fn new<S: Into<String>>(index_name: S, index_access: T) -> Self {
+        let (base, state) = IndexBuilder::new(index_access)
+            .index_type(IndexType::MountainRange)
+            .index_name(index_name)
+            .build();
+        Self {
+            base,
+            state,
+            _v: PhantomData,
+        }
+    }
+
+    /// Creates a new index representation based on the name, common prefix of its keys
+    /// and storage view.
+    ///
+    /// Storage view can be specified as [`&Snapshot`] or [`&mut Fork`]. In the first case, only
+    /// immutable methods are available. In the second case, both immutable and mutable methods are
+    /// available.
+    ///
+    /// [`&Snapshot`]: ../trait.Snapshot.html
+    /// [`&mut Fork`]: ../struct.Fork.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, MountainRangeIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let index_id = vec![01];
+    ///
+    /// let snapshot = db.snapshot();
+    /// let index: MountainRangeIndex<_, u8> =
+    ///     MountainRangeIndex::new_in_family(name, &index_id, &snapshot);
+    ///
+    /// let fork = db.fork();
+    /// let mut mut_index: MountainRangeIndex<_, u8> =
+    ///     MountainRangeIndex::new_in_family(name, &index_id, &fork);
+    /// ```
+    pub fn new_in_family<S, I>(family_name: S, index_id: &I, index_access: T) -> Self
+    where
+        I: BinaryKey,
+        I: ?Sized,
+        S: Into<String>,
+    {
+        let (base, state) = IndexBuilder::new(index_access)
+            .index_type(IndexType::MountainRange)
+            .index_name(family_name)
+            .family_id(index_id)
+            .build();
+        Self {
+            base,
+            state,
+            _v: PhantomData,
+        }
+    }
+
+    pub fn create_from<I: Into<IndexAddress>>(address: I, access: T) -> Self {
+        let (base, state) = IndexBuilder::from_address(address, access)
+            .index_type(IndexType::MountainRange)
+            .build();
+
+        Self {
+            base,
+            state,
+            _v: PhantomData,
+        }
+    }
+
+    pub fn get_from<I: Into<IndexAddress>>(address: I, access: T) -> Option<Self> {
+        IndexBuilder::from_address(address, access)
+            .index_type(IndexType::MountainRange)
+            .build_existed()
+            .map(|(base, state)| Self {
+                base,
+                state,
+                _v: PhantomData,
+            })
+    }
+
+    fn get_branch_unchecked(&self, key: ProofListKey) -> Hash {
+        debug_assert!(key.height() > 0);
+
+        self.base.get(&key).unwrap()
+    }
+
+    fn set_branch(&mut self, key: ProofListKey, hash: Hash) {
+        debug_assert!(key.height() > 0);
+
+        self.base.put(&key, hash)
+    }
+
+    fn check_len(&self, len: u64) {
+        if len > self.len() {
+            panic!(
+                "Length out of bounds: the len is {} but the requested len is {}",
+                self.len(),
+                len
+            );
+        }
+    }
+
+    /// Returns the element at the indicated position or `None` if the indicated position
+    /// is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, MountainRangeIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = MountainRangeIndex::new(name, &fork);
+    /// assert_eq!(None, index.get(0));
+    ///
+    /// index.push(10);
+    /// assert_eq!(Some(10), index.get(0));
+    /// ```
+    pub fn get(&self, index: u64) -> Option<V> {
+        self.base.get(&ProofListKey::leaf(index))
+    }
+
+    /// Returns the last element of the list or `None` if it is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, MountainRangeIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = MountainRangeIndex::new(name, &fork);
+    /// assert_eq!(None, index.last());
+    ///
+    /// index.push(1);
+    /// assert_eq!(Some(1), index.last());
+    /// ```
+    pub fn last(&self) -> Option<V> {
+        match self.len() {
+            0 => None,
+            l => self.get(l - 1),
+        }
+    }
+
+    /// Returns `true` if the list contains no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, MountainRangeIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = MountainRangeIndex::new(name, &fork);
+    /// assert!(index.is_empty());
+    ///
+    /// index.push(10);
+    /// assert!(!index.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of elements in the list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, MountainRangeIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = MountainRangeIndex::new(name, &fork);
+    /// assert_eq!(0, index.len());
+    ///
+    /// index.push(1);
+    /// assert_eq!(1, index.len());
+    /// ```
+    pub fn len(&self) -> u64 {
+        self.state.get()
+    }
+
+    /// Returns the hashes of the peaks of the range, from the highest peak to the lowest one.
+    ///
+    /// The number of peaks is equal to the number of ones in the binary representation
+    /// of the list length. Together with the length, the peaks are sufficient to compute
+    /// the hash of the index and to append new elements to the range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, MountainRangeIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = MountainRangeIndex::new(name, &fork);
+    /// assert!(index.peaks().is_empty());
+    ///
+    /// index.extend(vec![1_u8, 2, 3, 4, 5]);
+    /// assert_eq!(index.peaks().len(), 2);
+    /// ```
+    pub fn peaks(&self) -> Vec<Hash> {
+        self.peaks_at(self.len())
+    }
+
+    fn peaks_at(&self, len: u64) -> Vec<Hash> {
+        ProofListKey::peaks(len)
+            .into_iter()
+            .map(|key| self.get_branch_unchecked(key))
+            .collect()
+    }
+
+    /// Returns the hash of the index at the moment when it contained `len` elements.
+    /// `object_hash_at(self.len())` is equal to `object_hash()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is greater than the current length of the list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, MountainRangeIndex, ObjectHash};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = MountainRangeIndex::new(name, &fork);
+    ///
+    /// index.extend(vec![1_u8, 2]);
+    /// let old_hash = index.object_hash();
+    /// index.push(3);
+    /// assert_eq!(index.object_hash_at(2), old_hash);
+    /// ```
+    pub fn object_hash_at(&self, len: u64) -> Hash {
+        self.check_len(len);
+        HashTag::hash_mountain_range_node(len, bag_peaks(&self.peaks_at(len)))
+    }
+
+    /// Returns a proof of inclusion for the list element at the specified position
+    /// into the current state of the list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is equal or greater than the current length of the list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, MountainRangeIndex, ObjectHash};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = MountainRangeIndex::new(name, &fork);
+    /// index.extend(vec![1_u8, 2, 3]);
+    ///
+    /// let proof = index.get_proof(1);
+    /// assert_eq!(proof.validate(index.object_hash()).unwrap(), (1, &2));
+    /// ```
+    pub fn get_proof(&self, index: u64) -> MountainRangeProof<V> {
+        self.get_proof_at(index, self.len())
+    }
+
+    /// Returns a proof of inclusion for the list element at the specified position
+    /// into the state of the list when it contained `len` elements. The proof
+    /// can be checked against [`object_hash_at(len)`].
+    ///
+    /// [`object_hash_at(len)`]: #method.object_hash_at
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is greater than the current length of the list, or `index`
+    /// is equal or greater than `len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, MountainRangeIndex, ObjectHash};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = MountainRangeIndex::new(name, &fork);
+    /// index.extend(vec![1_u8, 2, 3]);
+    /// let old_hash = index.object_hash();
+    /// index.extend(vec![4_u8, 5]);
+    ///
+    /// let proof = index.get_proof_at(2, 3);
+    /// assert_eq!(proof.validate(old_hash).unwrap(), (2, &3));
+    /// ```
+    pub fn get_proof_at(&self, index: u64, len: u64) -> MountainRangeProof<V> {
+        self.check_len(len);
+        if index >= len {
+            panic!(
+                "Index out of bounds: the len is {} but the index is {}",
+                len, index
+            );
+        }
+
+        let peaks = ProofListKey::peaks(len);
+        let peak_position = peaks
+            .iter()
+            .position(|peak| index < peak.first_left_leaf_index() + peak.leaves_count())
+            .unwrap();
+
+        let mut path = Vec::new();
+        let mut key = ProofListKey::new(1, index);
+        while key.height() < peaks[peak_position].height() {
+            let sibling = if key.is_left() {
+                key.as_right()
+            } else {
+                key.as_left()
+            };
+            path.push(self.get_branch_unchecked(sibling));
+            key = key.parent();
+        }
+
+        let other_peaks = peaks
+            .into_iter()
+            .enumerate()
+            .filter(|&(i, _)| i != peak_position)
+            .map(|(_, peak)| self.get_branch_unchecked(peak))
+            .collect();
+        let value = self.get(index).unwrap();
+        MountainRangeProof::new(len, index, value, path, other_peaks)
+    }
+
+    /// Returns a proof that the list with `old_len` elements is a prefix
+    /// of the list with `new_len` elements. The proof can be checked against
+    /// [`object_hash_at(old_len)`] and [`object_hash_at(new_len)`].
+    ///
+    /// [`object_hash_at(old_len)`]: #method.object_hash_at
+    /// [`object_hash_at(new_len)`]: #method.object_hash_at
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` is greater than the current length of the list, or `old_len`
+    /// is greater than `new_len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, MountainRangeIndex, ObjectHash};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = MountainRangeIndex::new(name, &fork);
+    /// index.extend(vec![1_u8, 2, 3]);
+    /// let old_hash = index.object_hash();
+    /// index.extend(vec![4_u8, 5]);
+    ///
+    /// let proof = index.get_consistency_proof(3, 5);
+    /// assert!(proof.validate(old_hash, index.object_hash()).is_ok());
+    /// ```
+    pub fn get_consistency_proof(&self, old_len: u64, new_len: u64) -> ConsistencyProof {
+        self.check_len(new_len);
+        if old_len > new_len {
+            panic!(
+                "Illegal lengths: the old len is {} but the new len is {}",
+                old_len, new_len
+            );
+        }
+
+        let mut hashes = Vec::new();
+        for peak in ProofListKey::peaks(new_len) {
+            self.collect_consistency_hashes(peak, old_len, &mut hashes);
+        }
+        ConsistencyProof::new(old_len, new_len, self.peaks_at(old_len), hashes)
+    }
+
+    /// Collects hashes of the nodes under `key` that are not covered by the peaks
+    /// of the range with `old_len` elements.
+    fn collect_consistency_hashes(&self, key: ProofListKey, old_len: u64, hashes: &mut Vec<Hash>) {
+        if key.is_covered_by(old_len) {
+            // The node is one of the old peaks.
+            return;
+        }
+        if key.first_left_leaf_index() >= old_len {
+            hashes.push(self.get_branch_unchecked(key));
+        } else {
+            self.collect_consistency_hashes(key.left(), old_len, hashes);
+            self.collect_consistency_hashes(key.right(), old_len, hashes);
+        }
+    }
+
+    /// Returns an iterator over the list. The iterator element type is V.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, MountainRangeIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let snapshot = db.snapshot();
+    /// let index: MountainRangeIndex<_, u8> = MountainRangeIndex::new(name, &snapshot);
+    ///
+    /// for val in index.iter() {
+    ///     println!("{}", val);
+    /// }
+    /// ```
+    pub fn iter(&self) -> MountainRangeIndexIter<V> {
+        MountainRangeIndexIter {
+            base_iter: self.base.iter(&0_u8),
+        }
+    }
+
+    /// Returns an iterator over the list starting from the specified position. The iterator
+    /// element type is V.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, MountainRangeIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let snapshot = db.snapshot();
+    /// let index: MountainRangeIndex<_, u8> = MountainRangeIndex::new(name, &snapshot);
+    ///
+    /// for val in index.iter_from(1) {
+    ///     println!("{}", val);
+    /// }
+    /// ```
+    pub fn iter_from(&self, from: u64) -> MountainRangeIndexIter<V> {
+        MountainRangeIndexIter {
+            base_iter: self.base.iter_from(&0_u8, &ProofListKey::leaf(from)),
+        }
+    }
+
+    /// Appends an element to the back of the list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, MountainRangeIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = MountainRangeIndex::new(name, &fork);
+    ///
+    /// index.push(1);
+    /// assert!(!index.is_empty());
+    /// ```
+    pub fn push(&mut self, value: V) {
+        let len = self.len();
+        let mut key = ProofListKey::new(1, len);
+        let mut hash = HashTag::hash_leaf(&value.to_bytes());
+        self.base.put(&ProofListKey::leaf(len), value);
+        self.set_branch(key, hash);
+
+        // Merge the new peak with the peaks of the same height.
+        while !key.is_left() {
+            hash = HashTag::hash_node(&self.get_branch_unchecked(key.as_left()), &hash);
+            key = key.parent();
+            self.set_branch(key, hash);
+        }
+        self.state.set(len + 1);
+    }
+
+    /// Extends the list with the contents of an iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, MountainRangeIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = MountainRangeIndex::new(name, &fork);
+    ///
+    /// index.extend([1, 2, 3].iter().cloned());
+    /// assert_eq!(3, index.len());
+    /// ```
+    pub fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = V>,
+    {
+        for value in iter {
+            self.push(value)
+        }
+    }
+}
+
+impl<T, V> ObjectHash for MountainRangeIndex<T, V>
+where
+    T: IndexAccess,
+    V: BinaryValue,
+{
+    /// Returns the hash of the mountain range.
+    ///
+    /// The hash is calculated as follows:
+    /// ```text
+    /// h = sha-256( HashTag::MountainRangeNode || len as u64 || peaks_root )
+    /// ```
+    /// and `peaks_root` is calculated from the peaks of the range ordered from the highest
+    /// one to the lowest one:
+    /// ```text
+    /// peaks_root = hash_node(peak_0, hash_node(peak_1, ... hash_node(peak_k-1, peak_k)))
+    /// ```
+    /// For the empty list, `peaks_root` is equal to `Hash::zero()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, MountainRangeIndex, HashTag, ObjectHash};
+    /// use exonum_crypto::Hash;
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = MountainRangeIndex::new(name, &fork);
+    ///
+    /// let default_hash = index.object_hash();
+    /// assert_eq!(HashTag::hash_mountain_range_node(0, Hash::zero()), default_hash);
+    ///
+    /// index.push(1);
+    /// let hash = index.object_hash();
+    /// assert_ne!(hash, default_hash);
+    /// ```
+    fn object_hash(&self) -> Hash {
+        self.object_hash_at(self.len())
+    }
+}
+
+impl<'a, T, V> ::std::iter::IntoIterator for &'a MountainRangeIndex<T, V>
+where
+    T: IndexAccess,
+    V: BinaryValue,
+{
+    type Item = V;
+    type IntoIter = MountainRangeIndexIter<'a, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, V> Iterator for MountainRangeIndexIter<'a, V>
+where
+    V: BinaryValue,
+{
+    type Item = V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.base_iter.next().map(|(_, v)| v)
+    }
+}
@@ -0,0 +1,248 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde_derive::{Deserialize, Serialize};
+
+use exonum_crypto::Hash;
+
+use crate::{
+    hash::HashTag,
+    proof_list_index::{ConsistencyRestorer, ProofListKey, MAX_LIST_LENGTH},
+    BinaryValue,
+};
+
+/// Computes the root of the peaks ordered from the highest peak to the lowest one.
+pub(super) fn bag_peaks(peaks: &[Hash]) -> Hash {
+    let mut peaks = peaks.iter().rev();
+    let lowest_peak = match peaks.next() {
+        Some(peak) => *peak,
+        None => return Hash::zero(),
+    };
+    peaks.fold(lowest_peak, |root, peak| HashTag::hash_node(peak, &root))
+}
+
+/// An error that is returned when a proof for `MountainRangeIndex` is invalid.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Fail)]
+pub enum MountainRangeProofError {
+    /// The index of the element is not less than the length of the list.
+    #[fail(display = "index of the element is out of bounds")]
+    IndexOutOfBounds,
+    /// The length of the list is too large, or the old length of the list is greater
+    /// than the new length.
+    #[fail(display = "illegal length of the list")]
+    IllegalLength,
+    /// The number of hashes in the proof does not correspond to the length of the list.
+    #[fail(display = "number of hashes does not correspond to the length of the list")]
+    InvalidHashesCount,
+    /// The hash of the proof is not equal to the trusted hash of the list.
+    #[fail(display = "hash of the proof is not equal to the trusted list hash")]
+    UnmatchedRootHash,
+}
+
+/// Proof of inclusion of an element into a `MountainRangeIndex` with the specified length.
+///
+/// The proof consists of the element itself, the hashes of the sibling nodes on the path
+/// from the element to the peak containing it (starting from the bottom), and the hashes
+/// of the other peaks of the range.
+///
+/// # Examples
+///
+/// ```
+/// use exonum_merkledb::{TemporaryDB, Database, MountainRangeIndex, ObjectHash};
+///
+/// let db = TemporaryDB::new();
+/// let fork = db.fork();
+/// let mut index = MountainRangeIndex::new("index", &fork);
+/// index.extend(vec![1_u32, 2, 3, 4, 5]);
+///
+/// let proof = index.get_proof(3);
+/// assert_eq!(proof.length(), 5);
+/// assert_eq!(proof.validate(index.object_hash()).unwrap(), (3, &4));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MountainRangeProof<V> {
+    length: u64,
+    index: u64,
+    value: V,
+    path: Vec<Hash>,
+    peaks: Vec<Hash>,
+}
+
+impl<V> MountainRangeProof<V> {
+    pub(super) fn new(
+        length: u64,
+        index: u64,
+        value: V,
+        path: Vec<Hash>,
+        peaks: Vec<Hash>,
+    ) -> Self {
+        Self {
+            length,
+            index,
+            value,
+            path,
+            peaks,
+        }
+    }
+
+    /// Returns the length of the list for which the proof is created.
+    pub fn length(&self) -> u64 {
+        self.length
+    }
+}
+
+impl<V: BinaryValue> MountainRangeProof<V> {
+    /// Verifies the correctness of the proof by the trusted hash of the list with
+    /// the length specified in the proof.
+    ///
+    /// If the proof is valid, the index of the element and a reference to it are returned.
+    pub fn validate(&self, expected_list_hash: Hash) -> Result<(u64, &V), MountainRangeProofError> {
+        if self.length > MAX_LIST_LENGTH {
+            return Err(MountainRangeProofError::IllegalLength);
+        }
+        if self.index >= self.length {
+            return Err(MountainRangeProofError::IndexOutOfBounds);
+        }
+
+        let peak_keys = ProofListKey::peaks(self.length);
+        if self.peaks.len() + 1 != peak_keys.len() {
+            return Err(MountainRangeProofError::InvalidHashesCount);
+        }
+        let peak_position = peak_keys
+            .iter()
+            .position(|peak| self.index < peak.first_left_leaf_index() + peak.leaves_count())
+            .unwrap();
+        let peak = peak_keys[peak_position];
+        if self.path.len() + 1 != usize::from(peak.height()) {
+            return Err(MountainRangeProofError::InvalidHashesCount);
+        }
+
+        let mut key = ProofListKey::new(1, self.index);
+        let mut hash = HashTag::hash_leaf(&self.value.to_bytes());
+        for sibling in &self.path {
+            hash = if key.is_left() {
+                HashTag::hash_node(&hash, sibling)
+            } else {
+                HashTag::hash_node(sibling, &hash)
+            };
+            key = key.parent();
+        }
+
+        let mut peaks = self.peaks.clone();
+        peaks.insert(peak_position, hash);
+        if HashTag::hash_mountain_range_node(self.length, bag_peaks(&peaks)) != expected_list_hash {
+            return Err(MountainRangeProofError::UnmatchedRootHash);
+        }
+        Ok((self.index, &self.value))
+    }
+}
+
+/// Proof that a `MountainRangeIndex` with the old length is a prefix of the index
+/// with the new length.
+///
+/// The proof consists of the peaks of the range with the old length and the hashes
+/// of the nodes which cover the elements appended after the old length. The verifier
+/// restores the peaks of the range with the new length from these hashes,
+/// so both the old and the new hash of the list are computed from the same data.
+///
+/// # Examples
+///
+/// ```
+/// use exonum_merkledb::{TemporaryDB, Database, MountainRangeIndex, ObjectHash};
+///
+/// let db = TemporaryDB::new();
+/// let fork = db.fork();
+/// let mut index = MountainRangeIndex::new("index", &fork);
+/// index.extend(vec![1_u32, 2, 3]);
+/// let old_hash = index.object_hash();
+/// index.extend(vec![4_u32, 5, 6, 7]);
+///
+/// let proof = index.get_consistency_proof(3, 7);
+/// assert_eq!((proof.old_length(), proof.new_length()), (3, 7));
+/// assert!(proof.validate(old_hash, index.object_hash()).is_ok());
+/// assert!(proof.validate(index.object_hash(), old_hash).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsistencyProof {
+    old_length: u64,
+    new_length: u64,
+    old_peaks: Vec<Hash>,
+    hashes: Vec<Hash>,
+}
+
+impl ConsistencyProof {
+    pub(super) fn new(
+        old_length: u64,
+        new_length: u64,
+        old_peaks: Vec<Hash>,
+        hashes: Vec<Hash>,
+    ) -> Self {
+        Self {
+            old_length,
+            new_length,
+            old_peaks,
+            hashes,
+        }
+    }
+
+    /// Returns the old length of the list.
+    pub fn old_length(&self) -> u64 {
+        self.old_length
+    }
+
+    /// Returns the new length of the list.
+    pub fn new_length(&self) -> u64 {
+        self.new_length
+    }
+
+    /// Verifies the correctness of the proof by the trusted hashes of the list
+    /// with the old and the new length.
+    pub fn validate(
+        &self,
+        expected_old_hash: Hash,
+        expected_new_hash: Hash,
+    ) -> Result<(), MountainRangeProofError> {
+        if self.old_length > self.new_length || self.new_length > MAX_LIST_LENGTH {
+            return Err(MountainRangeProofError::IllegalLength);
+        }
+
+        let mut restorer = ConsistencyRestorer::new(self.old_length, &self.old_peaks, &self.hashes)
+            .ok_or(MountainRangeProofError::InvalidHashesCount)?;
+        let old_hash =
+            HashTag::hash_mountain_range_node(self.old_length, bag_peaks(&self.old_peaks));
+        if old_hash != expected_old_hash {
+            return Err(MountainRangeProofError::UnmatchedRootHash);
+        }
+
+        // Peaks are perfect subtrees, so their nodes are restored the same way as
+        // in a Merkle tree over the list.
+        let new_peaks = ProofListKey::peaks(self.new_length)
+            .into_iter()
+            .map(|key| {
+                restorer
+                    .restore_node(key, self.new_length)
+                    .ok_or(MountainRangeProofError::InvalidHashesCount)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if !restorer.is_exhausted() {
+            return Err(MountainRangeProofError::InvalidHashesCount);
+        }
+
+        let new_hash = HashTag::hash_mountain_range_node(self.new_length, bag_peaks(&new_peaks));
+        if new_hash != expected_new_hash {
+            return Err(MountainRangeProofError::UnmatchedRootHash);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,346 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{from_str, from_value, json, to_string, to_value, Value};
+
+use exonum_crypto::Hash;
+
+use super::{ConsistencyProof, MountainRangeIndex, MountainRangeProof, MountainRangeProofError};
+use crate::{hash::HashTag, BinaryValue, Database, ObjectHash, TemporaryDB};
+
+const IDX_NAME: &str = "idx_name";
+const MAX_LEN: u64 = 33;
+
+/// Modifies the JSON representation of the proof.
+fn tamper<P, F>(proof: &P, modify: F) -> P
+where
+    P: Serialize + DeserializeOwned,
+    F: FnOnce(&mut Value),
+{
+    let mut value = to_value(proof).unwrap();
+    modify(&mut value);
+    from_value(value).unwrap()
+}
+
+/// Computes the hash of a perfect Merkle tree over the specified leaves.
+fn perfect_tree_hash(leaves: &[Hash]) -> Hash {
+    if leaves.len() == 1 {
+        return leaves[0];
+    }
+    let (left, right) = leaves.split_at(leaves.len() / 2);
+    HashTag::hash_node(&perfect_tree_hash(left), &perfect_tree_hash(right))
+}
+
+/// Computes the hash of the mountain range over the specified values from scratch.
+fn mountain_range_hash(values: &[u64]) -> Hash {
+    let leaves: Vec<_> = values
+        .iter()
+        .map(|value| HashTag::hash_leaf(&value.to_bytes()))
+        .collect();
+
+    let mut peaks = Vec::new();
+    let mut rest = leaves.as_slice();
+    while !rest.is_empty() {
+        let size = 1 << (63 - (rest.len() as u64).leading_zeros());
+        let (peak, tail) = rest.split_at(size);
+        peaks.push(perfect_tree_hash(peak));
+        rest = tail;
+    }
+
+    let peaks_root = match peaks.split_last() {
+        None => Hash::zero(),
+        Some((lowest, others)) => others
+            .iter()
+            .rev()
+            .fold(*lowest, |root, peak| HashTag::hash_node(peak, &root)),
+    };
+    HashTag::hash_mountain_range_node(values.len() as u64, peaks_root)
+}
+
+#[test]
+fn test_mountain_range_methods() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut index = MountainRangeIndex::new(IDX_NAME, &fork);
+
+    assert!(index.is_empty());
+    assert_eq!(index.len(), 0);
+    assert_eq!(index.last(), None);
+    assert!(index.peaks().is_empty());
+
+    index.extend(vec![1_u64, 2, 3, 4, 5, 6, 7]);
+    assert_eq!(index.len(), 7);
+    assert_eq!(index.get(0), Some(1));
+    assert_eq!(index.get(6), Some(7));
+    assert_eq!(index.get(7), None);
+    assert_eq!(index.last(), Some(7));
+    assert_eq!(index.peaks().len(), 3);
+    assert_eq!(index.iter().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6, 7]);
+    assert_eq!(index.iter_from(5).collect::<Vec<_>>(), vec![6, 7]);
+
+    index.push(8);
+    assert_eq!(index.peaks().len(), 1);
+}
+
+#[test]
+fn test_object_hash() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut index = MountainRangeIndex::new(IDX_NAME, &fork);
+    assert_eq!(
+        index.object_hash(),
+        HashTag::hash_mountain_range_node(0, Hash::zero())
+    );
+
+    let mut values = Vec::new();
+    let mut hashes = vec![index.object_hash()];
+    for value in 0..MAX_LEN {
+        index.push(value);
+        values.push(value);
+        assert_eq!(index.object_hash(), mountain_range_hash(&values));
+        hashes.push(index.object_hash());
+    }
+
+    for (len, hash) in hashes.iter().enumerate() {
+        assert_eq!(index.object_hash_at(len as u64), *hash);
+    }
+}
+
+#[test]
+fn test_object_hash_persists() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    MountainRangeIndex::new(IDX_NAME, &fork).extend(0_u64..5);
+    db.merge(fork.into_patch()).unwrap();
+
+    let fork = db.fork();
+    MountainRangeIndex::new(IDX_NAME, &fork).extend(5_u64..11);
+    db.merge(fork.into_patch()).unwrap();
+
+    let snapshot = db.snapshot();
+    let index: MountainRangeIndex<_, u64> = MountainRangeIndex::new(IDX_NAME, &snapshot);
+    let values: Vec<_> = (0..11).collect();
+    assert_eq!(index.object_hash(), mountain_range_hash(&values));
+    assert_eq!(index.object_hash_at(5), mountain_range_hash(&values[..5]));
+}
+
+#[test]
+#[should_panic(expected = "Length out of bounds")]
+fn test_object_hash_at_illegal_len() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut index = MountainRangeIndex::new(IDX_NAME, &fork);
+    index.push(1_u64);
+    index.object_hash_at(2);
+}
+
+#[test]
+fn test_inclusion_proofs() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut index = MountainRangeIndex::new(IDX_NAME, &fork);
+    index.extend(0..MAX_LEN);
+
+    for len in 1..=MAX_LEN {
+        let list_hash = index.object_hash_at(len);
+        for i in 0..len {
+            let proof = index.get_proof_at(i, len);
+            assert_eq!(proof.length(), len);
+            assert_eq!(proof.validate(list_hash).unwrap(), (i, &i));
+        }
+    }
+
+    let proof = index.get_proof(10);
+    assert_eq!(proof.validate(index.object_hash()).unwrap(), (10, &10));
+    assert_eq!(
+        proof.validate(index.object_hash_at(20)).unwrap_err(),
+        MountainRangeProofError::UnmatchedRootHash
+    );
+}
+
+#[test]
+#[should_panic(expected = "Index out of bounds")]
+fn test_inclusion_proof_illegal_index() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut index = MountainRangeIndex::new(IDX_NAME, &fork);
+    index.extend(0_u64..5);
+    index.get_proof_at(3, 3);
+}
+
+#[test]
+fn test_invalid_inclusion_proofs() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut index = MountainRangeIndex::new(IDX_NAME, &fork);
+    index.extend(0_u64..11);
+    let list_hash = index.object_hash();
+    let proof = index.get_proof(9);
+
+    let tampered = tamper(&proof, |proof| proof["value"] = json!(100));
+    assert_eq!(
+        tampered.validate(list_hash).unwrap_err(),
+        MountainRangeProofError::UnmatchedRootHash
+    );
+
+    let tampered = tamper(&proof, |proof| proof["index"] = json!(8));
+    assert_eq!(
+        tampered.validate(list_hash).unwrap_err(),
+        MountainRangeProofError::UnmatchedRootHash
+    );
+
+    let tampered = tamper(&proof, |proof| proof["index"] = json!(11));
+    assert_eq!(
+        tampered.validate(list_hash).unwrap_err(),
+        MountainRangeProofError::IndexOutOfBounds
+    );
+
+    let tampered = tamper(&proof, |proof| proof["length"] = json!(12));
+    assert_eq!(
+        tampered.validate(list_hash).unwrap_err(),
+        MountainRangeProofError::InvalidHashesCount
+    );
+
+    let tampered = tamper(&proof, |proof| {
+        proof["path"]
+            .as_array_mut()
+            .unwrap()
+            .push(json!(Hash::zero()))
+    });
+    assert_eq!(
+        tampered.validate(list_hash).unwrap_err(),
+        MountainRangeProofError::InvalidHashesCount
+    );
+
+    let tampered = tamper(&proof, |proof| proof["length"] = json!(u64::max_value()));
+    assert_eq!(
+        tampered.validate(list_hash).unwrap_err(),
+        MountainRangeProofError::IllegalLength
+    );
+}
+
+#[test]
+fn test_consistency_proofs() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut index = MountainRangeIndex::new(IDX_NAME, &fork);
+    index.extend(0..MAX_LEN);
+
+    for new_len in 0..=MAX_LEN {
+        let new_hash = index.object_hash_at(new_len);
+        for old_len in 0..=new_len {
+            let old_hash = index.object_hash_at(old_len);
+            let proof = index.get_consistency_proof(old_len, new_len);
+            proof.validate(old_hash, new_hash).unwrap();
+
+            if old_len < new_len {
+                assert_eq!(
+                    proof.validate(new_hash, old_hash).unwrap_err(),
+                    MountainRangeProofError::UnmatchedRootHash
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_consistency_proof_with_rewritten_history() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut index = MountainRangeIndex::new(IDX_NAME, &fork);
+    index.extend(0_u64..6);
+    let old_hash = index.object_hash();
+
+    let mut other_index = MountainRangeIndex::new("other", &fork);
+    other_index.extend(vec![0_u64, 1, 2, 3, 100, 5, 6, 7, 8]);
+    let proof = other_index.get_consistency_proof(6, 9);
+    assert_eq!(
+        proof
+            .validate(old_hash, other_index.object_hash())
+            .unwrap_err(),
+        MountainRangeProofError::UnmatchedRootHash
+    );
+}
+
+#[test]
+fn test_invalid_consistency_proofs() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut index = MountainRangeIndex::new(IDX_NAME, &fork);
+    index.extend(0_u64..13);
+    let (old_hash, new_hash) = (index.object_hash_at(5), index.object_hash());
+    let proof = index.get_consistency_proof(5, 13);
+
+    let tampered = tamper(&proof, |proof| proof["hashes"][0] = json!(Hash::zero()));
+    assert_eq!(
+        tampered.validate(old_hash, new_hash).unwrap_err(),
+        MountainRangeProofError::UnmatchedRootHash
+    );
+
+    let tampered = tamper(&proof, |proof| {
+        proof["hashes"]
+            .as_array_mut()
+            .unwrap()
+            .push(json!(Hash::zero()))
+    });
+    assert_eq!(
+        tampered.validate(old_hash, new_hash).unwrap_err(),
+        MountainRangeProofError::InvalidHashesCount
+    );
+
+    let tampered = tamper(&proof, |proof| {
+        proof["hashes"].as_array_mut().unwrap().pop();
+    });
+    assert_eq!(
+        tampered.validate(old_hash, new_hash).unwrap_err(),
+        MountainRangeProofError::InvalidHashesCount
+    );
+
+    let tampered = tamper(&proof, |proof| {
+        proof["old_peaks"].as_array_mut().unwrap().pop();
+    });
+    assert_eq!(
+        tampered.validate(old_hash, new_hash).unwrap_err(),
+        MountainRangeProofError::InvalidHashesCount
+    );
+
+    let tampered = tamper(&proof, |proof| proof["old_length"] = json!(14));
+    assert_eq!(
+        tampered.validate(old_hash, new_hash).unwrap_err(),
+        MountainRangeProofError::IllegalLength
+    );
+}
+
+#[test]
+fn test_proofs_serialization() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut index = MountainRangeIndex::new(IDX_NAME, &fork);
+    index.extend(0_u64..7);
+
+    let proof = index.get_proof(4);
+    let json = to_string(&proof).unwrap();
+    let deserialized: MountainRangeProof<u64> = from_str(&json).unwrap();
+    assert_eq!(deserialized, proof);
+    assert_eq!(deserialized.validate(index.object_hash()).unwrap(), (4, &4));
+
+    let proof = index.get_consistency_proof(3, 7);
+    let json = to_string(&proof).unwrap();
+    let deserialized: ConsistencyProof = from_str(&json).unwrap();
+    assert_eq!(deserialized, proof);
+    deserialized
+        .validate(index.object_hash_at(3), index.object_hash())
+        .unwrap();
+}
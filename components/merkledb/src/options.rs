@@ -14,12 +14,39 @@
 
 //! Abstract settings for databases.
 
+use std::{collections::BTreeMap, path::PathBuf};
+
 use serde_derive::{Deserialize, Serialize};
 
 /// Options for the database.
 ///
 /// These parameters apply to the underlying database of Exonum, currently `RocksDB`.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+///
+/// Column family tuning parameters (`compression_type`, `write_buffer_size`,
+/// `block_cache_size` and `bloom_filter_bits`) set in this structure apply to all column
+/// families of the database. They can be overridden for individual column families
+/// in the [`column_families`] section.
+///
+/// # Examples
+///
+/// The options are usually specified in the `database` section of the node configuration:
+///
+/// ```toml
+/// [database]
+/// create_if_missing = true
+/// compression_type = "lz4"
+/// block_cache_size = 268435456
+/// bloom_filter_bits = 10
+/// max_total_wal_size = 1073741824
+/// cache_size = 67108864
+///
+/// [database.column_families.""]
+/// compression_type = "zstd"
+/// write_buffer_size = 134217728
+/// ```
+///
+/// [`column_families`]: #structfield.column_families
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DbOptions {
     /// Number of open files that can be used by the database.
     ///
@@ -40,6 +67,128 @@ pub struct DbOptions {
     ///
     /// Defaults to `true`.
     pub create_if_missing: bool,
+    /// An algorithm used to compress the data blocks of the database.
+    ///
+    /// Defaults to `None`, meaning that the default algorithm of the underlying database
+    /// is used.
+    #[serde(default)]
+    pub compression_type: Option<CompressionType>,
+    /// Amount of data in bytes to build up in memory before converting it to a sorted
+    /// on-disk file.
+    ///
+    /// Larger values increase performance, especially during bulk loads, at the cost
+    /// of memory usage and recovery time.
+    ///
+    /// Defaults to `None`, meaning that the default value of the underlying database is used.
+    #[serde(default)]
+    pub write_buffer_size: Option<usize>,
+    /// Size in bytes of the LRU cache for the uncompressed data blocks.
+    ///
+    /// The cache is created for each column family separately, so the total memory used
+    /// by block caches is up to this size multiplied by the number of column families.
+    /// When setting large caches, consider enabling them only for the column families
+    /// which are read frequently in the [`column_families`] section.
+    ///
+    /// Defaults to `None`, meaning that the default value of the underlying database is used.
+    #[serde(default)]
+    pub block_cache_size: Option<usize>,
+    /// Number of bits per key used by the Bloom filter, which allows to avoid disk reads
+    /// for the missing keys. The value of 10 yields approximately 1% false positive rate.
+    ///
+    /// Defaults to `None`, meaning that Bloom filters are not used.
+    #[serde(default)]
+    pub bloom_filter_bits: Option<i32>,
+    /// Maximal total size in bytes of the write-ahead log files. Once the limit is exceeded,
+    /// the database flushes the column families backed by the oldest live log files.
+    ///
+    /// Defaults to `None`, meaning that the limit is chosen by the underlying database.
+    #[serde(default)]
+    pub max_total_wal_size: Option<u64>,
+    /// Directory to store the write-ahead log files in. Placing the log on a separate
+    /// drive may improve write performance.
+    ///
+    /// Defaults to `None`, meaning that the log is stored in the database directory.
+    #[serde(default)]
+    pub wal_dir: Option<PathBuf>,
+    /// Column family specific tuning parameters, keyed by the column family name.
+    ///
+    /// The parameters set for a column family take precedence over the ones set
+    /// for the whole database.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub column_families: BTreeMap<String, ColumnFamilyOptions>,
+    /// Size in bytes of the in-memory cache for the values read from the database
+    /// (see [`CachedDB`]). The cache is not a part of the underlying database and should be
+    /// applied by the code opening the database.
+    ///
+    /// Defaults to `None`, meaning that the values are not cached.
+    ///
+    /// [`CachedDB`]: struct.CachedDB.html
+    #[serde(default)]
+    pub cache_size: Option<usize>,
+}
+
+/// Tuning parameters of a single column family.
+///
+/// Unset parameters fall back to the values set in [`DbOptions`].
+///
+/// [`DbOptions`]: struct.DbOptions.html
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct ColumnFamilyOptions {
+    /// See [`DbOptions::compression_type`](struct.DbOptions.html#structfield.compression_type).
+    #[serde(default)]
+    pub compression_type: Option<CompressionType>,
+    /// See [`DbOptions::write_buffer_size`](struct.DbOptions.html#structfield.write_buffer_size).
+    #[serde(default)]
+    pub write_buffer_size: Option<usize>,
+    /// See [`DbOptions::block_cache_size`](struct.DbOptions.html#structfield.block_cache_size).
+    #[serde(default)]
+    pub block_cache_size: Option<usize>,
+    /// See [`DbOptions::bloom_filter_bits`](struct.DbOptions.html#structfield.bloom_filter_bits).
+    #[serde(default)]
+    pub bloom_filter_bits: Option<i32>,
+}
+
+/// Algorithms of data compression supported by the database.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionType {
+    /// No compression.
+    None,
+    /// [Snappy](https://github.com/google/snappy) compression.
+    Snappy,
+    /// [LZ4](https://lz4.github.io/lz4/) compression.
+    Lz4,
+    /// LZ4 compression in the high compression mode.
+    Lz4hc,
+    /// [Zstandard](https://facebook.github.io/zstd/) compression.
+    Zstd,
+    /// [zlib](https://zlib.net/) compression.
+    Zlib,
+    /// [bzip2](http://www.bzip.org/) compression.
+    Bz2,
+}
+
+impl DbOptions {
+    /// Returns tuning parameters of the specified column family, taking into account
+    /// both the database-wide parameters and the column family specific overrides.
+    pub fn column_family_options(&self, name: &str) -> ColumnFamilyOptions {
+        let defaults = ColumnFamilyOptions {
+            compression_type: self.compression_type,
+            write_buffer_size: self.write_buffer_size,
+            block_cache_size: self.block_cache_size,
+            bloom_filter_bits: self.bloom_filter_bits,
+        };
+
+        match self.column_families.get(name) {
+            Some(overrides) => ColumnFamilyOptions {
+                compression_type: overrides.compression_type.or(defaults.compression_type),
+                write_buffer_size: overrides.write_buffer_size.or(defaults.write_buffer_size),
+                block_cache_size: overrides.block_cache_size.or(defaults.block_cache_size),
+                bloom_filter_bits: overrides.bloom_filter_bits.or(defaults.bloom_filter_bits),
+            },
+            None => defaults,
+        }
+    }
 }
 
 impl Default for DbOptions {
@@ -47,6 +196,66 @@ impl Default for DbOptions {
         Self {
             max_open_files: None,
             create_if_missing: true,
+            compression_type: None,
+            write_buffer_size: None,
+            block_cache_size: None,
+            bloom_filter_bits: None,
+            max_total_wal_size: None,
+            wal_dir: None,
+            column_families: BTreeMap::new(),
+            cache_size: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ColumnFamilyOptions, CompressionType, DbOptions};
+
+    #[test]
+    fn column_family_overrides() {
+        let mut options = DbOptions::default();
+        options.compression_type = Some(CompressionType::Lz4);
+        options.bloom_filter_bits = Some(10);
+        options.column_families.insert(
+            "cf".to_owned(),
+            ColumnFamilyOptions {
+                compression_type: Some(CompressionType::Zstd),
+                write_buffer_size: Some(1 << 20),
+                ..ColumnFamilyOptions::default()
+            },
+        );
+
+        let cf_options = options.column_family_options("cf");
+        assert_eq!(cf_options.compression_type, Some(CompressionType::Zstd));
+        assert_eq!(cf_options.write_buffer_size, Some(1 << 20));
+        assert_eq!(cf_options.bloom_filter_bits, Some(10));
+        assert_eq!(cf_options.block_cache_size, None);
+
+        let other_options = options.column_family_options("other");
+        assert_eq!(other_options.compression_type, Some(CompressionType::Lz4));
+        assert_eq!(other_options.write_buffer_size, None);
+    }
+
+    #[test]
+    fn options_deserialization() {
+        let options: DbOptions = serde_json::from_str(
+            r#"{
+                "max_open_files": null,
+                "create_if_missing": true,
+                "compression_type": "snappy",
+                "max_total_wal_size": 1024,
+                "column_families": { "": { "compression_type": "none" } }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(options.compression_type, Some(CompressionType::Snappy));
+        assert_eq!(options.max_total_wal_size, Some(1024));
+        assert_eq!(
+            options.column_family_options("").compression_type,
+            Some(CompressionType::None)
+        );
+        assert_eq!(options.write_buffer_size, None);
+    }
+}
@@ -0,0 +1,759 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An implementation of a Merkelized set for items that utilize the `BinaryKey` trait.
+//!
+//! `ProofKeySetIndex` implements a set that stores elements in a binary Merkle Patricia tree,
+//! similar to `ProofMapIndex` with empty values. The given section contains information
+//! on the methods related to `ProofKeySetIndex`, the iterator over the items of this set
+//! and the membership proofs.
+
+use serde_derive::{Deserialize, Serialize};
+
+use std::{borrow::Cow, fmt, ops::RangeBounds};
+
+use exonum_crypto::Hash;
+
+use crate::{
+    proof_map_index::{CheckedMapProof, MapProofError, ProofMapIndexIter},
+    views::{AnyObject, IndexAccess, IndexAddress, IndexBuilder, IndexType, View},
+    BinaryKey, BinaryValue, MapProof, ObjectHash, ProofMapIndex,
+};
+
+/// A Merkelized set of key items.
+///
+/// `ProofKeySetIndex` implements a set that stores the elements in a binary Merkle Patricia
+/// tree, so that it can provide proofs of membership and non-membership for the elements.
+/// `ProofKeySetIndex` requires that elements implement the [`BinaryKey`] and [`ObjectHash`]
+/// traits.
+///
+/// [`BinaryKey`]: ../trait.BinaryKey.html
+/// [`ObjectHash`]: ../trait.ObjectHash.html
+pub struct ProofKeySetIndex<T: IndexAccess, K> {
+    map: ProofMapIndex<T, K, ()>,
+}
+
+/// An iterator over the items of a `ProofKeySetIndex`.
+///
+/// This struct is created by the [`iter`], [`iter_from`], [`iter_rev`], [`range`]
+/// or [`range_rev`] method on [`ProofKeySetIndex`]. See its documentation for details.
+///
+/// [`iter`]: struct.ProofKeySetIndex.html#method.iter
+/// [`iter_from`]: struct.ProofKeySetIndex.html#method.iter_from
+/// [`iter_rev`]: struct.ProofKeySetIndex.html#method.iter_rev
+/// [`range`]: struct.ProofKeySetIndex.html#method.range
+/// [`range_rev`]: struct.ProofKeySetIndex.html#method.range_rev
+/// [`ProofKeySetIndex`]: struct.ProofKeySetIndex.html
+#[derive(Debug)]
+pub struct ProofKeySetIndexIter<'a, K> {
+    base_iter: ProofMapIndexIter<'a, K, ()>,
+}
+
+/// Proof of membership and non-membership for the items of a `ProofKeySetIndex`,
+/// which allows restoring the `object_hash()` of the set.
+///
+/// # Workflow
+///
+/// Proofs can be created with [`get_proof()`] and [`get_multiproof()`] methods
+/// of `ProofKeySetIndex` and verified with [`check()`].
+///
+/// ```
+/// # use exonum_merkledb::{Database, TemporaryDB, ObjectHash};
+/// # use exonum_merkledb::proof_key_set_index::ProofKeySetIndex;
+/// # use exonum_crypto::hash;
+/// let fork = { let db = TemporaryDB::new(); db.fork() };
+/// let mut set = ProofKeySetIndex::new("index", &fork);
+/// let (h1, h2, h3) = (hash(&[1]), hash(&[2]), hash(&[3]));
+/// set.insert(h1);
+/// set.insert(h2);
+///
+/// let proof = set.get_multiproof(vec![h1, h3]);
+/// let checked_proof = proof.check().unwrap();
+/// assert_eq!(checked_proof.present_items().collect::<Vec<_>>(), vec![&h1]);
+/// assert_eq!(checked_proof.missing_items().collect::<Vec<_>>(), vec![&h3]);
+/// assert_eq!(checked_proof.root_hash(), set.object_hash());
+/// ```
+///
+/// # Serialization
+///
+/// `SetProof` has the same JSON and binary representations as a [`MapProof`] with empty
+/// values; the binary representation is provided by the [`BinaryValue`] implementation.
+///
+/// [`get_proof()`]: struct.ProofKeySetIndex.html#method.get_proof
+/// [`get_multiproof()`]: struct.ProofKeySetIndex.html#method.get_multiproof
+/// [`check()`]: #method.check
+/// [`MapProof`]: ../proof_map_index/struct.MapProof.html#json-serialization
+/// [`BinaryValue`]: ../trait.BinaryValue.html
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SetProof<K> {
+    inner: MapProof<K, ()>,
+}
+
+/// Version of `SetProof` obtained after verification.
+///
+/// See [`SetProof`] for an example of usage.
+///
+/// [`SetProof`]: struct.SetProof.html#workflow
+#[derive(Debug)]
+pub struct CheckedSetProof<K> {
+    inner: CheckedMapProof<K, ()>,
+}
+
+impl<T, K> AnyObject<T> for ProofKeySetIndex<T, K>
+where
+    T: IndexAccess,
+    K: BinaryKey + ObjectHash,
+{
+    fn view(self) -> View<T> {
+        self.map.view()
+    }
+
+    fn object_type(&self) -> IndexType {
+        IndexType::ProofKeySet
+    }
+
+    fn metadata(&self) -> Vec<u8> {
+        self.map.metadata()
+    }
+}
+
+impl<T, K> ProofKeySetIndex<T, K>
+where
+    T: IndexAccess,
+    K: BinaryKey + ObjectHash,
+{
+    /// Creates a new index representation based on the name and storage view.
+    ///
+    /// Storage view can be specified as [`&Snapshot`] or [`&mut Fork`]. In the first case, only
+    /// immutable methods are available. In the second case, both immutable and mutable methods are
+    /// available.
+    ///
+    /// [`&Snapshot`]: ../trait.Snapshot.html
+    /// [`&mut Fork`]: ../struct.Fork.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database};
+    /// use exonum_merkledb::proof_key_set_index::ProofKeySetIndex;
+    /// use exonum_crypto::Hash;
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let snapshot = db.snapshot();
+    /// let index: ProofKeySetIndex<_, Hash> = ProofKeySetIndex::new(name, &snapshot);
+    ///
+    /// let fork = db.fork();
+    /// let mut mut_index: ProofKeySetIndex<_, Hash> = ProofKeySetIndex::new(name, &fork);
+    /// ```
+    pub fn new<S: Into<String>>(index_name: S, view: T) -> Self {
+        let builder = IndexBuilder::new(view)
+            .index_type(IndexType::ProofKeySet)
+            .index_name(index_name);
+        Self {
+            map: ProofMapIndex::from_builder(builder),
+        }
+    }
+
+    /// Creates a new index representation based on the name, common prefix of its keys
+    /// and storage view.
+    ///
+    /// Storage view can be specified as [`&Snapshot`] or [`&mut Fork`]. In the first case, only
+    /// immutable methods are available. In the second case, both immutable and mutable methods are
+    /// available.
+    ///
+    /// [`&Snapshot`]: ../trait.Snapshot.html
+    /// [`&mut Fork`]: ../struct.Fork.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database};
+    /// use exonum_merkledb::proof_key_set_index::ProofKeySetIndex;
+    /// use exonum_crypto::Hash;
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let index_id = vec![01];
+    ///
+    /// let snapshot = db.snapshot();
+    /// let index: ProofKeySetIndex<_, Hash> =
+    ///     ProofKeySetIndex::new_in_family(name, &index_id, &snapshot);
+    /// ```
+    pub fn new_in_family<S, I>(family_name: S, index_id: &I, view: T) -> Self
+    where
+        I: BinaryKey,
+        I: ?Sized,
+        S: Into<String>,
+    {
+        let builder = IndexBuilder::new(view)
+            .index_type(IndexType::ProofKeySet)
+            .index_name(family_name)
+            .family_id(index_id);
+        Self {
+            map: ProofMapIndex::from_builder(builder),
+        }
+    }
+
+    pub fn get_from<I: Into<IndexAddress>>(address: I, access: T) -> Option<Self> {
+        let builder =
+            IndexBuilder::from_address(address, access).index_type(IndexType::ProofKeySet);
+        ProofMapIndex::from_builder_existed(builder).map(|map| Self { map })
+    }
+
+    pub fn create_from<I: Into<IndexAddress>>(address: I, access: T) -> Self {
+        let builder =
+            IndexBuilder::from_address(address, access).index_type(IndexType::ProofKeySet);
+        Self {
+            map: ProofMapIndex::from_builder(builder),
+        }
+    }
+
+    /// Returns `true` if the set contains the indicated item.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database};
+    /// use exonum_merkledb::proof_key_set_index::ProofKeySetIndex;
+    /// use exonum_crypto::Hash;
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = ProofKeySetIndex::new(name, &fork);
+    /// assert!(!index.contains(&Hash::default()));
+    ///
+    /// index.insert(Hash::default());
+    /// assert!(index.contains(&Hash::default()));
+    /// ```
+    pub fn contains(&self, item: &K) -> bool {
+        self.map.contains(item)
+    }
+
+    /// Returns the proof of membership or non-membership for the specified item.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database};
+    /// use exonum_merkledb::proof_key_set_index::ProofKeySetIndex;
+    /// use exonum_crypto::Hash;
+    ///
+    /// let db = TemporaryDB::new();
+    /// let snapshot = db.snapshot();
+    /// let index: ProofKeySetIndex<_, Hash> = ProofKeySetIndex::new("index", &snapshot);
+    ///
+    /// let proof = index.get_proof(Hash::default());
+    /// ```
+    pub fn get_proof(&self, item: K) -> SetProof<K> {
+        SetProof {
+            inner: self.map.get_proof(item),
+        }
+    }
+
+    /// Returns the combined proof of membership or non-membership for the multiple
+    /// specified items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database};
+    /// use exonum_merkledb::proof_key_set_index::ProofKeySetIndex;
+    ///
+    /// let db = TemporaryDB::new();
+    /// let snapshot = db.snapshot();
+    /// let index: ProofKeySetIndex<_, Vec<u8>> = ProofKeySetIndex::new("index", &snapshot);
+    ///
+    /// let proof = index.get_multiproof(vec![vec![0; 32], vec![1; 32]]);
+    /// ```
+    pub fn get_multiproof<KI>(&self, items: KI) -> SetProof<K>
+    where
+        KI: IntoIterator<Item = K>,
+    {
+        SetProof {
+            inner: self.map.get_multiproof(items),
+        }
+    }
+
+    /// Returns an iterator visiting all items of the set in ascending order of their
+    /// binary representation. The iterator element type is `K::Owned`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database};
+    /// use exonum_merkledb::proof_key_set_index::ProofKeySetIndex;
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let snapshot = db.snapshot();
+    /// let index: ProofKeySetIndex<_, u8> = ProofKeySetIndex::new(name, &snapshot);
+    ///
+    /// for item in index.iter() {
+    ///     println!("{}", item);
+    /// }
+    /// ```
+    pub fn iter(&self) -> ProofKeySetIndexIter<K> {
+        ProofKeySetIndexIter {
+            base_iter: self.map.iter(),
+        }
+    }
+
+    /// Returns an iterator visiting all items of the set in ascending order of their
+    /// binary representation starting from the specified item. The iterator element type
+    /// is `K::Owned`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database};
+    /// use exonum_merkledb::proof_key_set_index::ProofKeySetIndex;
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let snapshot = db.snapshot();
+    /// let index: ProofKeySetIndex<_, u8> = ProofKeySetIndex::new(name, &snapshot);
+    ///
+    /// for item in index.iter_from(&2) {
+    ///     println!("{}", item);
+    /// }
+    /// ```
+    pub fn iter_from(&self, from: &K) -> ProofKeySetIndexIter<K> {
+        ProofKeySetIndexIter {
+            base_iter: self.map.iter_from(from),
+        }
+    }
+
+    /// Returns an iterator visiting all items of the set in descending order of their
+    /// binary representation. The iterator element type is `K::Owned`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database};
+    /// use exonum_merkledb::proof_key_set_index::ProofKeySetIndex;
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = ProofKeySetIndex::new(name, &fork);
+    /// index.insert(1_u8);
+    /// index.insert(2_u8);
+    ///
+    /// assert_eq!(index.iter_rev().collect::<Vec<_>>(), vec![2, 1]);
+    /// ```
+    pub fn iter_rev(&self) -> ProofKeySetIndexIter<K> {
+        ProofKeySetIndexIter {
+            base_iter: self.map.iter_rev(),
+        }
+    }
+
+    /// Returns an iterator visiting items of the set within the specified range in ascending
+    /// order of their binary representation. The iterator element type is `K::Owned`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database};
+    /// use exonum_merkledb::proof_key_set_index::ProofKeySetIndex;
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = ProofKeySetIndex::new(name, &fork);
+    /// for i in 1_u8..5 {
+    ///     index.insert(i);
+    /// }
+    ///
+    /// assert_eq!(index.range(2_u8..4).collect::<Vec<_>>(), vec![2, 3]);
+    /// ```
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> ProofKeySetIndexIter<K> {
+        ProofKeySetIndexIter {
+            base_iter: self.map.range(range),
+        }
+    }
+
+    /// Returns an iterator visiting items of the set within the specified range in descending
+    /// order of their binary representation. The iterator element type is `K::Owned`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database};
+    /// use exonum_merkledb::proof_key_set_index::ProofKeySetIndex;
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = ProofKeySetIndex::new(name, &fork);
+    /// for i in 1_u8..5 {
+    ///     index.insert(i);
+    /// }
+    ///
+    /// assert_eq!(index.range_rev(2_u8..).collect::<Vec<_>>(), vec![4, 3, 2]);
+    /// ```
+    pub fn range_rev<R: RangeBounds<K>>(&self, range: R) -> ProofKeySetIndexIter<K> {
+        ProofKeySetIndexIter {
+            base_iter: self.map.range_rev(range),
+        }
+    }
+
+    /// Adds an item to the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database};
+    /// use exonum_merkledb::proof_key_set_index::ProofKeySetIndex;
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = ProofKeySetIndex::new(name, &fork);
+    ///
+    /// index.insert(1);
+    /// assert!(index.contains(&1));
+    /// ```
+    #[cfg_attr(feature = "cargo-clippy", allow(clippy::needless_pass_by_value))]
+    pub fn insert(&mut self, item: K) {
+        self.map.put(&item, ())
+    }
+
+    /// Removes an item from the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database};
+    /// use exonum_merkledb::proof_key_set_index::ProofKeySetIndex;
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = ProofKeySetIndex::new(name, &fork);
+    ///
+    /// index.insert(1);
+    /// assert!(index.contains(&1));
+    ///
+    /// index.remove(&1);
+    /// assert!(!index.contains(&1));
+    /// ```
+    pub fn remove(&mut self, item: &K) {
+        self.map.remove(item)
+    }
+
+    /// Clears the set, removing all items.
+    ///
+    /// # Notes
+    ///
+    /// Currently, this method is not optimized to delete a large set of data. During the execution of
+    /// this method, the amount of allocated memory is linearly dependent on the number of elements
+    /// in the index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database};
+    /// use exonum_merkledb::proof_key_set_index::ProofKeySetIndex;
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = ProofKeySetIndex::new(name, &fork);
+    ///
+    /// index.insert(1);
+    /// assert!(index.contains(&1));
+    ///
+    /// index.clear();
+    /// assert!(!index.contains(&1));
+    /// ```
+    pub fn clear(&mut self) {
+        self.map.clear()
+    }
+
+    /// Returns the number of items in the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database};
+    /// use exonum_merkledb::proof_key_set_index::ProofKeySetIndex;
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = ProofKeySetIndex::new("index", &fork);
+    /// assert_eq!(0, index.len());
+    ///
+    /// index.insert(1);
+    /// assert_eq!(1, index.len());
+    /// ```
+    pub fn len(&self) -> u64 {
+        self.map.len()
+    }
+
+    /// Returns `true` if the set contains no items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database};
+    /// use exonum_merkledb::proof_key_set_index::ProofKeySetIndex;
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = ProofKeySetIndex::new("index", &fork);
+    /// assert!(index.is_empty());
+    ///
+    /// index.insert(1);
+    /// assert!(!index.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+impl<T, K> ObjectHash for ProofKeySetIndex<T, K>
+where
+    T: IndexAccess,
+    K: BinaryKey + ObjectHash,
+{
+    /// Returns the hash of the set, which is calculated in the same way as the hash
+    /// of a `ProofMapIndex` with empty values. For hash of the empty set
+    /// see [`HashTag::empty_map_hash`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, HashTag, ObjectHash};
+    /// use exonum_merkledb::proof_key_set_index::ProofKeySetIndex;
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = ProofKeySetIndex::new("index", &fork);
+    ///
+    /// let default_hash = index.object_hash();
+    /// assert_eq!(HashTag::empty_map_hash(), default_hash);
+    ///
+    /// index.insert(1);
+    /// assert_ne!(index.object_hash(), default_hash);
+    /// ```
+    ///
+    /// [`HashTag::empty_map_hash`]: ../enum.HashTag.html#method.empty_map_hash
+    fn object_hash(&self) -> Hash {
+        self.map.object_hash()
+    }
+}
+
+impl<'a, T, K> ::std::iter::IntoIterator for &'a ProofKeySetIndex<T, K>
+where
+    T: IndexAccess,
+    K: BinaryKey + ObjectHash,
+{
+    type Item = K::Owned;
+    type IntoIter = ProofKeySetIndexIter<'a, K>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K> Iterator for ProofKeySetIndexIter<'a, K>
+where
+    K: BinaryKey,
+{
+    type Item = K::Owned;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.base_iter.next().map(|(key, _)| key)
+    }
+}
+
+impl<T, K> fmt::Debug for ProofKeySetIndex<T, K>
+where
+    T: IndexAccess,
+    K: BinaryKey + ObjectHash,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ProofKeySetIndex")
+            .field("map", &self.map)
+            .finish()
+    }
+}
+
+impl<K> SetProof<K> {
+    /// Retrieves references to items that the proof shows as present in the set.
+    /// This method does not perform any integrity checks of the proof.
+    pub fn present_items_unchecked(&self) -> impl Iterator<Item = &K> {
+        self.inner
+            .all_entries_unchecked()
+            .filter_map(|(item, value)| value.map(|_| item))
+    }
+
+    /// Retrieves references to items that the proof shows as missing from the set.
+    /// This method does not perform any integrity checks of the proof.
+    pub fn missing_items_unchecked(&self) -> impl Iterator<Item = &K> {
+        self.inner.missing_keys_unchecked()
+    }
+}
+
+impl<K> SetProof<K>
+where
+    K: BinaryKey + ObjectHash,
+{
+    /// Consumes this proof producing a `CheckedSetProof` structure.
+    /// Fails if the proof is malformed.
+    pub fn check(self) -> Result<CheckedSetProof<K>, MapProofError> {
+        self.inner.check().map(|inner| CheckedSetProof { inner })
+    }
+}
+
+impl<K> BinaryValue for SetProof<K>
+where
+    K: BinaryKey + ToOwned<Owned = K>,
+{
+    fn to_bytes(&self) -> Vec<u8> {
+        self.inner.to_bytes()
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Result<Self, failure::Error> {
+        MapProof::from_bytes(bytes).map(|inner| Self { inner })
+    }
+}
+
+impl<K> CheckedSetProof<K> {
+    /// Retrieves references to items that the proof shows as present in the set.
+    pub fn present_items(&self) -> impl Iterator<Item = &K> {
+        self.inner.entries().map(|(item, _)| item)
+    }
+
+    /// Retrieves references to items that the proof shows as missing from the set.
+    pub fn missing_items(&self) -> impl Iterator<Item = &K> {
+        self.inner.missing_keys()
+    }
+
+    /// Returns a hash of the set that this proof is constructed for.
+    pub fn root_hash(&self) -> Hash {
+        self.inner.root_hash()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use exonum_crypto::{hash, Hash, HASH_SIZE};
+
+    use super::{ProofKeySetIndex, SetProof};
+    use crate::{BinaryValue, Database, ObjectHash, ProofMapIndex, TemporaryDB};
+
+    #[test]
+    fn proof_key_set_methods() {
+        let db = TemporaryDB::new();
+        let fork = db.fork();
+        let mut set = ProofKeySetIndex::new("set", &fork);
+        assert!(set.is_empty());
+
+        set.insert(1_u32);
+        set.insert(3);
+        set.insert(3);
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&1));
+        assert!(!set.contains(&2));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(set.iter_from(&2).collect::<Vec<_>>(), vec![3]);
+
+        set.remove(&1);
+        assert!(!set.contains(&1));
+        assert_eq!(set.len(), 1);
+
+        set.clear();
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn proof_key_set_proofs() {
+        let db = TemporaryDB::new();
+        let fork = db.fork();
+        let items = (0_u8..20).map(|i| hash(&[i])).collect::<Vec<_>>();
+        {
+            let mut set = ProofKeySetIndex::new("set", &fork);
+            for item in &items {
+                set.insert(*item);
+            }
+        }
+        let set = ProofKeySetIndex::new("set", &fork);
+
+        for item in &items {
+            let checked_proof = set.get_proof(*item).check().unwrap();
+            assert_eq!(checked_proof.root_hash(), set.object_hash());
+            assert_eq!(
+                checked_proof.present_items().collect::<Vec<_>>(),
+                vec![item]
+            );
+            assert_eq!(checked_proof.missing_items().count(), 0);
+        }
+
+        let missing_item = hash(&[100]);
+        let proof = set.get_multiproof(vec![items[0], missing_item]);
+        assert_eq!(
+            proof.present_items_unchecked().collect::<Vec<_>>(),
+            vec![&items[0]]
+        );
+        let restored_proof = SetProof::from_bytes(proof.to_bytes().into()).unwrap();
+        let checked_proof = restored_proof.check().unwrap();
+        assert_eq!(checked_proof.root_hash(), set.object_hash());
+        assert_eq!(
+            checked_proof.present_items().collect::<Vec<_>>(),
+            vec![&items[0]]
+        );
+        assert_eq!(
+            checked_proof.missing_items().collect::<Vec<_>>(),
+            vec![&missing_item]
+        );
+
+        // The set hash is the same as the hash of a map with empty values.
+        let mut map = ProofMapIndex::new("map", &fork);
+        for item in &items {
+            map.put(item, ());
+        }
+        assert_eq!(map.object_hash(), set.object_hash());
+    }
+
+    #[test]
+    fn binary_set_proof_with_truncated_key() {
+        let db = TemporaryDB::new();
+        let fork = db.fork();
+        let set: ProofKeySetIndex<_, Hash> = ProofKeySetIndex::new("set", &fork);
+        // The proof for an empty set consists of a single missing item.
+        let bytes = set.get_proof(hash(&[1])).to_bytes();
+        assert_eq!(bytes[4] as usize, HASH_SIZE);
+
+        let mut truncated_bytes = bytes[..4].to_vec();
+        truncated_bytes.push(3);
+        truncated_bytes.extend_from_slice(&bytes[5..8]);
+        assert!(SetProof::<Hash>::from_bytes(truncated_bytes.into()).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "Index type doesn't match specified")]
+    fn proof_key_set_type_is_checked() {
+        let db = TemporaryDB::new();
+        let fork = db.fork();
+        {
+            let mut map = ProofMapIndex::new("index", &fork);
+            map.put(&1_u32, ());
+        }
+        let _set: ProofKeySetIndex<_, u32> = ProofKeySetIndex::new("index", &fork);
+    }
+}
@@ -16,8 +16,10 @@ use super::super::BinaryKey;
 
 const HEIGHT_SHIFT: u64 = 56;
 const MAX_INDEX: u64 = 0xFF_FFFF_FFFF_FFFF; // 2_u64.pow(56) - 1
+/// Maximum length of a list addressed by `ProofListKey`s.
+pub const MAX_LIST_LENGTH: u64 = MAX_INDEX + 1;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct ProofListKey {
     index: u64,
     height: u8,
@@ -41,6 +43,23 @@ impl ProofListKey {
         Self::new(0, index)
     }
 
+    /// Returns the keys of the maximal perfect subtrees (peaks) covering the first `len`
+    /// leaves, from the leftmost (and highest) peak to the rightmost one.
+    pub fn peaks(len: u64) -> Vec<Self> {
+        debug_assert!(len <= MAX_LIST_LENGTH);
+
+        let mut keys = Vec::new();
+        let mut first_leaf = 0;
+        for height in (0..64).rev() {
+            let size = 1_u64 << height;
+            if len & size != 0 {
+                keys.push(Self::new(height + 1, first_leaf >> height));
+                first_leaf += size;
+            }
+        }
+        keys
+    }
+
     pub fn as_db_key(&self) -> u64 {
         (u64::from(self.height) << HEIGHT_SHIFT) + self.index
     }
@@ -69,6 +88,19 @@ impl ProofListKey {
         }
     }
 
+    /// Returns the number of leaves in the subtree with the root at this key.
+    pub fn leaves_count(&self) -> u64 {
+        debug_assert!(self.height > 0);
+
+        1 << (self.height - 1)
+    }
+
+    /// Returns `true` if all leaves of the subtree with the root at this key are among
+    /// the first `len` leaves.
+    pub fn is_covered_by(&self, len: u64) -> bool {
+        self.first_left_leaf_index() + self.leaves_count() <= len
+    }
+
     pub fn first_right_leaf_index(&self) -> u64 {
         if self.height < 2 {
             self.index
@@ -14,7 +14,10 @@
 
 //! An implementation of a Merkelized version of an array list (Merkle tree).
 
-pub use self::proof::{ListProof, ListProofError};
+pub use self::proof::{ListConsistencyProof, ListProof, ListProofError};
+
+pub(crate) use self::key::{ProofListKey, MAX_LIST_LENGTH};
+pub(crate) use self::proof::ConsistencyRestorer;
 
 use std::{
     marker::PhantomData,
@@ -23,7 +26,7 @@ use std::{
 
 use exonum_crypto::Hash;
 
-use self::{key::ProofListKey, proof::ProofOfAbsence};
+use self::proof::ProofOfAbsence;
 use crate::views::IndexAddress;
 use crate::{
     hash::HashTag,
@@ -43,7 +46,12 @@ mod tests;
 /// `ProofListIndex` implements a Merkle tree, storing elements as leaves and using `u64` as
 /// an index. `ProofListIndex` requires that elements implement the [`BinaryValue`] trait.
 ///
+/// Elements can be removed from the list with the [`remove`] method, which leaves
+/// a hole at the position of the element; the list provides proofs of absence
+/// for such positions.
+///
 /// [`BinaryValue`]: ../trait.BinaryValue.html
+/// [`remove`]: #method.remove
 #[derive(Debug)]
 pub struct ProofListIndex<T: IndexAccess, V> {
     base: View<T>,
@@ -53,11 +61,14 @@ pub struct ProofListIndex<T: IndexAccess, V> {
 
 /// An iterator over the items of a `ProofListIndex`.
 ///
-/// This struct is created by the [`iter`] or
-/// [`iter_from`] method on [`ProofListIndex`]. See its documentation for details.
+/// This struct is created by the [`iter`], [`iter_from`], [`iter_rev`], [`range`]
+/// or [`range_rev`] method on [`ProofListIndex`]. See its documentation for details.
 ///
 /// [`iter`]: struct.ProofListIndex.html#method.iter
 /// [`iter_from`]: struct.ProofListIndex.html#method.iter_from
+/// [`iter_rev`]: struct.ProofListIndex.html#method.iter_rev
+/// [`range`]: struct.ProofListIndex.html#method.range
+/// [`range_rev`]: struct.ProofListIndex.html#method.range_rev
 /// [`ProofListIndex`]: struct.ProofListIndex.html
 #[derive(Debug)]
 pub struct ProofListIndexIter<'a, V> {
@@ -216,7 +227,10 @@ where
 
     fn construct_proof(&self, key: ProofListKey, from: u64, to: u64) -> ListProof<V> {
         if key.height() == 1 {
-            return ListProof::Leaf(self.get(key.index()).unwrap());
+            return match self.get(key.index()) {
+                Some(value) => ListProof::Leaf(value),
+                None => ListProof::Removed,
+            };
         }
         let middle = key.first_right_leaf_index();
         if to <= middle {
@@ -251,6 +265,26 @@ where
         self.base.put(&key, hash)
     }
 
+    /// Sets the hash of the leaf at the specified position and updates the hashes
+    /// of the branches above it.
+    fn set_leaf_hash(&mut self, index: u64, hash: Hash) {
+        let mut key = ProofListKey::new(1, index);
+        self.set_branch(key, hash);
+        while key.height() < self.height() {
+            let (left, right) = (key.as_left(), key.as_right());
+            let hash = if self.has_branch(right) {
+                HashTag::hash_node(
+                    &self.get_branch_unchecked(left),
+                    &self.get_branch_unchecked(right),
+                )
+            } else {
+                HashTag::hash_single_node(&self.get_branch_unchecked(left))
+            };
+            key = key.parent();
+            self.set_branch(key, hash);
+        }
+    }
+
     /// Returns the element at the indicated position or `None` if the indicated position
     /// is out of bounds.
     ///
@@ -438,6 +472,72 @@ where
         }
     }
 
+    /// Returns a proof that the list with `old_len` elements is a prefix of the current list.
+    ///
+    /// The proof can be checked against the hash of the list with `old_len` elements
+    /// and the current [`object_hash()`] of the list. The proof is valid only if elements
+    /// with indices less than `old_len` have not been changed or removed since the list
+    /// had `old_len` elements.
+    ///
+    /// [`object_hash()`]: #method.object_hash
+    ///
+    /// # Panics
+    ///
+    /// Panics if `old_len` is greater than the current length of the list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, ProofListIndex, ObjectHash};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = ProofListIndex::new(name, &fork);
+    /// index.extend(vec![1_u8, 2, 3]);
+    /// let old_hash = index.object_hash();
+    ///
+    /// index.extend(vec![4_u8, 5]);
+    /// let proof = index.get_consistency_proof(3);
+    /// assert!(proof.validate(old_hash, index.object_hash()).is_ok());
+    /// ```
+    pub fn get_consistency_proof(&self, old_len: u64) -> ListConsistencyProof {
+        if old_len > self.len() {
+            panic!(
+                "Illegal lengths: the len is {} but the old len is {}",
+                self.len(),
+                old_len
+            );
+        }
+
+        let old_peaks = ProofListKey::peaks(old_len)
+            .into_iter()
+            .map(|key| self.get_branch_unchecked(key))
+            .collect();
+        let mut hashes = Vec::new();
+        if !self.is_empty() {
+            self.collect_consistency_hashes(self.root_key(), old_len, &mut hashes);
+        }
+        ListConsistencyProof::new(old_len, self.len(), old_peaks, hashes)
+    }
+
+    /// Collects hashes of the nodes under `key` that are not covered by the peaks
+    /// of the list with `old_len` elements.
+    fn collect_consistency_hashes(&self, key: ProofListKey, old_len: u64, hashes: &mut Vec<Hash>) {
+        if key.is_covered_by(old_len) {
+            // The node is one of the old peaks.
+            return;
+        }
+        if key.first_left_leaf_index() >= old_len {
+            hashes.push(self.get_branch_unchecked(key));
+        } else {
+            self.collect_consistency_hashes(key.left(), old_len, hashes);
+            if self.has_branch(key.right()) {
+                self.collect_consistency_hashes(key.right(), old_len, hashes);
+            }
+        }
+    }
+
     /// Returns an iterator over the list. The iterator element type is V.
     ///
     /// # Examples
@@ -483,6 +583,72 @@ where
         }
     }
 
+    /// Returns an iterator over the list in reverse order, i.e., starting from the last
+    /// element. The iterator element type is V.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = ProofListIndex::new(name, &fork);
+    /// index.extend(vec![1_u8, 2, 3]);
+    ///
+    /// assert_eq!(index.iter_rev().collect::<Vec<_>>(), vec![3, 2, 1]);
+    /// ```
+    pub fn iter_rev(&self) -> ProofListIndexIter<V> {
+        ProofListIndexIter {
+            base_iter: self.base.iter_rev(&0_u8),
+        }
+    }
+
+    /// Returns an iterator over the elements of the list with indices within the specified
+    /// range. The iterator element type is V.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = ProofListIndex::new(name, &fork);
+    /// index.extend(vec![1_u8, 2, 3, 4]);
+    ///
+    /// assert_eq!(index.range(1..3).collect::<Vec<_>>(), vec![2, 3]);
+    /// ```
+    pub fn range<R: RangeBounds<u64>>(&self, range: R) -> ProofListIndexIter<V> {
+        ProofListIndexIter {
+            base_iter: self.base.range(&0_u8, leaf_range(&range)),
+        }
+    }
+
+    /// Returns an iterator over the elements of the list with indices within the specified
+    /// range in reverse order. The iterator element type is V.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = ProofListIndex::new(name, &fork);
+    /// index.extend(vec![1_u8, 2, 3, 4]);
+    ///
+    /// assert_eq!(index.range_rev(1..).collect::<Vec<_>>(), vec![4, 3, 2]);
+    /// ```
+    pub fn range_rev<R: RangeBounds<u64>>(&self, range: R) -> ProofListIndexIter<V> {
+        ProofListIndexIter {
+            base_iter: self.base.range_rev(&0_u8, leaf_range(&range)),
+        }
+    }
+
     /// Appends an element to the back of the proof list.
     ///
     /// # Examples
@@ -573,22 +739,45 @@ where
                 index
             );
         }
-        let mut key = ProofListKey::new(1, index);
-        self.base.put(&key, HashTag::hash_leaf(&value.to_bytes()));
+        self.set_leaf_hash(index, HashTag::hash_leaf(&value.to_bytes()));
         self.base.put(&ProofListKey::leaf(index), value);
-        while key.height() < self.height() {
-            let (left, right) = (key.as_left(), key.as_right());
-            let hash = if self.has_branch(right) {
-                HashTag::hash_node(
-                    &self.get_branch_unchecked(left),
-                    &self.get_branch_unchecked(right),
-                )
-            } else {
-                HashTag::hash_single_node(&self.get_branch_unchecked(left))
-            };
-            key = key.parent();
-            self.set_branch(key, hash);
+    }
+
+    /// Removes the element at the specified position and returns it, or returns `None`
+    /// if the position is out of bounds or the element has already been removed.
+    ///
+    /// The element leaves a hole in the list: the length of the list and the positions
+    /// of other elements do not change. The hole is represented in the Merkle tree
+    /// by a leaf with the zero hash, so the proofs for the removed positions assert that
+    /// the elements are absent (see [`ListProof::Removed`]).
+    ///
+    /// [`ListProof::Removed`]: enum.ListProof.html#variant.Removed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, ProofListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index = ProofListIndex::new(name, &fork);
+    ///
+    /// index.extend([1, 2, 3].iter().cloned());
+    /// assert_eq!(Some(2), index.remove(1));
+    /// assert_eq!(None, index.remove(1));
+    /// assert_eq!(None, index.get(1));
+    /// assert_eq!(3, index.len());
+    /// assert_eq!(vec![1, 3], index.iter().collect::<Vec<_>>());
+    /// ```
+    pub fn remove(&mut self, index: u64) -> Option<V> {
+        if index >= self.len() {
+            return None;
         }
+        let value = self.get(index)?;
+        self.set_leaf_hash(index, Hash::zero());
+        self.base.remove(&ProofListKey::leaf(index));
+        Some(value)
     }
 
     /// Clears the proof list, removing all values.
@@ -621,6 +810,19 @@ where
     }
 }
 
+/// Converts a range of list indices to the range of keys of the corresponding leaves.
+fn leaf_range<R: RangeBounds<u64>>(range: &R) -> (Bound<ProofListKey>, Bound<ProofListKey>) {
+    let leaf_bound = |bound: Bound<&u64>| match bound {
+        Bound::Included(&index) => Bound::Included(ProofListKey::leaf(index)),
+        Bound::Excluded(&index) => Bound::Excluded(ProofListKey::leaf(index)),
+        Bound::Unbounded => Bound::Unbounded,
+    };
+    (
+        leaf_bound(range.start_bound()),
+        leaf_bound(range.end_bound()),
+    )
+}
+
 impl<T, V> ObjectHash for ProofListIndex<T, V>
 where
     T: IndexAccess,
@@ -13,11 +13,31 @@
 // limitations under the License.
 
 use serde::{de::Error, ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
+use serde_derive::{Deserialize, Serialize};
 use serde_json::{from_value, Error as SerdeJsonError, Value};
 
+use std::{borrow::Cow, slice};
+
 use exonum_crypto::Hash;
 
-use super::{super::BinaryValue, key::ProofListKey, HashTag};
+use super::{
+    super::BinaryValue,
+    key::{ProofListKey, MAX_LIST_LENGTH},
+    HashTag,
+};
+use crate::encoding;
+
+// Markers of the proof node kinds in the binary encoding of `ListProof`.
+const FULL_NODE: u8 = 0;
+const LEFT_NODE: u8 = 1;
+const SINGLE_LEFT_NODE: u8 = 2;
+const RIGHT_NODE: u8 = 3;
+const LEAF_NODE: u8 = 4;
+const ABSENT_NODE: u8 = 5;
+const REMOVED_NODE: u8 = 6;
+
+// Maximum depth of a decoded proof; it cannot exceed the maximum height of the list tree.
+const MAX_PROOF_DEPTH: usize = 64;
 
 /// Encapsulates a proof of absence for `ProofListIndex`.
 ///
@@ -57,6 +77,15 @@ impl ProofOfAbsence {
 }
 
 /// An enum that represents a proof of existence for a proof list elements.
+///
+/// Besides JSON, `ListProof` can be serialized with a compact binary layout
+/// via its [`BinaryValue`] implementation. The layout starts with a byte specifying
+/// its version (currently, 1), followed by the nodes of the proof in the depth-first
+/// order. Each node starts with a byte specifying its kind and is followed
+/// by its hashes (32 bytes each), its children, or, for leaves, the value prefixed
+/// by its length. Numbers, including lengths, are encoded with the `leb128` algorithm.
+///
+/// [`BinaryValue`]: ../trait.BinaryValue.html
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ListProof<V> {
     /// A branch of proof in which both children contain requested elements.
@@ -67,6 +96,11 @@ pub enum ListProof<V> {
     Right(Hash, Box<ListProof<V>>),
     /// A leaf of the proof with the requested element.
     Leaf(V),
+    /// A leaf of the proof for the requested element, which has been removed from the list.
+    /// Removed elements are not returned by [`validate`], i.e., the proof asserts their absence.
+    ///
+    /// [`validate`]: #method.validate
+    Removed,
     /// Proof of absence of an element with the specified index.
     Absent(ProofOfAbsence),
 }
@@ -83,6 +117,13 @@ pub enum ListProofError {
     /// The hash of the proof is not equal to the trusted root hash.
     #[fail(display = "hash of the proof is not equal to the trusted root hash")]
     UnmatchedRootHash,
+    /// The length of the list is too large, or the old length of the list is greater
+    /// than the new length.
+    #[fail(display = "illegal length of the list")]
+    IllegalLength,
+    /// The number of hashes in the proof does not correspond to the lengths of the list.
+    #[fail(display = "number of hashes does not correspond to the lengths of the list")]
+    InvalidHashesCount,
 }
 
 impl<V> ListProof<V>
@@ -118,6 +159,13 @@ where
                 vec.push((key.index(), value));
                 HashTag::hash_leaf(&value.to_bytes())
             }
+            ListProof::Removed => {
+                if key.height() > 1 {
+                    return Err(ListProofError::UnexpectedLeaf);
+                }
+                // Removed elements are represented by leaves with the zero hash.
+                Hash::zero()
+            }
             ListProof::Absent(ref proof) => {
                 HashTag::hash_list_node(proof.length, proof.merkle_root)
             }
@@ -135,6 +183,7 @@ where
     /// and `length` of the `ProofListIndex`.
     ///
     /// If the proof is valid, a vector with indices and references to elements is returned.
+    /// Removed elements within the proven range are absent from the vector.
     /// Otherwise, `Err` is returned.
     ///
     /// If the proof is the proof of absence, then empty vector will be returned.
@@ -163,6 +212,253 @@ where
     }
 }
 
+/// Proof that a `ProofListIndex` with the old length is a prefix of the index
+/// with the new length, similar to consistency proofs in [Certificate Transparency].
+///
+/// The proof consists of the hashes of the maximal perfect subtrees covering the elements
+/// of the old list (_peaks_), and the hashes of the nodes that cover the elements appended
+/// after the old length. Both the old and the new Merkle roots of the list are restored
+/// from these hashes, and the corresponding list hashes are computed with
+/// [`HashTag::hash_list_node`].
+///
+/// Proofs can be created with the [`get_consistency_proof()`] method of `ProofListIndex`
+/// and verified with [`validate()`].
+///
+/// ```
+/// use exonum_merkledb::{TemporaryDB, Database, ProofListIndex, ObjectHash};
+///
+/// let db = TemporaryDB::new();
+/// let fork = db.fork();
+/// let mut index = ProofListIndex::new("index", &fork);
+/// index.extend(vec![1_u32, 2, 3]);
+/// let old_hash = index.object_hash();
+/// index.extend(vec![4_u32, 5]);
+///
+/// let proof = index.get_consistency_proof(3);
+/// assert_eq!((proof.old_length(), proof.new_length()), (3, 5));
+/// assert!(proof.validate(old_hash, index.object_hash()).is_ok());
+/// ```
+///
+/// [Certificate Transparency]: https://tools.ietf.org/html/rfc6962#section-2.1.2
+/// [`HashTag::hash_list_node`]: ../enum.HashTag.html#method.hash_list_node
+/// [`get_consistency_proof()`]: struct.ProofListIndex.html#method.get_consistency_proof
+/// [`validate()`]: #method.validate
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListConsistencyProof {
+    old_length: u64,
+    new_length: u64,
+    old_peaks: Vec<Hash>,
+    hashes: Vec<Hash>,
+}
+
+impl ListConsistencyProof {
+    pub(super) fn new(
+        old_length: u64,
+        new_length: u64,
+        old_peaks: Vec<Hash>,
+        hashes: Vec<Hash>,
+    ) -> Self {
+        Self {
+            old_length,
+            new_length,
+            old_peaks,
+            hashes,
+        }
+    }
+
+    /// Returns the old length of the list.
+    pub fn old_length(&self) -> u64 {
+        self.old_length
+    }
+
+    /// Returns the new length of the list.
+    pub fn new_length(&self) -> u64 {
+        self.new_length
+    }
+
+    /// Verifies the correctness of the proof by the trusted list hashes of the list
+    /// with the old and the new length.
+    pub fn validate(
+        &self,
+        expected_old_hash: Hash,
+        expected_new_hash: Hash,
+    ) -> Result<(), ListProofError> {
+        if self.old_length > self.new_length || self.new_length > MAX_LIST_LENGTH {
+            return Err(ListProofError::IllegalLength);
+        }
+
+        let mut restorer = ConsistencyRestorer::new(self.old_length, &self.old_peaks, &self.hashes)
+            .ok_or(ListProofError::InvalidHashesCount)?;
+        let old_root = restorer
+            .restore_root(self.old_length)
+            .ok_or(ListProofError::InvalidHashesCount)?;
+        if HashTag::hash_list_node(self.old_length, old_root) != expected_old_hash {
+            return Err(ListProofError::UnmatchedRootHash);
+        }
+
+        let new_root = restorer
+            .restore_root(self.new_length)
+            .ok_or(ListProofError::InvalidHashesCount)?;
+        if !restorer.is_exhausted() {
+            return Err(ListProofError::InvalidHashesCount);
+        }
+        if HashTag::hash_list_node(self.new_length, new_root) != expected_new_hash {
+            return Err(ListProofError::UnmatchedRootHash);
+        }
+        Ok(())
+    }
+}
+
+/// Restores the nodes of the Merkle tree over the list elements from the peaks of the tree
+/// for the old length of the list and the hashes of the nodes covering only new elements,
+/// which are consumed in the depth-first order.
+///
+/// Used to validate the consistency proofs of `ProofListIndex` and `MountainRangeIndex`.
+pub(crate) struct ConsistencyRestorer<'a> {
+    old_length: u64,
+    old_keys: Vec<ProofListKey>,
+    old_peaks: &'a [Hash],
+    hashes: slice::Iter<'a, Hash>,
+}
+
+impl<'a> ConsistencyRestorer<'a> {
+    /// Creates a restorer, or returns `None` if the number of old peaks does not correspond
+    /// to the old length of the list.
+    pub fn new(old_length: u64, old_peaks: &'a [Hash], hashes: &'a [Hash]) -> Option<Self> {
+        let old_keys = ProofListKey::peaks(old_length);
+        if old_keys.len() != old_peaks.len() {
+            return None;
+        }
+        Some(Self {
+            old_length,
+            old_keys,
+            old_peaks,
+            hashes: hashes.iter(),
+        })
+    }
+
+    /// Restores the root of the Merkle tree for the list with the specified length.
+    /// Returns `None` if there are not enough hashes.
+    pub fn restore_root(&mut self, len: u64) -> Option<Hash> {
+        if len == 0 {
+            return Some(Hash::default());
+        }
+        let height = len.next_power_of_two().trailing_zeros() as u8 + 1;
+        self.restore_node(ProofListKey::new(height, 0), len)
+    }
+
+    /// Restores the node with the specified key in the Merkle tree for the list with
+    /// the specified length. Returns `None` if there are not enough hashes.
+    pub fn restore_node(&mut self, key: ProofListKey, len: u64) -> Option<Hash> {
+        if key.is_covered_by(self.old_length) {
+            // Nodes covering only old elements are restored from the old peaks.
+            let position = self.old_keys.iter().position(|old_key| *old_key == key)?;
+            Some(self.old_peaks[position])
+        } else if key.first_left_leaf_index() >= self.old_length {
+            self.hashes.next().cloned()
+        } else {
+            let left = self.restore_node(key.left(), len)?;
+            if key.first_right_leaf_index() < len {
+                let right = self.restore_node(key.right(), len)?;
+                Some(HashTag::hash_node(&left, &right))
+            } else {
+                Some(HashTag::hash_single_node(&left))
+            }
+        }
+    }
+
+    /// Checks if all the hashes have been consumed.
+    pub fn is_exhausted(&self) -> bool {
+        self.hashes.len() == 0
+    }
+}
+
+impl<V: BinaryValue> ListProof<V> {
+    fn write_node(&self, buffer: &mut Vec<u8>) {
+        match *self {
+            ListProof::Full(ref left, ref right) => {
+                encoding::write_u8(buffer, FULL_NODE);
+                left.write_node(buffer);
+                right.write_node(buffer);
+            }
+            ListProof::Left(ref left, Some(ref right)) => {
+                encoding::write_u8(buffer, LEFT_NODE);
+                left.write_node(buffer);
+                encoding::write_hash(buffer, right);
+            }
+            ListProof::Left(ref left, None) => {
+                encoding::write_u8(buffer, SINGLE_LEFT_NODE);
+                left.write_node(buffer);
+            }
+            ListProof::Right(ref left, ref right) => {
+                encoding::write_u8(buffer, RIGHT_NODE);
+                encoding::write_hash(buffer, left);
+                right.write_node(buffer);
+            }
+            ListProof::Leaf(ref value) => {
+                encoding::write_u8(buffer, LEAF_NODE);
+                encoding::write_bytes(buffer, &value.to_bytes());
+            }
+            ListProof::Absent(ref proof) => {
+                encoding::write_u8(buffer, ABSENT_NODE);
+                encoding::write_u64(buffer, proof.length);
+                encoding::write_hash(buffer, &proof.merkle_root);
+            }
+            ListProof::Removed => encoding::write_u8(buffer, REMOVED_NODE),
+        }
+    }
+
+    fn read_node(reader: &mut &[u8], depth: usize) -> Result<Self, failure::Error> {
+        ensure!(depth <= MAX_PROOF_DEPTH, "Encoded list proof is too deep");
+
+        let read_child = |reader: &mut &[u8]| Self::read_node(reader, depth + 1).map(Box::new);
+        let node = match encoding::read_u8(reader)? {
+            FULL_NODE => {
+                let left = read_child(reader)?;
+                ListProof::Full(left, read_child(reader)?)
+            }
+            LEFT_NODE => {
+                let left = read_child(reader)?;
+                ListProof::Left(left, Some(encoding::read_hash(reader)?))
+            }
+            SINGLE_LEFT_NODE => ListProof::Left(read_child(reader)?, None),
+            RIGHT_NODE => {
+                let left = encoding::read_hash(reader)?;
+                ListProof::Right(left, read_child(reader)?)
+            }
+            LEAF_NODE => {
+                let value = encoding::read_bytes(reader)?;
+                ListProof::Leaf(V::from_bytes(value.into())?)
+            }
+            ABSENT_NODE => {
+                let length = encoding::read_u64(reader)?;
+                let merkle_root = encoding::read_hash(reader)?;
+                ListProof::Absent(ProofOfAbsence::new(length, merkle_root))
+            }
+            REMOVED_NODE => ListProof::Removed,
+            marker => bail!("Invalid node marker in the encoded list proof: {}", marker),
+        };
+        Ok(node)
+    }
+}
+
+impl<V: BinaryValue> BinaryValue for ListProof<V> {
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        encoding::write_version(&mut buffer);
+        self.write_node(&mut buffer);
+        buffer
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Result<Self, failure::Error> {
+        let mut reader = bytes.as_ref();
+        encoding::read_version(&mut reader)?;
+        let proof = Self::read_node(&mut reader, 0)?;
+        encoding::read_end(reader)?;
+        Ok(proof)
+    }
+}
+
 impl<V: Serialize> Serialize for ListProof<V> {
     fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
     where
@@ -200,6 +496,10 @@ impl<V: Serialize> Serialize for ListProof<V> {
                 state.serialize_field("length", &proof.length)?;
                 state.serialize_field("hash", &proof.merkle_root)?;
             }
+            Removed => {
+                state = ser.serialize_struct("Removed", 1)?;
+                state.serialize_field("removed", &true)?;
+            }
         }
         state.end()
     }
@@ -277,14 +577,19 @@ where
                 }
             }
             1 => {
-                if map_key_value.get("val").is_none() && map_key_value.get("left").is_none() {
+                if map_key_value.get("val").is_none()
+                    && map_key_value.get("left").is_none()
+                    && map_key_value.get("removed").is_none()
+                {
                     return Err(D::Error::custom(format!(
                         "Invalid json: unknown key met. \
-                         Expected: {} or {}. json: {:?}",
-                        "val", "left", json
+                         Expected: {}, {} or {}. json: {:?}",
+                        "val", "left", "removed", json
                     )));
                 }
-                if let Some(leaf_value) = map_key_value.get("val") {
+                if map_key_value.contains_key("removed") {
+                    ListProof::Removed
+                } else if let Some(leaf_value) = map_key_value.get("val") {
                     let val: V = from_value(leaf_value.clone()).map_err(|err| {
                         D::Error::custom(format_err_string("V", leaf_value, &err))
                     })?;
@@ -18,17 +18,18 @@ use hex::FromHex;
 use rand::{thread_rng, Rng, RngCore};
 use serde::ser::Serialize;
 use serde_derive::Serialize;
-use serde_json::{from_str, to_string};
+use serde_json::{from_str, from_value, json, to_string, to_value, Value};
 
 use exonum_crypto::Hash;
 
 use crate::{
     hash::{HashTag, ObjectHash},
     proof_list_index::{
+        ListConsistencyProof,
         ListProof::{
             self, {Full, Leaf, Left, Right},
         },
-        ProofListIndex,
+        ListProofError, ProofListIndex,
     },
     BinaryValue, Database, TemporaryDB,
 };
@@ -84,6 +85,68 @@ fn test_list_methods() {
     assert_eq!(index.len(), 0);
 }
 
+#[test]
+fn test_remove() {
+    let db = TemporaryDB::default();
+    let fork = db.fork();
+    let mut index = ProofListIndex::new(IDX_NAME, &fork);
+    index.extend((0_u8..10).map(|i| vec![i]));
+    let full_hash = index.object_hash();
+
+    assert_eq!(index.remove(3), Some(vec![3]));
+    assert_eq!(index.remove(3), None);
+    assert_eq!(index.remove(10), None);
+    assert_eq!(index.remove(9), Some(vec![9]));
+    assert_eq!(index.len(), 10);
+    assert_eq!(index.get(3), None);
+    assert_eq!(index.last(), None);
+    assert_eq!(
+        index.iter().collect::<Vec<_>>(),
+        vec![
+            vec![0],
+            vec![1],
+            vec![2],
+            vec![4],
+            vec![5],
+            vec![6],
+            vec![7],
+            vec![8]
+        ]
+    );
+    let list_hash = index.object_hash();
+    assert_ne!(list_hash, full_hash);
+
+    // Proofs for the removed elements assert their absence.
+    for &i in &[3, 9] {
+        let proof = index.get_proof(i);
+        assert!(proof.validate(list_hash, index.len()).unwrap().is_empty());
+        assert_eq!(proof, from_str(&to_string(&proof).unwrap()).unwrap());
+        assert_eq!(
+            proof,
+            ListProof::from_bytes(proof.to_bytes().into()).unwrap()
+        );
+    }
+    let proof = index.get_range_proof(2..5);
+    let entries = proof.validate(list_hash, index.len()).unwrap();
+    assert_eq!(entries, vec![(2, &vec![2]), (4, &vec![4])]);
+
+    // Pushing and setting elements work as usual.
+    index.push(vec![10]);
+    let proof = index.get_proof(10);
+    assert_eq!(
+        proof.validate(index.object_hash(), index.len()).unwrap(),
+        vec![(10, &vec![10])]
+    );
+    index.set(3, vec![3]);
+    index.set(9, vec![9]);
+    index.push(vec![11]);
+
+    let other_fork = db.fork();
+    let mut other_index = ProofListIndex::new(IDX_NAME, &other_fork);
+    other_index.extend((0_u8..12).map(|i| vec![i]));
+    assert_eq!(index.object_hash(), other_index.object_hash());
+}
+
 #[test]
 fn test_height() {
     let db = TemporaryDB::default();
@@ -316,6 +379,45 @@ fn test_randomly_generate_proofs() {
     }
 }
 
+#[test]
+fn test_proof_binary_serialization() {
+    let db = TemporaryDB::default();
+    let fork = db.fork();
+    let mut index = ProofListIndex::new(IDX_NAME, &fork);
+    let num_values = 100;
+    let values = random_values(num_values as usize);
+    let mut rng = thread_rng();
+    for value in &values {
+        index.push(value.clone());
+    }
+
+    for _ in 0..50 {
+        let start_range = rng.gen_range(0, num_values);
+        let end_range = rng.gen_range(start_range + 1, num_values + 1);
+        let range_proof = index.get_range_proof(start_range..end_range);
+
+        let bytes = range_proof.to_bytes();
+        assert!(bytes.len() < to_string(&range_proof).unwrap().len());
+        let restored_proof = ListProof::<Vec<u8>>::from_bytes(bytes.as_slice().into()).unwrap();
+        assert_eq!(range_proof, restored_proof);
+
+        // Truncated or extended proofs are rejected.
+        assert!(ListProof::<Vec<u8>>::from_bytes(bytes[..bytes.len() - 1].into()).is_err());
+        let mut extended_bytes = bytes.clone();
+        extended_bytes.push(0);
+        assert!(ListProof::<Vec<u8>>::from_bytes(extended_bytes.into()).is_err());
+    }
+
+    let absence_proof = index.get_proof(num_values);
+    let restored_proof = ListProof::from_bytes(absence_proof.to_bytes().into()).unwrap();
+    assert_eq!(absence_proof, restored_proof);
+
+    // Unknown versions of the layout are rejected.
+    let mut bytes = absence_proof.to_bytes();
+    bytes[0] = 0;
+    assert!(ListProof::<Vec<u8>>::from_bytes(bytes.into()).is_err());
+}
+
 fn hash_leaf_node(value: &[u8]) -> Hash {
     HashTag::Blob.hash_stream().update(value).hash()
 }
@@ -712,3 +814,129 @@ fn assert_proof_of_absence<V: BinaryValue + ObjectHash + Debug>(
         panic!("Unexpected proof {:?}", proof);
     }
 }
+
+#[test]
+fn test_consistency_proofs() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = ProofListIndex::new(IDX_NAME, &fork);
+
+    let mut hashes = vec![list.object_hash()];
+    for i in 0..40_u64 {
+        list.push(i);
+        hashes.push(list.object_hash());
+
+        for (old_len, &old_hash) in hashes.iter().enumerate() {
+            let proof = list.get_consistency_proof(old_len as u64);
+            assert_eq!(proof.old_length(), old_len as u64);
+            assert_eq!(proof.new_length(), list.len());
+            proof.validate(old_hash, list.object_hash()).unwrap();
+        }
+    }
+
+    let proof = list.get_consistency_proof(10);
+    assert_eq!(
+        proof.validate(hashes[11], list.object_hash()).unwrap_err(),
+        ListProofError::UnmatchedRootHash
+    );
+    assert_eq!(
+        proof.validate(hashes[10], hashes[39]).unwrap_err(),
+        ListProofError::UnmatchedRootHash
+    );
+
+    let json = to_string(&proof).unwrap();
+    let deserialized: ListConsistencyProof = from_str(&json).unwrap();
+    assert_eq!(deserialized, proof);
+}
+
+#[test]
+fn test_consistency_proof_with_rewritten_history() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = ProofListIndex::new(IDX_NAME, &fork);
+    list.extend(0_u64..10);
+    let old_hash = list.object_hash();
+    list.extend(10_u64..15);
+
+    list.set(3, 100);
+    let proof = list.get_consistency_proof(10);
+    assert_eq!(
+        proof.validate(old_hash, list.object_hash()).unwrap_err(),
+        ListProofError::UnmatchedRootHash
+    );
+
+    list.set(3, 3);
+    list.remove(9);
+    let proof = list.get_consistency_proof(10);
+    assert_eq!(
+        proof.validate(old_hash, list.object_hash()).unwrap_err(),
+        ListProofError::UnmatchedRootHash
+    );
+
+    // Changes of the appended elements do not break consistency.
+    list.set(9, 9);
+    list.set(12, 100);
+    let proof = list.get_consistency_proof(10);
+    proof.validate(old_hash, list.object_hash()).unwrap();
+}
+
+#[test]
+fn test_invalid_consistency_proofs() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = ProofListIndex::new(IDX_NAME, &fork);
+    list.extend(0_u64..5);
+    let old_hash = list.object_hash();
+    list.extend(5_u64..13);
+    let new_hash = list.object_hash();
+    let proof = to_value(list.get_consistency_proof(5)).unwrap();
+
+    let tamper = |modify: &dyn Fn(&mut Value)| {
+        let mut proof = proof.clone();
+        modify(&mut proof);
+        let proof: ListConsistencyProof = from_value(proof).unwrap();
+        proof.validate(old_hash, new_hash).unwrap_err()
+    };
+
+    assert_eq!(
+        tamper(&|proof| proof["hashes"][0] = json!(Hash::zero())),
+        ListProofError::UnmatchedRootHash
+    );
+    assert_eq!(
+        tamper(&|proof| proof["old_peaks"][1] = json!(Hash::zero())),
+        ListProofError::UnmatchedRootHash
+    );
+    assert_eq!(
+        tamper(&|proof| proof["hashes"]
+            .as_array_mut()
+            .unwrap()
+            .push(json!(Hash::zero()))),
+        ListProofError::InvalidHashesCount
+    );
+    assert_eq!(
+        tamper(&|proof| {
+            proof["hashes"].as_array_mut().unwrap().pop();
+        }),
+        ListProofError::InvalidHashesCount
+    );
+    assert_eq!(
+        tamper(&|proof| {
+            proof["old_peaks"].as_array_mut().unwrap().pop();
+        }),
+        ListProofError::InvalidHashesCount
+    );
+    assert_eq!(
+        tamper(&|proof| proof["old_length"] = json!(14)),
+        ListProofError::IllegalLength
+    );
+}
+
+#[test]
+#[should_panic(expected = "Illegal lengths")]
+fn test_consistency_proof_illegal_length() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = ProofListIndex::new(IDX_NAME, &fork);
+    list.extend(0_u64..5);
+    list.get_consistency_proof(6);
+}
@@ -22,7 +22,7 @@ use leb128;
 
 use exonum_crypto::HASH_SIZE;
 
-use crate::{BinaryKey, ObjectHash};
+use crate::{views::IndexType, BinaryKey, ObjectHash};
 
 /// This prefix defines a node as a branch.
 pub const BRANCH_KEY_PREFIX: u8 = 0;
@@ -147,6 +147,121 @@ impl ProofPath {
             self.bytes[PROOF_PATH_LEN_POS] = 0;
         };
     }
+
+    /// Returns the least leaf path starting with this path.
+    pub(crate) fn min_leaf(&self) -> Self {
+        self.complete_with(ChildKind::Left)
+    }
+
+    /// Returns the greatest leaf path starting with this path.
+    pub(crate) fn max_leaf(&self) -> Self {
+        self.complete_with(ChildKind::Right)
+    }
+
+    /// Converts this path to a leaf path, setting all the bits after the end of the path
+    /// to the specified value.
+    fn complete_with(&self, kind: ChildKind) -> Self {
+        debug_assert_eq!(self.start(), 0);
+
+        let mut bytes = [0_u8; KEY_SIZE];
+        bytes.copy_from_slice(self.raw_key());
+        for i in self.end() as usize..KEY_SIZE * 8 {
+            match kind {
+                ChildKind::Left => bytes[i / 8] &= !(1 << (i % 8)),
+                ChildKind::Right => bytes[i / 8] |= 1 << (i % 8),
+            }
+        }
+        Self::from_bytes(&bytes)
+    }
+}
+
+/// Converts keys of a `ProofMapIndex` to the paths in the underlying Merkle Patricia tree.
+///
+/// The conversion is specified by the key mode of the index, which is either [`Hashed`]
+/// or [`Raw`].
+///
+/// [`Hashed`]: struct.Hashed.html
+/// [`Raw`]: struct.Raw.html
+pub trait ToProofPath<K> {
+    /// Converts the key to the path.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not valid according to [`is_valid_key`](#method.is_valid_key).
+    fn transform_key(key: &K) -> ProofPath;
+
+    /// Checks if the key can be converted to a path. Used to check keys in untrusted proofs.
+    fn is_valid_key(_key: &K) -> bool {
+        true
+    }
+
+    /// Restores the key from the path of a leaf, or returns `None` if the key mode
+    /// does not allow it.
+    fn restore_key(_path: &ProofPath) -> Option<K>
+    where
+        K: BinaryKey + ToOwned<Owned = K>,
+    {
+        None
+    }
+
+    /// Type of the index stored in the index metadata, which prevents opening the index
+    /// with another key mode.
+    fn index_type() -> IndexType {
+        IndexType::ProofMap
+    }
+}
+
+/// Default key mode of a `ProofMapIndex`, in which the path of a key is the hash
+/// of the key (see [`ProofPath::new()`]).
+///
+/// [`ProofPath::new()`]: struct.ProofPath.html#method.new
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Hashed;
+
+impl<K: ObjectHash> ToProofPath<K> for Hashed {
+    fn transform_key(key: &K) -> ProofPath {
+        ProofPath::new(key)
+    }
+}
+
+/// Key mode of a `ProofMapIndex`, in which the path of a key is the key itself.
+///
+/// Keys must be serialized to exactly [`PROOF_MAP_KEY_SIZE`] bytes and should be uniformly
+/// distributed (e.g., public keys or hashes), otherwise the tree becomes unbalanced.
+/// Since keys are not hashed, modifying the index is faster than in the [`Hashed`] mode.
+///
+/// [`PROOF_MAP_KEY_SIZE`]: constant.PROOF_MAP_KEY_SIZE.html
+/// [`Hashed`]: struct.Hashed.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Raw;
+
+impl<K: BinaryKey> ToProofPath<K> for Raw {
+    fn transform_key(key: &K) -> ProofPath {
+        assert!(
+            Self::is_valid_key(key),
+            "Keys of a raw `ProofMapIndex` must have {} bytes, got {}",
+            KEY_SIZE,
+            key.size()
+        );
+        let mut bytes = [0_u8; KEY_SIZE];
+        key.write(&mut bytes);
+        ProofPath::from_bytes(&bytes)
+    }
+
+    fn is_valid_key(key: &K) -> bool {
+        key.size() == KEY_SIZE
+    }
+
+    fn restore_key(path: &ProofPath) -> Option<K>
+    where
+        K: ToOwned<Owned = K>,
+    {
+        Some(K::read(path.raw_key()))
+    }
+
+    fn index_type() -> IndexType {
+        IndexType::RawProofMap
+    }
 }
 
 /// The bits representation of the `ProofPath`.
@@ -412,6 +527,42 @@ impl ProofPath {
         }
         bytes_written
     }
+
+    /// Reads the proof path from the compressed binary representation created
+    /// by `write_compressed` and advances the `reader` past it.
+    pub(crate) fn read_compressed(reader: &mut &[u8]) -> Result<Self, failure::Error> {
+        let bits_len = leb128::read::unsigned(reader)?;
+        ensure!(
+            bits_len <= (KEY_SIZE * 8) as u64,
+            "Invalid length of the compressed proof path: {}",
+            bits_len
+        );
+        let bits_len = bits_len as usize;
+        let bytes_len = div_ceil!(bits_len, 8);
+        ensure!(
+            reader.len() >= bytes_len,
+            "Unexpected end of the compressed proof path"
+        );
+
+        let mut raw = [0_u8; PROOF_PATH_SIZE];
+        raw[PROOF_PATH_KEY_POS..PROOF_PATH_KEY_POS + bytes_len]
+            .copy_from_slice(&reader[..bytes_len]);
+        *reader = &reader[bytes_len..];
+        if bits_len == KEY_SIZE * 8 {
+            raw[PROOF_PATH_KIND_POS] = LEAF_KEY_PREFIX;
+        } else {
+            raw[PROOF_PATH_KIND_POS] = BRANCH_KEY_PREFIX;
+            raw[PROOF_PATH_LEN_POS] = bits_len as u8;
+            // Trims insignificant bits in the last byte.
+            if bits_len % 8 != 0 {
+                reset_bits(
+                    &mut raw[PROOF_PATH_KEY_POS + bytes_len - 1],
+                    (bits_len % 8) as u16,
+                );
+            }
+        }
+        Ok(Self::from_inner(raw))
+    }
 }
 
 #[cfg(test)]
@@ -420,8 +571,6 @@ mod tests {
     use serde_json::{self, json, Value};
     use smallvec::{smallvec, SmallVec};
 
-    use std::io::Read;
-
     use super::*;
 
     const MAX_PROOF_PATH_BITS: u16 = 256;
@@ -433,25 +582,6 @@ mod tests {
             buf.truncate(bytes_written);
             buf
         }
-
-        /// Reads the proof path from the compressed binary representation.
-        fn read_compressed(value: &[u8]) -> Self {
-            let mut reader = Cursor::new(value);
-            let bits_len = leb128::read::unsigned(&mut reader).unwrap() as usize;
-            debug_assert!(bits_len <= KEY_SIZE * 8);
-
-            let mut raw = [0_u8; PROOF_PATH_SIZE];
-            let _read = reader
-                .read(&mut raw[PROOF_PATH_KEY_POS..PROOF_PATH_KEY_POS + KEY_SIZE])
-                .unwrap();
-            if bits_len == KEY_SIZE * 8 {
-                raw[PROOF_PATH_KIND_POS] = LEAF_KEY_PREFIX;
-            } else {
-                raw[PROOF_PATH_KIND_POS] = BRANCH_KEY_PREFIX;
-                raw[PROOF_PATH_LEN_POS] = bits_len as u8;
-            }
-            Self::from_inner(raw)
-        }
     }
 
     /// Creates a random non-leaf, non-empty path.
@@ -503,7 +633,7 @@ mod tests {
         for _ in 0..1000 {
             let key = random_path(&mut rng);
             let buf = key.compressed();
-            let key2 = ProofPath::read_compressed(buf.as_ref());
+            let key2 = ProofPath::read_compressed(&mut buf.as_ref()).unwrap();
             assert_eq!(key2, key);
             // Trims insignificant bits in the last byte.
             let trimmed_key = {
@@ -633,7 +763,7 @@ mod tests {
     fn test_proof_path_compress_leaf_regular() {
         let key = ProofPath::from_bytes(&[250; 32]);
         let buf = key.compressed();
-        let key2 = ProofPath::read_compressed(buf.as_ref());
+        let key2 = ProofPath::read_compressed(&mut buf.as_ref()).unwrap();
         assert_eq!(key2, key);
     }
 
@@ -642,7 +772,7 @@ mod tests {
         let mut key = ProofPath::from_bytes(&[250; 32]);
         key = key.prefix(0);
         let buf = key.compressed();
-        let key2 = ProofPath::read_compressed(buf.as_ref());
+        let key2 = ProofPath::read_compressed(&mut buf.as_ref()).unwrap();
         assert_eq!(key2, key);
     }
 
@@ -651,7 +781,7 @@ mod tests {
         let mut key = ProofPath::from_bytes(&[250; 32]);
         key = key.prefix(255);
         let buf = key.compressed();
-        let key2 = ProofPath::read_compressed(buf.as_ref());
+        let key2 = ProofPath::read_compressed(&mut buf.as_ref()).unwrap();
         assert_eq!(key2, key);
     }
 
@@ -662,7 +792,7 @@ mod tests {
         key = key.suffix(5);
 
         let buf = key.compressed();
-        let mut key2 = ProofPath::read_compressed(buf.as_ref());
+        let mut key2 = ProofPath::read_compressed(&mut buf.as_ref()).unwrap();
         key2.start = 5;
         assert_eq!(key2, key);
         // Trims insignificant bits in the last byte.
@@ -683,7 +813,7 @@ mod tests {
         for i in 0..MAX_PROOF_PATH_BITS {
             let key = origin.prefix(i);
             let buf = key.compressed();
-            let mut key2 = ProofPath::read_compressed(buf.as_ref());
+            let mut key2 = ProofPath::read_compressed(&mut buf.as_ref()).unwrap();
             assert_eq!(key2, key);
             key2.set_end(None);
             for j in i..MAX_PROOF_PATH_BITS {
@@ -692,6 +822,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_proof_path_read_compressed_sequence() {
+        let mut rng = rand::thread_rng();
+        let keys = (0..10).map(|_| random_path(&mut rng)).collect::<Vec<_>>();
+        let buf = keys
+            .iter()
+            .flat_map(|key| key.compressed().into_iter())
+            .collect::<Vec<_>>();
+
+        let mut reader = buf.as_slice();
+        for key in &keys {
+            assert_eq!(ProofPath::read_compressed(&mut reader).unwrap(), *key);
+        }
+        assert!(reader.is_empty());
+        assert!(ProofPath::read_compressed(&mut reader).is_err());
+
+        let buf = ProofPath::from_bytes(&[1; 32]).compressed();
+        assert!(ProofPath::read_compressed(&mut &buf[..buf.len() - 1]).is_err());
+    }
+
     #[test]
     fn test_proof_path_suffix() {
         let b = ProofPath::from_inner(*b"\x00\x01\x02\xFF\x0C0000000000000000000000000000\x20");
@@ -732,6 +882,43 @@ mod tests {
         assert_eq!(b.prefix(1).len(), 1);
     }
 
+    #[test]
+    fn test_proof_path_leaf_bounds() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let mut bytes = [0; 32];
+            rng.fill(&mut bytes);
+            let path = ProofPath::from_bytes(&bytes);
+            let prefix = path.prefix(rng.gen::<u16>() % 256);
+
+            let (min_leaf, max_leaf) = (prefix.min_leaf(), prefix.max_leaf());
+            assert!(min_leaf.is_leaf() && max_leaf.is_leaf());
+            assert!(min_leaf.starts_with(&prefix) && max_leaf.starts_with(&prefix));
+            assert!(min_leaf <= path && path <= max_leaf);
+
+            assert_eq!(path.min_leaf(), path);
+            assert_eq!(path.max_leaf(), path);
+        }
+
+        let prefix = ProofPath::from_bytes(&[0b_0101; 32]).prefix(3);
+        assert_eq!(
+            prefix.min_leaf(),
+            ProofPath::from_bytes(&{
+                let mut bytes = [0; 32];
+                bytes[0] = 0b_0101;
+                bytes
+            })
+        );
+        assert_eq!(
+            prefix.max_leaf(),
+            ProofPath::from_bytes(&{
+                let mut bytes = [0xff; 32];
+                bytes[0] = 0b_1111_1101;
+                bytes
+            })
+        );
+    }
+
     #[test]
     fn test_proof_path_len() {
         let b = ProofPath::from_inner(*b"\x01qwertyuiopasdfghjklzxcvbnm123456\x00");
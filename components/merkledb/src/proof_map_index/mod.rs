@@ -17,8 +17,8 @@
 #[doc(hidden)]
 pub use self::node::{BranchNode, Node};
 pub use self::{
-    key::{ProofPath, KEY_SIZE as PROOF_MAP_KEY_SIZE, PROOF_PATH_SIZE},
-    proof::{CheckedMapProof, MapProof, MapProofError},
+    key::{Hashed, ProofPath, Raw, ToProofPath, KEY_SIZE as PROOF_MAP_KEY_SIZE, PROOF_PATH_SIZE},
+    proof::{CheckedMapProof, CheckedMapRangeProof, MapProof, MapProofError, MapRangeProof},
 };
 
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
@@ -28,13 +28,14 @@ use std::{
     io::{Read, Write},
     marker::PhantomData,
     mem::size_of,
+    ops::{Bound, RangeBounds},
 };
 
 use exonum_crypto::Hash;
 
 use self::{
     key::{BitsRange, ChildKind, VALUE_KEY_PREFIX},
-    proof::{create_multiproof, create_proof},
+    proof::{create_multiproof, create_proof, create_range_proof},
 };
 use crate::views::{AnyObject, IndexAddress};
 use crate::{
@@ -59,22 +60,58 @@ const LEN_SIZE: usize = size_of::<u64>();
 /// `ProofMapIndex` requires that keys implement the [`BinaryKey`] trait and
 /// values implement the [`BinaryValue`] trait.
 ///
+/// The `KeyMode` parameter specifies how keys are converted to paths in the tree.
+/// By default ([`Hashed`]), the path of a key is its hash. Indexes with keys that are
+/// already uniformly distributed 32-byte values (such as public keys or hashes) can use
+/// the [`Raw`] mode, in which the key itself is used as the path; such indexes are created
+/// with [`new_raw`] or [`new_raw_in_family`] (see also [`RawProofMapIndex`]).
+///
 /// [`BinaryKey`]: ../trait.BinaryKey.html
 /// [`BinaryValue`]: ../trait.BinaryValue.html
-pub struct ProofMapIndex<T: IndexAccess, K, V> {
+/// [`Hashed`]: struct.Hashed.html
+/// [`Raw`]: struct.Raw.html
+/// [`new_raw`]: #method.new_raw
+/// [`new_raw_in_family`]: #method.new_raw_in_family
+/// [`RawProofMapIndex`]: type.RawProofMapIndex.html
+pub struct ProofMapIndex<T: IndexAccess, K, V, KeyMode = Hashed> {
     base: View<T>,
     state: IndexState<T, ProofMapState>,
     _k: PhantomData<K>,
     _v: PhantomData<V>,
+    _key_mode: PhantomData<KeyMode>,
 }
 
+/// A `ProofMapIndex` with keys used as paths in the Merkle Patricia tree without hashing.
+///
+/// # Examples
+///
+/// ```
+/// use exonum_merkledb::{Database, ObjectHash, ProofMapIndex, RawProofMapIndex, TemporaryDB};
+/// use exonum_crypto::hash;
+///
+/// let db = TemporaryDB::new();
+/// let fork = db.fork();
+/// let mut index: RawProofMapIndex<_, _, u32> = ProofMapIndex::new_raw("index", &fork);
+/// let key = hash(&[1, 2, 3]);
+/// index.put(&key, 1);
+///
+/// let proof = index.get_proof(key);
+/// let checked_proof = proof.check().unwrap();
+/// assert_eq!(checked_proof.entries().collect::<Vec<_>>(), vec![(&key, &1)]);
+/// assert_eq!(checked_proof.root_hash(), index.object_hash());
+/// ```
+pub type RawProofMapIndex<T, K, V> = ProofMapIndex<T, K, V, Raw>;
+
 /// An iterator over the entries of a `ProofMapIndex`.
 ///
-/// This struct is created by the [`iter`] or
-/// [`iter_from`] method on [`ProofMapIndex`]. See its documentation for details.
+/// This struct is created by the [`iter`], [`iter_from`], [`iter_rev`], [`range`]
+/// or [`range_rev`] method on [`ProofMapIndex`]. See its documentation for details.
 ///
 /// [`iter`]: struct.ProofMapIndex.html#method.iter
 /// [`iter_from`]: struct.ProofMapIndex.html#method.iter_from
+/// [`iter_rev`]: struct.ProofMapIndex.html#method.iter_rev
+/// [`range`]: struct.ProofMapIndex.html#method.range
+/// [`range_rev`]: struct.ProofMapIndex.html#method.range_rev
 /// [`ProofMapIndex`]: struct.ProofMapIndex.html
 #[derive(Debug)]
 pub struct ProofMapIndexIter<'a, K, V> {
@@ -109,18 +146,19 @@ pub struct ProofMapIndexValues<'a, V> {
     base_iter: ViewIter<'a, Vec<u8>, V>,
 }
 
-impl<T, K, V> AnyObject<T> for ProofMapIndex<T, K, V>
+impl<T, K, V, KeyMode> AnyObject<T> for ProofMapIndex<T, K, V, KeyMode>
 where
     T: IndexAccess,
-    K: BinaryKey + ObjectHash,
+    K: BinaryKey,
     V: BinaryValue + ObjectHash,
+    KeyMode: ToProofPath<K>,
 {
     fn view(self) -> View<T> {
         self.base
     }
 
     fn object_type(&self) -> IndexType {
-        IndexType::ProofMap
+        KeyMode::index_type()
     }
 
     fn metadata(&self) -> Vec<u8> {
@@ -160,6 +198,23 @@ impl<T: BinaryKey> ValuePath for T {
     }
 }
 
+/// Converts a range of keys to the range of paths under which the values are stored.
+fn value_path_range<K, R>(range: &R) -> (Bound<Vec<u8>>, Bound<Vec<u8>>)
+where
+    K: BinaryKey,
+    R: RangeBounds<K>,
+{
+    let path_bound = |bound: Bound<&K>| match bound {
+        Bound::Included(key) => Bound::Included(key.to_value_path()),
+        Bound::Excluded(key) => Bound::Excluded(key.to_value_path()),
+        Bound::Unbounded => Bound::Unbounded,
+    };
+    (
+        path_bound(range.start_bound()),
+        path_bound(range.end_bound()),
+    )
+}
+
 #[derive(Debug, Default, Copy, Clone)]
 struct ProofMapState {
     len: u64,
@@ -249,6 +304,7 @@ where
             state,
             _k: PhantomData,
             _v: PhantomData,
+            _key_mode: PhantomData,
         }
     }
 
@@ -302,24 +358,85 @@ where
             state,
             _k: PhantomData,
             _v: PhantomData,
+            _key_mode: PhantomData,
         }
     }
+}
+
+impl<T, K, V> ProofMapIndex<T, K, V, Raw>
+where
+    T: IndexAccess,
+    K: BinaryKey,
+    V: BinaryValue + ObjectHash,
+{
+    /// Creates a new index representation with raw keys based on the name and storage view.
+    /// Keys of the index must be serialized to exactly [`PROOF_MAP_KEY_SIZE`] bytes.
+    ///
+    /// See [`new`](#method.new) for details.
+    ///
+    /// [`PROOF_MAP_KEY_SIZE`]: constant.PROOF_MAP_KEY_SIZE.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, ProofMapIndex, RawProofMapIndex};
+    /// use exonum_crypto::PublicKey;
+    ///
+    /// let db = TemporaryDB::new();
+    /// let snapshot = db.snapshot();
+    /// let index: RawProofMapIndex<_, PublicKey, u8> = ProofMapIndex::new_raw("name", &snapshot);
+    /// ```
+    pub fn new_raw<S: Into<String>>(index_name: S, view: T) -> Self {
+        Self::from_builder(
+            IndexBuilder::new(view)
+                .index_type(IndexType::RawProofMap)
+                .index_name(index_name),
+        )
+    }
+
+    /// Creates a new index representation with raw keys based on the name, common prefix
+    /// of its keys and storage view.
+    ///
+    /// See [`new_in_family`](#method.new_in_family) and [`new_raw`](#method.new_raw)
+    /// for details.
+    pub fn new_raw_in_family<S, I>(family_name: S, index_id: &I, view: T) -> Self
+    where
+        I: BinaryKey,
+        I: ?Sized,
+        S: Into<String>,
+    {
+        Self::from_builder(
+            IndexBuilder::new(view)
+                .index_type(IndexType::RawProofMap)
+                .index_name(family_name)
+                .family_id(index_id),
+        )
+    }
+}
 
+impl<T, K, V, KeyMode> ProofMapIndex<T, K, V, KeyMode>
+where
+    T: IndexAccess,
+    K: BinaryKey,
+    V: BinaryValue + ObjectHash,
+    KeyMode: ToProofPath<K>,
+{
     pub fn get_from<I: Into<IndexAddress>>(address: I, access: T) -> Option<Self> {
         IndexBuilder::from_address(address, access)
-            .index_type(IndexType::ProofMap)
+            .index_type(KeyMode::index_type())
             .build_existed()
             .map(|(base, state)| Self {
                 base,
                 state,
                 _k: PhantomData,
                 _v: PhantomData,
+                _key_mode: PhantomData,
             })
     }
 
     pub fn create_from<I: Into<IndexAddress>>(address: I, access: T) -> Self {
         let (base, state) = IndexBuilder::from_address(address, access)
-            .index_type(IndexType::ProofMap)
+            .index_type(KeyMode::index_type())
             .build();
 
         Self {
@@ -327,9 +444,34 @@ where
             state,
             _k: PhantomData,
             _v: PhantomData,
+            _key_mode: PhantomData,
         }
     }
 
+    /// Creates an index with the address and the type specified in the `builder`.
+    /// Used by indexes backed by a Merkle Patricia tree, such as `ProofKeySetIndex`.
+    pub(crate) fn from_builder(builder: IndexBuilder<T>) -> Self {
+        let (base, state) = builder.build();
+        Self {
+            base,
+            state,
+            _k: PhantomData,
+            _v: PhantomData,
+            _key_mode: PhantomData,
+        }
+    }
+
+    /// Similar to `from_builder`, but returns `None` if the index has not been created yet.
+    pub(crate) fn from_builder_existed(builder: IndexBuilder<T>) -> Option<Self> {
+        builder.build_existed().map(|(base, state)| Self {
+            base,
+            state,
+            _k: PhantomData,
+            _v: PhantomData,
+            _key_mode: PhantomData,
+        })
+    }
+
     fn get_root_path(&self) -> Option<ProofPath> {
         self.state.get().proof_path
     }
@@ -422,7 +564,7 @@ where
     ///
     /// let proof = index.get_proof(Hash::default());
     /// ```
-    pub fn get_proof(&self, key: K) -> MapProof<K, V> {
+    pub fn get_proof(&self, key: K) -> MapProof<K, V, KeyMode> {
         create_proof(
             key,
             self.get_root_node(),
@@ -444,7 +586,7 @@ where
     ///
     /// let proof = index.get_multiproof(vec![vec![0; 32], vec![1; 32]]);
     /// ```
-    pub fn get_multiproof<KI>(&self, keys: KI) -> MapProof<K, V>
+    pub fn get_multiproof<KI>(&self, keys: KI) -> MapProof<K, V, KeyMode>
     where
         KI: IntoIterator<Item = K>,
     {
@@ -456,6 +598,69 @@ where
         )
     }
 
+    /// Returns the proof of completeness for all the entries of the map with the paths
+    /// within the specified range. Entries of the map are ordered by their paths rather
+    /// than by keys; the path of a key can be obtained with [`ProofPath::new()`]
+    /// (or with [`ToProofPath::transform_key()`] for the index key mode in general).
+    ///
+    /// For maps with raw keys, the keys are restored from their paths, so the proof is created
+    /// in time proportional to the number of entries within the range. For maps with hashed
+    /// keys, the keys cannot be restored from their paths, so this method iterates over
+    /// the entries of the map until all the keys within the range are found.
+    ///
+    /// See [`MapRangeProof`] for an example of usage.
+    ///
+    /// [`ProofPath::new()`]: struct.ProofPath.html#method.new
+    /// [`ToProofPath::transform_key()`]: trait.ToProofPath.html#tymethod.transform_key
+    /// [`MapRangeProof`]: struct.MapRangeProof.html
+    pub fn get_range_proof<R>(&self, range: R) -> MapRangeProof<K, V, KeyMode>
+    where
+        R: RangeBounds<ProofPath>,
+        K: ToOwned<Owned = K>,
+    {
+        create_range_proof(
+            cloned_bound(range.start_bound()),
+            cloned_bound(range.end_bound()),
+            self.get_root_node(),
+            |path| self.get_node_unchecked(path),
+            |paths| {
+                let keys = paths
+                    .iter()
+                    .map(KeyMode::restore_key)
+                    .collect::<Option<Vec<_>>>();
+                if let Some(keys) = keys {
+                    return keys
+                        .into_iter()
+                        .map(|key| {
+                            let value = self.get_value_unchecked(&key);
+                            (key, value)
+                        })
+                        .collect();
+                }
+
+                let mut entries = self
+                    .iter()
+                    .map(|(key, value)| (KeyMode::transform_key(&key), key, value))
+                    .filter(|(path, ..)| {
+                        paths
+                            .binary_search_by(|p| {
+                                p.partial_cmp(path).expect("Incomparable leaf paths")
+                            })
+                            .is_ok()
+                    })
+                    .take(paths.len())
+                    .collect::<Vec<_>>();
+                entries.sort_unstable_by(|x, y| {
+                    x.0.partial_cmp(&y.0).expect("Incomparable leaf paths")
+                });
+                entries
+                    .into_iter()
+                    .map(|(_, key, value)| (key, value))
+                    .collect()
+            },
+        )
+    }
+
     /// Returns an iterator over the entries of the map in ascending order. The iterator element
     /// type is `(K::Output, V)`.
     ///
@@ -613,6 +818,90 @@ where
         }
     }
 
+    /// Returns an iterator over the entries of the map in descending order of keys.
+    /// The iterator element type is `(K::Output, V)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, ProofMapIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index: ProofMapIndex<_, [u8; 32], u8> = ProofMapIndex::new(name, &fork);
+    /// index.put(&[1; 32], 1_u8);
+    /// index.put(&[2; 32], 2_u8);
+    ///
+    /// let values = index.iter_rev().map(|(_, v)| v).collect::<Vec<_>>();
+    /// assert_eq!(values, vec![2, 1]);
+    /// ```
+    pub fn iter_rev(&self) -> ProofMapIndexIter<K, V> {
+        ProofMapIndexIter {
+            base_iter: self.base.iter_rev(&VALUE_KEY_PREFIX),
+            _k: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over the entries of the map with keys within the specified range
+    /// in ascending order of keys. The iterator element type is `(K::Output, V)`.
+    ///
+    /// Note that keys are compared by their binary representation, which does not affect
+    /// the Merkle tree; use [`get_range_proof`] to prove ranges of the tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, ProofMapIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index: ProofMapIndex<_, [u8; 32], u8> = ProofMapIndex::new(name, &fork);
+    /// for i in 1_u8..5 {
+    ///     index.put(&[i; 32], i);
+    /// }
+    ///
+    /// let values = index.range([2; 32]..[4; 32]).map(|(_, v)| v).collect::<Vec<_>>();
+    /// assert_eq!(values, vec![2, 3]);
+    /// ```
+    ///
+    /// [`get_range_proof`]: #method.get_range_proof
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> ProofMapIndexIter<K, V> {
+        ProofMapIndexIter {
+            base_iter: self.base.range(&VALUE_KEY_PREFIX, value_path_range(&range)),
+            _k: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over the entries of the map with keys within the specified range
+    /// in descending order of keys. The iterator element type is `(K::Output, V)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, ProofMapIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let fork = db.fork();
+    /// let mut index: ProofMapIndex<_, [u8; 32], u8> = ProofMapIndex::new(name, &fork);
+    /// for i in 1_u8..5 {
+    ///     index.put(&[i; 32], i);
+    /// }
+    ///
+    /// let values = index.range_rev(..=[2; 32]).map(|(_, v)| v).collect::<Vec<_>>();
+    /// assert_eq!(values, vec![2, 1]);
+    /// ```
+    pub fn range_rev<R: RangeBounds<K>>(&self, range: R) -> ProofMapIndexIter<K, V> {
+        ProofMapIndexIter {
+            base_iter: self
+                .base
+                .range_rev(&VALUE_KEY_PREFIX, value_path_range(&range)),
+            _k: PhantomData,
+        }
+    }
+
     fn insert_leaf(&mut self, proof_path: &ProofPath, key: &K, value: V) -> Hash {
         debug_assert!(proof_path.is_leaf());
         let hash = HashTag::hash_leaf(&value.to_bytes());
@@ -764,7 +1053,7 @@ where
     /// assert!(index.contains(&hash));
     /// ```
     pub fn put(&mut self, key: &K, value: V) {
-        let proof_path = ProofPath::new(key);
+        let proof_path = KeyMode::transform_key(key);
         let root_path = match self.get_root_node() {
             Some((prefix, Node::Leaf(prefix_data))) => {
                 let prefix_path = prefix;
@@ -845,7 +1134,7 @@ where
     /// assert!(!index.contains(&hash));
     /// ```
     pub fn remove(&mut self, key: &K) {
-        let proof_path = ProofPath::new(key);
+        let proof_path = KeyMode::transform_key(key);
         match self.get_root_node() {
             // If we have only on leaf, then we just need to remove it (if any)
             Some((prefix, Node::Leaf(_))) => {
@@ -963,11 +1252,21 @@ where
     }
 }
 
-impl<T, K, V> ObjectHash for ProofMapIndex<T, K, V>
+// `Bound::cloned()` is not stable yet.
+fn cloned_bound(bound: Bound<&ProofPath>) -> Bound<ProofPath> {
+    match bound {
+        Bound::Included(path) => Bound::Included(*path),
+        Bound::Excluded(path) => Bound::Excluded(*path),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+impl<T, K, V, KeyMode> ObjectHash for ProofMapIndex<T, K, V, KeyMode>
 where
     T: IndexAccess,
-    K: BinaryKey + ObjectHash,
+    K: BinaryKey,
     V: BinaryValue + ObjectHash,
+    KeyMode: ToProofPath<K>,
 {
     /// Returns the hash of the proof map object. See [`HashTag::hash_map_node`].
     /// For hash of the empty map see [`HashTag::empty_map_hash`].
@@ -998,11 +1297,12 @@ where
     }
 }
 
-impl<'a, T, K, V> ::std::iter::IntoIterator for &'a ProofMapIndex<T, K, V>
+impl<'a, T, K, V, KeyMode> ::std::iter::IntoIterator for &'a ProofMapIndex<T, K, V, KeyMode>
 where
     T: IndexAccess,
-    K: BinaryKey + ObjectHash,
+    K: BinaryKey,
     V: BinaryValue + ObjectHash,
+    KeyMode: ToProofPath<K>,
 {
     type Item = (K::Owned, V);
     type IntoIter = ProofMapIndexIter<'a, K, V>;
@@ -1049,27 +1349,33 @@ where
 }
 
 #[allow(clippy::use_self)]
-impl<T, K, V> fmt::Debug for ProofMapIndex<T, K, V>
+impl<T, K, V, KeyMode> fmt::Debug for ProofMapIndex<T, K, V, KeyMode>
 where
     T: IndexAccess,
-    K: BinaryKey + ObjectHash,
+    K: BinaryKey,
     V: BinaryValue + ObjectHash + fmt::Debug,
+    KeyMode: ToProofPath<K>,
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        struct Entry<'a, T: 'a + IndexAccess, K: 'a, V: 'a + BinaryValue> {
-            index: &'a ProofMapIndex<T, K, V>,
+        struct Entry<'a, T: 'a + IndexAccess, K: 'a, V: 'a + BinaryValue, KeyMode: 'a> {
+            index: &'a ProofMapIndex<T, K, V, KeyMode>,
             path: ProofPath,
             hash: Hash,
             node: Node,
         }
 
-        impl<'a, T, K, V> Entry<'a, T, K, V>
+        impl<'a, T, K, V, KeyMode> Entry<'a, T, K, V, KeyMode>
         where
             T: IndexAccess,
-            K: BinaryKey + ObjectHash,
+            K: BinaryKey,
             V: BinaryValue + ObjectHash,
+            KeyMode: ToProofPath<K>,
         {
-            fn new(index: &'a ProofMapIndex<T, K, V>, hash: Hash, path: ProofPath) -> Self {
+            fn new(
+                index: &'a ProofMapIndex<T, K, V, KeyMode>,
+                hash: Hash,
+                path: ProofPath,
+            ) -> Self {
                 Entry {
                     index,
                     path,
@@ -1087,11 +1393,12 @@ where
             }
         }
 
-        impl<'a, T, K, V> fmt::Debug for Entry<'a, T, K, V>
+        impl<'a, T, K, V, KeyMode> fmt::Debug for Entry<'a, T, K, V, KeyMode>
         where
             T: IndexAccess,
-            K: BinaryKey + ObjectHash,
+            K: BinaryKey,
             V: BinaryValue + ObjectHash + fmt::Debug,
+            KeyMode: ToProofPath<K>,
         {
             fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
                 match self.node {
@@ -16,17 +16,27 @@ use failure::Fail;
 use serde::{Deserializer, Serializer};
 use serde_derive::{Deserialize, Serialize};
 
+use std::{
+    borrow::Cow,
+    marker::PhantomData,
+    ops::Bound::{self, Excluded, Included, Unbounded},
+};
+
 use exonum_crypto::Hash;
 
 use super::{
-    key::{BitsRange, ChildKind, ProofPath, KEY_SIZE},
+    key::{BitsRange, ChildKind, Hashed, ProofPath, ToProofPath, KEY_SIZE, PROOF_PATH_SIZE},
     node::{BranchNode, Node},
 };
-use crate::{BinaryKey, BinaryValue, HashTag, ObjectHash};
+use crate::{encoding, BinaryKey, BinaryValue, HashTag, ObjectHash};
 
 // Expected size of the proof, in number of hashed entries.
 const DEFAULT_PROOF_CAPACITY: usize = 8;
 
+// Markers of missing keys and key-value pairs in the binary encoding of `MapProof`.
+const MISSING_ENTRY: u8 = 0;
+const PRESENT_ENTRY: u8 = 1;
+
 impl serde::Serialize for ProofPath {
     fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
     where
@@ -122,6 +132,20 @@ pub enum MapProofError {
     /// Entries in the proof are not ordered by increasing path.
     #[fail(display = "invalid path ordering")]
     InvalidOrdering(ProofPath, ProofPath),
+
+    /// An entry in a range proof lies outside the proven range.
+    #[fail(display = "entry outside the proven range")]
+    EntryOutOfRange(ProofPath),
+
+    /// A proof entry in a range proof intersects the proven range; thus, the proof
+    /// does not guarantee that it contains all the entries in the range.
+    #[fail(display = "proof entry intersecting the proven range")]
+    IncompleteRange(ProofPath),
+
+    /// A key in the proof cannot be converted to a path with the key mode of the proof
+    /// (e.g., a key of a raw map has an invalid size).
+    #[fail(display = "invalid key in proof")]
+    InvalidKey,
 }
 
 // Used instead of `(ProofPath, Hash)` only for the purpose of clearer (de)serialization.
@@ -188,6 +212,13 @@ impl<K, V> Into<(K, Option<V>)> for OptionalEntry<K, V> {
     }
 }
 
+// Used instead of `(K, V)` only for the purpose of clearer (de)serialization.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct RangeEntry<K, V> {
+    key: K,
+    value: V,
+}
+
 /// View of a `ProofMapIndex`, i.e., a subset of its elements coupled with a *proof*,
 /// which jointly allow restoring the `merkle_root()` of the index. Apart from the
 /// existing elements, `MapProof` can assert absence of certain keys from the underlying
@@ -253,14 +284,58 @@ impl<K, V> Into<(K, Option<V>)> for OptionalEntry<K, V> {
 /// # }
 /// ```
 ///
+/// # Binary serialization
+///
+/// `MapProof` implements [`BinaryValue`] with a compact binary layout, which is
+/// several times smaller than JSON. The layout consists of:
+///
+/// - a byte with the version of the layout (currently, 1)
+/// - the number of proof entries, followed by the entries; each entry is the path
+///   of the subtree (its length in bits followed by the significant bytes of the path)
+///   and the 32-byte hash of the subtree
+/// - the number of entries, followed by the entries; each entry is a byte specifying
+///   whether the key is missing from the index (0) or present in it (1), the key
+///   and, for present keys, the value
+///
+/// Numbers, including lengths, are encoded with the `leb128` algorithm. Keys and values
+/// are prefixed with their length in bytes.
+///
+/// ```
+/// # use exonum_merkledb::{Database, TemporaryDB, BinaryValue, MapProof, ProofMapIndex};
+/// # use exonum_crypto::{hash, Hash};
+/// let fork = { let db = TemporaryDB::new(); db.fork() };
+/// let mut map = ProofMapIndex::new("index", &fork);
+/// map.put(&hash(&[1]), 100u32);
+/// map.put(&hash(&[2]), 200u32);
+///
+/// let proof = map.get_proof(hash(&[1]));
+/// let bytes = proof.to_bytes();
+/// let restored_proof = MapProof::<Hash, u32>::from_bytes(bytes.into()).unwrap();
+/// assert_eq!(
+///     restored_proof.check().unwrap().root_hash(),
+///     proof.check().unwrap().root_hash()
+/// );
+/// ```
+///
+/// # Key mode
+///
+/// Like `ProofMapIndex`, `MapProof` is parameterized by the key mode, which specifies
+/// how keys are converted to paths during the proof verification. Proofs created by
+/// a raw index (see [`RawProofMapIndex`]) should be verified as `MapProof<K, V, Raw>`.
+/// The key mode does not affect the serialization of the proof.
+///
 /// [`get_proof()`]: struct.ProofMapIndex.html#method.get_proof
 /// [`get_multiproof()`]: struct.ProofMapIndex.html#method.get_multiproof
 /// [`check()`]: #method.check
 /// [`ProofPath`]: struct.ProofPath.html
+/// [`BinaryValue`]: ../trait.BinaryValue.html
+/// [`RawProofMapIndex`]: type.RawProofMapIndex.html
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct MapProof<K, V> {
+pub struct MapProof<K, V, KeyMode = Hashed> {
     entries: Vec<OptionalEntry<K, V>>,
     proof: Vec<MapProofEntry>,
+    #[serde(skip)]
+    _key_mode: PhantomData<KeyMode>,
 }
 
 /// Version of `MapProof` obtained after verification.
@@ -274,6 +349,164 @@ pub struct CheckedMapProof<K, V> {
     hash: Hash,
 }
 
+/// Proof of completeness for a range of a `ProofMapIndex`, i.e., all the entries of the map
+/// with the paths (see [`ProofPath`]) within a certain range, coupled with a *proof*,
+/// which allows restoring the `merkle_root()` of the index.
+///
+/// Entries of a `ProofMapIndex` are ordered by their paths, rather than by keys, so the range
+/// is specified in terms of paths. The paths of the keys can be obtained with
+/// [`ProofPath::new()`]. Unlike a [`MapProof`], a verified range proof guarantees that
+/// no entries within the range were omitted. This allows, for example, to paginate
+/// over the map, proving each page against the root hash.
+///
+/// # Workflow
+///
+/// Range proofs can be created with the [`get_range_proof()`] method of `ProofMapIndex`
+/// and verified with [`check()`].
+///
+/// ```
+/// # use exonum_merkledb::{Database, TemporaryDB, ProofMapIndex, ObjectHash};
+/// # use exonum_merkledb::proof_map_index::ProofPath;
+/// # use exonum_crypto::hash;
+/// let fork = { let db = TemporaryDB::new(); db.fork() };
+/// let mut map = ProofMapIndex::new("index", &fork);
+/// for i in 0_u8..10 {
+///     map.put(&hash(&[i]), u32::from(i));
+/// }
+///
+/// // Get the proof for the first half of the map.
+/// let middle = ProofPath::new(&hash(&[5]));
+/// let proof = map.get_range_proof(..middle);
+/// let checked_proof = proof.check().unwrap();
+/// assert_eq!(checked_proof.root_hash(), map.object_hash());
+/// // All entries returned by the proof are within the range.
+/// assert!(checked_proof.entries().all(|(key, _)| ProofPath::new(key) < middle));
+///
+/// // Get the proof for the second half of the map.
+/// let checked_rest = map.get_range_proof(middle..).check().unwrap();
+/// assert_eq!(checked_rest.root_hash(), map.object_hash());
+/// assert_eq!(checked_proof.entries().count() + checked_rest.entries().count(), 10);
+/// ```
+///
+/// # JSON serialization
+///
+/// `MapRangeProof` is serialized to JSON as an object with the following fields:
+///
+/// - `start` and `end` are the bounds of the range, in the form of `{ "Included": ProofPath }`,
+///   `{ "Excluded": ProofPath }` or `"Unbounded"`.
+/// - `entries` is an array of `{ "key": K, "value": V }` objects for all key-value pairs
+///   within the range.
+/// - `proof` is an array of `{ "path": ProofPath, "hash": Hash }` objects for the subtrees
+///   of the map outside the range, sorted by increasing [`ProofPath`].
+///
+/// [`ProofPath`]: struct.ProofPath.html
+/// [`ProofPath::new()`]: struct.ProofPath.html#method.new
+/// [`MapProof`]: struct.MapProof.html
+/// [`get_range_proof()`]: struct.ProofMapIndex.html#method.get_range_proof
+/// [`check()`]: #method.check
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MapRangeProof<K, V, KeyMode = Hashed> {
+    start: Bound<ProofPath>,
+    end: Bound<ProofPath>,
+    entries: Vec<RangeEntry<K, V>>,
+    proof: Vec<MapProofEntry>,
+    #[serde(skip)]
+    _key_mode: PhantomData<KeyMode>,
+}
+
+/// Version of `MapRangeProof` obtained after verification.
+///
+/// See [`MapRangeProof`] for an example of usage.
+///
+/// [`MapRangeProof`]: struct.MapRangeProof.html#workflow
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CheckedMapRangeProof<K, V> {
+    start: Bound<ProofPath>,
+    end: Bound<ProofPath>,
+    entries: Vec<(K, V)>,
+    hash: Hash,
+}
+
+/// Checks if the leaf path lies within the range.
+fn range_contains(start: &Bound<ProofPath>, end: &Bound<ProofPath>, path: &ProofPath) -> bool {
+    let after_start = match *start {
+        Included(ref start) => start <= path,
+        Excluded(ref start) => start < path,
+        Unbounded => true,
+    };
+    let before_end = match *end {
+        Included(ref end) => path <= end,
+        Excluded(ref end) => path < end,
+        Unbounded => true,
+    };
+    after_start && before_end
+}
+
+/// Checks if the subtree with the specified path may contain leaves within the range.
+fn range_intersects(start: &Bound<ProofPath>, end: &Bound<ProofPath>, path: &ProofPath) -> bool {
+    let (min_leaf, max_leaf) = (path.min_leaf(), path.max_leaf());
+    let after_start = match *start {
+        Included(ref start) => *start <= max_leaf,
+        Excluded(ref start) => *start < max_leaf,
+        Unbounded => true,
+    };
+    let before_end = match *end {
+        Included(ref end) => min_leaf <= *end,
+        Excluded(ref end) => min_leaf < *end,
+        Unbounded => true,
+    };
+    after_start && before_end
+}
+
+/// Checks that the proof entries are ordered by increasing path and none of them
+/// is a prefix of another one.
+fn check_proof_entries(proof: &[MapProofEntry]) -> Result<(), MapProofError> {
+    use self::MapProofError::*;
+    use std::cmp::Ordering;
+
+    for w in proof.windows(2) {
+        let (prev_path, path) = (&w[0].path, &w[1].path);
+        match prev_path.partial_cmp(path) {
+            Some(Ordering::Less) => {
+                if path.starts_with(prev_path) {
+                    return Err(EmbeddedPaths {
+                        prefix: *prev_path,
+                        path: *path,
+                    });
+                }
+            }
+            Some(Ordering::Equal) => {
+                return Err(DuplicatePath(*path));
+            }
+            Some(Ordering::Greater) => {
+                return Err(InvalidOrdering(*prev_path, *path));
+            }
+            None => unreachable!("Incomparable keys in proof"),
+        }
+    }
+    Ok(())
+}
+
+/// Sorts the proof entries by path and checks that there are no duplicate paths.
+fn sort_proof_entries(proof: &mut [MapProofEntry]) -> Result<(), MapProofError> {
+    proof.sort_unstable_by(|x, y| {
+        x.path.partial_cmp(&y.path).expect(
+            "Incorrectly formed paths supplied to MapProof; \
+             paths should have `start` field set to 0",
+        )
+    });
+
+    // This check is required as duplicate paths can be introduced by entries
+    // (further, it's generally possible that two different entry keys lead to the same
+    // `ProofPath`).
+    for window in proof.windows(2) {
+        if window[0].path == window[1].path {
+            return Err(MapProofError::DuplicatePath(window[0].path));
+        }
+    }
+    Ok(())
+}
+
 /// Computes the root hash of the Merkle Patricia tree backing the specified entries
 /// in the map view.
 ///
@@ -432,15 +665,16 @@ impl<K, V> MapProofBuilder<K, V> {
     /// Creates a [`MapProof`] from the builder.
     ///
     /// [`MapProof`]: struct.MapProof.html
-    pub fn create(self) -> MapProof<K, V> {
+    pub fn create<KeyMode>(self) -> MapProof<K, V, KeyMode> {
         MapProof {
             entries: self.entries,
             proof: self.proof,
+            _key_mode: PhantomData,
         }
     }
 }
 
-impl<K, V> MapProof<K, V> {
+impl<K, V, KeyMode> MapProof<K, V, KeyMode> {
     /// Provides access to the proof part of the view. Useful mainly for debug purposes.
     pub fn proof_unchecked(&self) -> Vec<(ProofPath, Hash)> {
         self.proof
@@ -468,42 +702,26 @@ impl<K, V> MapProof<K, V> {
     }
 }
 
-impl<K, V> MapProof<K, V>
+impl<K, V, KeyMode> MapProof<K, V, KeyMode>
 where
-    K: BinaryKey + ObjectHash,
+    K: BinaryKey,
     V: BinaryValue + ObjectHash,
+    KeyMode: ToProofPath<K>,
 {
     fn precheck(&self) -> Result<(), MapProofError> {
         use self::MapProofError::*;
-        use std::cmp::Ordering;
 
         // Check that entries in proof are in increasing order
-        for w in self.proof.windows(2) {
-            let (prev_path, path) = (&w[0].path, &w[1].path);
-            match prev_path.partial_cmp(path) {
-                Some(Ordering::Less) => {
-                    if path.starts_with(prev_path) {
-                        return Err(EmbeddedPaths {
-                            prefix: *prev_path,
-                            path: *path,
-                        });
-                    }
-                }
-                Some(Ordering::Equal) => {
-                    return Err(DuplicatePath(*path));
-                }
-                Some(Ordering::Greater) => {
-                    return Err(InvalidOrdering(*prev_path, *path));
-                }
-                None => unreachable!("Incomparable keys in proof"),
-            }
-        }
+        check_proof_entries(&self.proof)?;
 
         // Check that no entry has a prefix among the paths in the proof entries.
         // In order to do this, it suffices to locate the closest smaller path in the proof entries
         // and check only it.
         for e in &self.entries {
-            let path = ProofPath::new(e.key());
+            if !KeyMode::is_valid_key(e.key()) {
+                return Err(InvalidKey);
+            }
+            let path = KeyMode::transform_key(e.key());
 
             match self.proof.binary_search_by(|pe| {
                 pe.path
@@ -559,28 +777,14 @@ where
 
         proof.extend(entries.iter().filter_map(|e| {
             e.as_kv().map(|(k, v)| MapProofEntry {
-                path: ProofPath::new(k),
+                path: KeyMode::transform_key(k),
                 hash: HashTag::hash_leaf(&v.to_bytes()),
             })
         }));
         // Rust docs state that in the case `self.proof` and `self.entries` are sorted
         // (which is the case for `MapProof`s returned by `ProofMapIndex.get_proof()`),
         // the sort is performed very quickly.
-        proof.sort_unstable_by(|x, y| {
-            x.path.partial_cmp(&y.path).expect(
-                "Incorrectly formed paths supplied to MapProof; \
-                 paths should have `start` field set to 0",
-            )
-        });
-
-        // This check is required as duplicate paths can be introduced by entries
-        // (further, it's generally possible that two different entry keys lead to the same
-        // `ProofPath`).
-        for window in proof.windows(2) {
-            if window[0].path == window[1].path {
-                return Err(MapProofError::DuplicatePath(window[0].path));
-            }
-        }
+        sort_proof_entries(&mut proof)?;
 
         collect(&proof).map(|h| CheckedMapProof {
             entries: entries.into_iter().map(OptionalEntry::into).collect(),
@@ -589,6 +793,80 @@ where
     }
 }
 
+impl<K, V, KeyMode> BinaryValue for MapProof<K, V, KeyMode>
+where
+    K: BinaryKey + ToOwned<Owned = K>,
+    V: BinaryValue,
+{
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        encoding::write_version(&mut buffer);
+
+        encoding::write_len(&mut buffer, self.proof.len());
+        let mut path_buffer = [0_u8; PROOF_PATH_SIZE];
+        for entry in &self.proof {
+            let path_len = entry.path.write_compressed(&mut path_buffer);
+            buffer.extend_from_slice(&path_buffer[..path_len]);
+            encoding::write_hash(&mut buffer, &entry.hash);
+        }
+
+        encoding::write_len(&mut buffer, self.entries.len());
+        for entry in &self.entries {
+            let key = entry.key();
+            let mut key_buffer = vec![0_u8; key.size()];
+            key.write(&mut key_buffer);
+            match entry.as_kv() {
+                Some((_, value)) => {
+                    encoding::write_u8(&mut buffer, PRESENT_ENTRY);
+                    encoding::write_bytes(&mut buffer, &key_buffer);
+                    encoding::write_bytes(&mut buffer, &value.to_bytes());
+                }
+                None => {
+                    encoding::write_u8(&mut buffer, MISSING_ENTRY);
+                    encoding::write_bytes(&mut buffer, &key_buffer);
+                }
+            }
+        }
+        buffer
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Result<Self, failure::Error> {
+        let mut reader = bytes.as_ref();
+        encoding::read_version(&mut reader)?;
+
+        let proof_len = encoding::read_len(&mut reader)?;
+        let mut proof = Vec::with_capacity(proof_len);
+        for _ in 0..proof_len {
+            let path = ProofPath::read_compressed(&mut reader)?;
+            let hash = encoding::read_hash(&mut reader)?;
+            proof.push(MapProofEntry { path, hash });
+        }
+
+        let entries_len = encoding::read_len(&mut reader)?;
+        let mut entries = Vec::with_capacity(entries_len);
+        for _ in 0..entries_len {
+            let marker = encoding::read_u8(&mut reader)?;
+            let key = encoding::read_key::<K>(&mut reader)?;
+            let entry = match marker {
+                MISSING_ENTRY => OptionalEntry::missing(key),
+                PRESENT_ENTRY => {
+                    let value = encoding::read_bytes(&mut reader)?;
+                    OptionalEntry::value(key, V::from_bytes(value.into())?)
+                }
+                _ => bail!("Invalid entry marker in the encoded map proof: {}", marker),
+            };
+            entries.push(entry);
+        }
+
+        encoding::read_end(reader)?;
+        Ok(Self {
+            entries,
+            proof,
+            _key_mode: PhantomData,
+        })
+    }
+}
+
 impl<K, V> CheckedMapProof<K, V> {
     /// Retrieves references to keys that the proof shows as missing from the map.
     pub fn missing_keys(&self) -> impl Iterator<Item = &K> {
@@ -618,16 +896,110 @@ impl<K, V> CheckedMapProof<K, V> {
     }
 }
 
+impl<K, V, KeyMode> MapRangeProof<K, V, KeyMode> {
+    /// Provides access to the proof part of the view. Useful mainly for debug purposes.
+    pub fn proof_unchecked(&self) -> Vec<(ProofPath, Hash)> {
+        self.proof.iter().map(|e| (e.path, e.hash)).collect()
+    }
+
+    /// Retrieves references to the entries in the proof.
+    /// This method does not perform any integrity checks of the proof.
+    pub fn entries_unchecked(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.entries.iter().map(|e| (&e.key, &e.value))
+    }
+}
+
+impl<K, V, KeyMode> MapRangeProof<K, V, KeyMode>
+where
+    K: BinaryKey,
+    V: BinaryValue + ObjectHash,
+    KeyMode: ToProofPath<K>,
+{
+    /// Consumes this proof producing a `CheckedMapRangeProof` structure.
+    ///
+    /// Fails if the proof is malformed, in particular, if any entry lies outside
+    /// the range, or if the proof does not guarantee that all the entries within the range
+    /// are present.
+    pub fn check(self) -> Result<CheckedMapRangeProof<K, V>, MapProofError> {
+        check_proof_entries(&self.proof)?;
+
+        // Proof entries represent subtrees of the map; none of them may contain leaves
+        // within the range, otherwise some entries may be omitted.
+        if let Some(entry) = self
+            .proof
+            .iter()
+            .find(|e| range_intersects(&self.start, &self.end, &e.path))
+        {
+            return Err(MapProofError::IncompleteRange(entry.path));
+        }
+
+        let (mut proof, entries) = (self.proof, self.entries);
+        for entry in &entries {
+            if !KeyMode::is_valid_key(&entry.key) {
+                return Err(MapProofError::InvalidKey);
+            }
+            let path = KeyMode::transform_key(&entry.key);
+            if !range_contains(&self.start, &self.end, &path) {
+                return Err(MapProofError::EntryOutOfRange(path));
+            }
+            proof.push(MapProofEntry {
+                path,
+                hash: HashTag::hash_leaf(&entry.value.to_bytes()),
+            });
+        }
+        sort_proof_entries(&mut proof)?;
+
+        let (start, end) = (self.start, self.end);
+        collect(&proof).map(|h| CheckedMapRangeProof {
+            start,
+            end,
+            entries: entries.into_iter().map(|e| (e.key, e.value)).collect(),
+            hash: HashTag::hash_map_node(h),
+        })
+    }
+}
+
+impl<K, V> CheckedMapRangeProof<K, V> {
+    /// Retrieves references to all the key-value pairs of the map within the range.
+    pub fn entries(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.entries.iter().map(|&(ref k, ref v)| (k, v))
+    }
+
+    /// Returns the start bound of the range.
+    pub fn start_bound(&self) -> Bound<&ProofPath> {
+        as_ref_bound(&self.start)
+    }
+
+    /// Returns the end bound of the range.
+    pub fn end_bound(&self) -> Bound<&ProofPath> {
+        as_ref_bound(&self.end)
+    }
+
+    /// Returns a hash of the map that this proof is constructed for.
+    pub fn root_hash(&self) -> Hash {
+        self.hash
+    }
+}
+
+fn as_ref_bound(bound: &Bound<ProofPath>) -> Bound<&ProofPath> {
+    match *bound {
+        Included(ref path) => Included(path),
+        Excluded(ref path) => Excluded(path),
+        Unbounded => Unbounded,
+    }
+}
+
 /// Creates a proof for a single key.
-pub fn create_proof<K, V, F, M>(
+pub fn create_proof<K, V, KeyMode, F, M>(
     key: K,
     root_node: Option<(ProofPath, Node)>,
     lookup: F,
     get_value: M,
-) -> MapProof<K, V>
+) -> MapProof<K, V, KeyMode>
 where
-    K: BinaryKey + ObjectHash,
+    K: BinaryKey,
     V: BinaryValue + ObjectHash,
+    KeyMode: ToProofPath<K>,
     F: Fn(&ProofPath) -> Node,
     M: Fn(&K) -> V,
 {
@@ -639,7 +1011,7 @@ where
         left_hashes
     }
 
-    let searched_path = ProofPath::new(&key);
+    let searched_path = KeyMode::transform_key(&key);
 
     match root_node {
         Some((root_path, Node::Branch(root_branch))) => {
@@ -832,15 +1204,16 @@ where
     builder
 }
 
-pub fn create_multiproof<K, V, KI, F, M>(
+pub fn create_multiproof<K, V, KeyMode, KI, F, M>(
     keys: KI,
     root_node: Option<(ProofPath, Node)>,
     lookup: F,
     get_value: M,
-) -> MapProof<K, V>
+) -> MapProof<K, V, KeyMode>
 where
-    K: BinaryKey + ObjectHash,
+    K: BinaryKey,
     V: BinaryValue + ObjectHash,
+    KeyMode: ToProofPath<K>,
     KI: IntoIterator<Item = K>,
     F: Fn(&ProofPath) -> Node,
     M: Fn(&K) -> V,
@@ -850,7 +1223,10 @@ where
             let mut builder = MapProofBuilder::new();
 
             let searched_paths = {
-                let mut keys: Vec<_> = keys.into_iter().map(|k| (ProofPath::new(&k), k)).collect();
+                let mut keys: Vec<_> = keys
+                    .into_iter()
+                    .map(|k| (KeyMode::transform_key(&k), k))
+                    .collect();
 
                 keys.sort_unstable_by(|x, y| {
                     // `unwrap` is safe here because all keys start from the same position `0`
@@ -887,7 +1263,7 @@ where
             let mut found_key: Option<K> = None;
 
             for key in keys {
-                let searched_path = ProofPath::new(&key);
+                let searched_path = KeyMode::transform_key(&key);
                 if root_path == searched_path {
                     found_key = Some(key);
                 } else {
@@ -913,3 +1289,93 @@ where
             .create(),
     }
 }
+
+/// Visits the children of a branch node intersecting the range, collecting paths of leaves
+/// within the range and proof entries for the subtrees outside the range.
+fn visit_range<F>(
+    range: (&Bound<ProofPath>, &Bound<ProofPath>),
+    branch: &BranchNode,
+    lookup: &F,
+    leaves: &mut Vec<ProofPath>,
+    proof: &mut Vec<MapProofEntry>,
+) where
+    F: Fn(&ProofPath) -> Node,
+{
+    for &kind in &[ChildKind::Left, ChildKind::Right] {
+        let path = branch.child_path(kind);
+        if !range_intersects(range.0, range.1, &path) {
+            proof.push(MapProofEntry {
+                path,
+                hash: branch.child_hash(kind),
+            });
+            continue;
+        }
+
+        match lookup(&path) {
+            Node::Branch(child) => visit_range(range, &child, lookup, leaves, proof),
+            Node::Leaf(_) => leaves.push(path),
+        }
+    }
+}
+
+/// Creates a proof for all the entries with the paths within the specified range.
+///
+/// `get_entries` receives paths of the leaves within the range in increasing order
+/// and should return the corresponding key-value pairs in the same order.
+pub fn create_range_proof<K, V, KeyMode, F, E>(
+    start: Bound<ProofPath>,
+    end: Bound<ProofPath>,
+    root_node: Option<(ProofPath, Node)>,
+    lookup: F,
+    get_entries: E,
+) -> MapRangeProof<K, V, KeyMode>
+where
+    K: BinaryKey,
+    V: BinaryValue + ObjectHash,
+    KeyMode: ToProofPath<K>,
+    F: Fn(&ProofPath) -> Node,
+    E: FnOnce(&[ProofPath]) -> Vec<(K, V)>,
+{
+    let mut leaves = Vec::new();
+    let mut proof = Vec::with_capacity(DEFAULT_PROOF_CAPACITY);
+
+    match root_node {
+        Some((_, Node::Branch(root_branch))) => {
+            visit_range(
+                (&start, &end),
+                &root_branch,
+                &lookup,
+                &mut leaves,
+                &mut proof,
+            );
+        }
+        Some((root_path, Node::Leaf(hash))) => {
+            if range_contains(&start, &end, &root_path) {
+                leaves.push(root_path);
+            } else {
+                proof.push(MapProofEntry {
+                    path: root_path,
+                    hash,
+                });
+            }
+        }
+        None => {}
+    }
+
+    let entries = if leaves.is_empty() {
+        Vec::new()
+    } else {
+        get_entries(&leaves)
+            .into_iter()
+            .map(|(key, value)| RangeEntry { key, value })
+            .collect()
+    };
+
+    MapRangeProof {
+        start,
+        end,
+        entries,
+        proof,
+        _key_mode: PhantomData,
+    }
+}
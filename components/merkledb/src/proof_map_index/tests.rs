@@ -12,7 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{cmp, collections::HashSet, fmt::Debug, hash::Hash as StdHash};
+use std::{
+    cmp,
+    collections::HashSet,
+    fmt::Debug,
+    hash::Hash as StdHash,
+    ops::{Bound, RangeBounds},
+};
 
 use pretty_assertions::assert_eq;
 use rand::{
@@ -29,7 +35,7 @@ use super::{
     key::{BitsRange, ChildKind, KEY_SIZE, LEAF_KEY_PREFIX},
     node::BranchNode,
     proof::MapProofBuilder,
-    MapProof, MapProofError, ProofMapIndex, ProofPath,
+    MapProof, MapProofError, MapRangeProof, ProofMapIndex, ProofPath, Raw, RawProofMapIndex,
 };
 use crate::{BinaryKey, BinaryValue, Database, Fork, HashTag, ObjectHash, TemporaryDB};
 
@@ -387,11 +393,12 @@ fn test_fuzz_insert() {
 
 fn check_map_proof<K, V>(proof: MapProof<K, V>, key: Option<K>, table: &ProofMapIndex<&Fork, K, V>)
 where
-    K: BinaryKey + ObjectHash + PartialEq + Debug + Serialize + DeserializeOwned,
+    K: BinaryKey<Owned = K> + ObjectHash + PartialEq + Debug + Serialize + DeserializeOwned,
     V: BinaryValue + ObjectHash + PartialEq + Debug + Serialize + DeserializeOwned,
 {
     let serialized_proof = serde_json::to_value(&proof).unwrap();
     let deserialized_proof: MapProof<K, V> = serde_json::from_value(serialized_proof).unwrap();
+    let binary_proof = MapProof::<K, V>::from_bytes(proof.to_bytes().into()).unwrap();
 
     let entries = match key {
         Some(key) => {
@@ -417,6 +424,13 @@ where
         proof.entries().collect::<Vec<_>>()
     );
     assert_eq!(deserialized_proof.root_hash(), proof.root_hash());
+
+    let binary_proof = binary_proof.check().unwrap();
+    assert_eq!(
+        binary_proof.entries().collect::<Vec<_>>(),
+        proof.entries().collect::<Vec<_>>()
+    );
+    assert_eq!(binary_proof.root_hash(), proof.root_hash());
 }
 
 fn check_map_multiproof<K, V>(
@@ -478,7 +492,7 @@ const MAX_CHECKED_ELEMENTS: usize = 1_024;
 
 fn check_proofs_for_data<K, V>(db: &dyn Database, data: &[(K, V)], nonexisting_keys: &[K])
 where
-    K: BinaryKey + ObjectHash + Clone + PartialEq + Debug + Serialize + DeserializeOwned,
+    K: BinaryKey<Owned = K> + ObjectHash + Clone + PartialEq + Debug + Serialize + DeserializeOwned,
     V: BinaryValue + ObjectHash + Clone + PartialEq + Debug + Serialize + DeserializeOwned,
 {
     let storage = db.fork();
@@ -623,6 +637,67 @@ fn test_invalid_map_proofs() {
     }
 }
 
+#[test]
+fn test_invalid_binary_map_proofs() {
+    let db = TemporaryDB::default();
+    let storage = db.fork();
+    let mut table = ProofMapIndex::new(IDX_NAME, &storage);
+    for i in 0_u8..16 {
+        table.put(&hash(&[i]), vec![i]);
+    }
+    let proof = table.get_multiproof(vec![hash(&[1]), hash(&[100])]);
+    let bytes = proof.to_bytes();
+    assert!(bytes.len() < serde_json::to_string(&proof).unwrap().len());
+
+    // Truncated or extended proofs are rejected.
+    for len in 0..bytes.len() {
+        assert!(MapProof::<Hash, Vec<u8>>::from_bytes(bytes[..len].into()).is_err());
+    }
+    let mut extended_bytes = bytes.clone();
+    extended_bytes.push(0);
+    assert!(MapProof::<Hash, Vec<u8>>::from_bytes(extended_bytes.into()).is_err());
+
+    // Unknown versions of the layout are rejected.
+    let mut bytes = bytes;
+    bytes[0] = 2;
+    assert!(MapProof::<Hash, Vec<u8>>::from_bytes(bytes.into()).is_err());
+}
+
+#[test]
+fn test_binary_map_proof_with_truncated_key() {
+    let db = TemporaryDB::default();
+    let storage = db.fork();
+    let table: ProofMapIndex<_, Hash, Vec<u8>> = ProofMapIndex::new(IDX_NAME, &storage);
+    // The proof for an empty map consists of a single missing entry.
+    let bytes = table.get_proof(hash(&[1])).to_bytes();
+    assert_eq!(bytes.len(), 5 + KEY_SIZE);
+    assert_eq!(bytes[4] as usize, KEY_SIZE);
+
+    // Keys of fixed-size types are checked before reading them.
+    let mut truncated_bytes = bytes[..4].to_vec();
+    truncated_bytes.push(3);
+    truncated_bytes.extend_from_slice(&bytes[5..8]);
+    let err = MapProof::<Hash, Vec<u8>>::from_bytes(truncated_bytes.into()).unwrap_err();
+    assert!(err.to_string().contains("Invalid size of a key"));
+    let mut truncated_bytes = bytes[..4].to_vec();
+    truncated_bytes.push(0);
+    assert!(MapProof::<u64, Vec<u8>>::from_bytes(truncated_bytes.into()).is_err());
+}
+
+#[test]
+fn test_binary_map_proof_with_invalid_utf8_key() {
+    let db = TemporaryDB::default();
+    let storage = db.fork();
+    let table: ProofMapIndex<_, String, Vec<u8>> = ProofMapIndex::new(IDX_NAME, &storage);
+    let mut bytes = table.get_proof("key".to_owned()).to_bytes();
+    assert_eq!(&bytes[4..], b"\x03key");
+
+    // Keys of variable-size types are validated with `BinaryKey::try_read` as well.
+    bytes[5..].copy_from_slice(&[0xff, 0xfe, 0xfd]);
+    let err = MapProof::<String, Vec<u8>>::from_bytes(bytes.into()).unwrap_err();
+    assert!(err.to_string().contains("Invalid key"));
+}
+
 #[test]
 fn test_build_proof_in_empty_tree() {
     let db = TemporaryDB::default();
@@ -1149,6 +1224,190 @@ fn test_fuzz_insert_build_multiproofs() {
     }
 }
 
+fn check_range_proof<K, V, R>(table: &ProofMapIndex<&Fork, K, V>, data: &[(K, V)], range: R)
+where
+    K: BinaryKey<Owned = K> + ObjectHash + PartialEq + Debug + Clone + Serialize + DeserializeOwned,
+    V: BinaryValue + ObjectHash + PartialEq + Debug + Clone + Serialize + DeserializeOwned,
+    R: RangeBounds<ProofPath> + Clone,
+{
+    let proof = table.get_range_proof(range.clone());
+    let serialized_proof = serde_json::to_value(&proof).unwrap();
+    let deserialized_proof: MapRangeProof<K, V> = serde_json::from_value(serialized_proof).unwrap();
+    let checked_proof = deserialized_proof.check().unwrap();
+    assert_eq!(checked_proof.root_hash(), table.object_hash());
+
+    let mut expected = data
+        .iter()
+        .filter(|(key, _)| range.contains(&ProofPath::new(key)))
+        .map(|(key, value)| (ProofPath::new(key), key, value))
+        .collect::<Vec<_>>();
+    expected.sort_unstable_by(|x, y| x.0.partial_cmp(&y.0).unwrap());
+    let expected = expected
+        .into_iter()
+        .map(|(_, key, value)| (key, value))
+        .collect::<Vec<_>>();
+    assert_eq!(checked_proof.entries().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn test_build_range_proof_in_empty_tree() {
+    let db = TemporaryDB::default();
+    let storage = db.fork();
+    let mut table = ProofMapIndex::new(IDX_NAME, &storage);
+
+    table.put(&[230; 32], vec![1]);
+    table.remove(&[230; 32]);
+
+    let proof = table.get_range_proof(..);
+    assert_eq!(proof.proof_unchecked(), vec![]);
+    check_range_proof(&table, &[], ..);
+}
+
+#[test]
+fn test_build_range_proof_in_single_node_tree() {
+    let db = TemporaryDB::default();
+    let storage = db.fork();
+    let mut table = ProofMapIndex::new(IDX_NAME, &storage);
+    table.put(&[230; 32], vec![1]);
+    let data = vec![([230; 32], vec![1])];
+
+    let path = ProofPath::new(&[230; 32]);
+    let proof = table.get_range_proof(path..=path);
+    assert_eq!(proof.proof_unchecked(), vec![]);
+    check_range_proof(&table, &data, path..=path);
+    check_range_proof(&table, &data, ..);
+
+    let proof = table.get_range_proof(..path);
+    assert_eq!(
+        proof.proof_unchecked(),
+        vec![(path, HashTag::hash_leaf(&[1]))]
+    );
+    check_range_proof(&table, &data, ..path);
+    check_range_proof(&table, &data, (Bound::Excluded(path), Bound::Unbounded));
+}
+
+#[test]
+fn test_fuzz_insert_build_range_proofs() {
+    const SAMPLE_SIZE: usize = 200;
+    const RANGES_COUNT: usize = 20;
+
+    let db = TemporaryDB::default();
+    let mut rng = thread_rng();
+    let mut exists_keys = HashSet::default();
+    let data = generate_random_data_keys(&mut exists_keys, SAMPLE_SIZE, &mut rng);
+
+    let storage = db.fork();
+    let mut table = ProofMapIndex::new(IDX_NAME, &storage);
+    for item in &data {
+        table.put(&item.0, item.1.clone());
+    }
+
+    check_range_proof(&table, &data, ..);
+    for _ in 0..RANGES_COUNT {
+        // Use both paths of existing keys and random paths as the bounds.
+        let mut bounds = data
+            .choose_multiple(&mut rng, 2)
+            .map(|(key, _)| ProofPath::new(key))
+            .collect::<Vec<_>>();
+        bounds.push(ProofPath::new(&rng.gen::<[u8; 32]>()));
+        bounds.sort_unstable_by(|x, y| x.partial_cmp(y).unwrap());
+        let (start, end) = (bounds[0], bounds[rng.gen_range(1, 3)]);
+
+        check_range_proof(&table, &data, start..end);
+        check_range_proof(&table, &data, start..=end);
+        check_range_proof(&table, &data, start..);
+        check_range_proof(&table, &data, ..end);
+        check_range_proof(
+            &table,
+            &data,
+            (Bound::Excluded(start), Bound::Included(end)),
+        );
+    }
+}
+
+#[test]
+fn test_build_range_proofs_with_raw_keys() {
+    const SAMPLE_SIZE: usize = 100;
+    const RANGES_COUNT: usize = 20;
+
+    let db = TemporaryDB::default();
+    let storage = db.fork();
+    let mut rng = thread_rng();
+    let mut table: RawProofMapIndex<_, Hash, u64> = ProofMapIndex::new_raw(IDX_NAME, &storage);
+    let mut data = (0..SAMPLE_SIZE as u64)
+        .map(|i| (hash(&i.to_le_bytes()), i))
+        .collect::<Vec<_>>();
+    for (key, value) in &data {
+        table.put(key, *value);
+    }
+    // Raw keys are ordered by their paths.
+    data.sort_unstable_by(|x, y| {
+        let (x_path, y_path) = (ProofPath::from_bytes(x.0), ProofPath::from_bytes(y.0));
+        x_path.partial_cmp(&y_path).unwrap()
+    });
+
+    for _ in 0..RANGES_COUNT {
+        let mut bounds = [rng.gen_range(0, SAMPLE_SIZE), rng.gen_range(0, SAMPLE_SIZE)];
+        bounds.sort();
+        let start = ProofPath::from_bytes(data[bounds[0]].0);
+        let end = ProofPath::from_bytes(data[bounds[1]].0);
+
+        let checked_proof = table.get_range_proof(start..end).check().unwrap();
+        assert_eq!(checked_proof.root_hash(), table.object_hash());
+        let expected = data[bounds[0]..bounds[1]]
+            .iter()
+            .map(|(key, value)| (key, value))
+            .collect::<Vec<_>>();
+        assert_eq!(checked_proof.entries().collect::<Vec<_>>(), expected);
+    }
+}
+
+#[test]
+fn test_invalid_range_proofs() {
+    use self::MapProofError::*;
+
+    let db = TemporaryDB::default();
+    let storage = db.fork();
+    let mut table = ProofMapIndex::new(IDX_NAME, &storage);
+    for i in 0_u8..16 {
+        table.put(&hash(&[i]), vec![i]);
+    }
+    let mut paths = (0_u8..16)
+        .map(|i| ProofPath::new(&hash(&[i])))
+        .collect::<Vec<_>>();
+    paths.sort_unstable_by(|x, y| x.partial_cmp(y).unwrap());
+    let (start, end) = (paths[4], paths[10]);
+
+    let proof = table.get_range_proof(start..end);
+    assert!(!proof.proof_unchecked().is_empty());
+
+    // Extend the range so that it intersects with a proof entry.
+    let mut json = serde_json::to_value(&proof).unwrap();
+    json["end"] = serde_json::to_value(Bound::<ProofPath>::Unbounded).unwrap();
+    let tampered_proof: MapRangeProof<Hash, Vec<u8>> = serde_json::from_value(json).unwrap();
+    match tampered_proof.check().unwrap_err() {
+        IncompleteRange(..) => {}
+        e => panic!("expected incomplete range error, got {}", e),
+    }
+
+    // Narrow the range so that an entry lies outside of it.
+    let mut json = serde_json::to_value(&proof).unwrap();
+    json["start"] = serde_json::to_value(Bound::Excluded(start)).unwrap();
+    let tampered_proof: MapRangeProof<Hash, Vec<u8>> = serde_json::from_value(json).unwrap();
+    match tampered_proof.check().unwrap_err() {
+        EntryOutOfRange(path) => assert_eq!(path, start),
+        e => panic!("expected entry out of range error, got {}", e),
+    }
+
+    // Remove an entry from the proof.
+    let mut json = serde_json::to_value(&proof).unwrap();
+    json["entries"].as_array_mut().unwrap().remove(1);
+    let tampered_proof: MapRangeProof<Hash, Vec<u8>> = serde_json::from_value(json).unwrap();
+    if let Ok(checked_proof) = tampered_proof.check() {
+        assert_ne!(checked_proof.root_hash(), table.object_hash());
+    }
+}
+
 #[test]
 fn test_fuzz_delete_build_proofs() {
     const SAMPLE_SIZE: usize = 200;
@@ -1480,3 +1739,105 @@ fn test_tree_with_hashed_key() {
         hash_isolated_node(&ProofPath::new(&other_key), &HashTag::hash_leaf(&[1, 2, 3]))
     );
 }
+
+#[test]
+fn test_raw_proof_map_index() {
+    fn hash_isolated_node(key: &ProofPath, h: &Hash) -> Hash {
+        HashTag::hash_map_node(HashTag::hash_single_entry_map(&key, &h))
+    }
+
+    let db = TemporaryDB::default();
+    let storage = db.fork();
+    let mut table: RawProofMapIndex<_, Hash, Vec<u8>> = ProofMapIndex::new_raw(IDX_NAME, &storage);
+
+    let (key, other_key) = (hash(&[1, 2]), hash(&[3, 4]));
+    table.put(&key, vec![1, 2, 3]);
+    table.put(&other_key, vec![2, 3, 4]);
+    assert_eq!(table.get(&key), Some(vec![1, 2, 3]));
+    assert_eq!(table.get(&hash(&[2, 2])), None);
+
+    let proof = table.get_proof(key);
+    assert_eq!(
+        proof.proof_unchecked(),
+        vec![(
+            ProofPath::from_bytes(other_key.as_ref()),
+            HashTag::hash_leaf(&[2, 3, 4])
+        )]
+    );
+    let checked_proof = proof.clone().check().unwrap();
+    assert_eq!(
+        checked_proof.entries().collect::<Vec<_>>(),
+        vec![(&key, &vec![1, 2, 3])]
+    );
+    assert_eq!(checked_proof.root_hash(), table.object_hash());
+
+    // Binary serialization of a raw proof.
+    let restored = MapProof::<Hash, Vec<u8>, Raw>::from_bytes(proof.to_bytes().into()).unwrap();
+    assert_eq!(restored.check().unwrap().root_hash(), table.object_hash());
+
+    // A raw proof is not valid as a proof for the hashed index.
+    let hashed_proof = MapProof::<Hash, Vec<u8>>::from_bytes(proof.to_bytes().into()).unwrap();
+    assert_ne!(
+        hashed_proof.check().unwrap().root_hash(),
+        table.object_hash()
+    );
+
+    let missing_key = hash(&[5, 6]);
+    let proof = table.get_multiproof(vec![key, other_key, missing_key]);
+    let checked_proof = proof.check().unwrap();
+    assert_eq!(checked_proof.entries().count(), 2);
+    assert_eq!(
+        checked_proof.missing_keys().collect::<Vec<_>>(),
+        vec![&missing_key]
+    );
+    assert_eq!(checked_proof.root_hash(), table.object_hash());
+
+    let range_proof = table.get_range_proof(..);
+    let checked_proof = range_proof.check().unwrap();
+    assert_eq!(checked_proof.entries().count(), 2);
+    assert_eq!(checked_proof.root_hash(), table.object_hash());
+
+    table.remove(&other_key);
+    assert_eq!(table.get(&other_key), None);
+    assert_eq!(
+        table.object_hash(),
+        hash_isolated_node(
+            &ProofPath::from_bytes(key.as_ref()),
+            &HashTag::hash_leaf(&[1, 2, 3])
+        )
+    );
+}
+
+#[test]
+#[should_panic(expected = "Index type doesn't match specified")]
+fn test_raw_proof_map_index_key_mode_is_checked() {
+    let db = TemporaryDB::default();
+    let fork = db.fork();
+    {
+        let mut table: RawProofMapIndex<_, Hash, u8> = ProofMapIndex::new_raw(IDX_NAME, &fork);
+        table.put(&hash(&[1, 2]), 1);
+    }
+    db.merge(fork.into_patch()).unwrap();
+
+    let snapshot = db.snapshot();
+    let _table: ProofMapIndex<_, Hash, u8> = ProofMapIndex::new(IDX_NAME, &snapshot);
+}
+
+#[test]
+fn test_raw_map_proof_with_invalid_key() {
+    let proof = MapProofBuilder::new()
+        .add_entry(vec![1_u8, 2, 3], vec![4_u8, 5])
+        .create::<Raw>();
+    match proof.check().unwrap_err() {
+        MapProofError::InvalidKey => {}
+        e => panic!("expected an invalid key error, got {}", e),
+    }
+
+    let proof = MapProofBuilder::<Vec<u8>, Vec<u8>>::new()
+        .add_missing(vec![0_u8; 31])
+        .create::<Raw>();
+    match proof.check().unwrap_err() {
+        MapProofError::InvalidKey => {}
+        e => panic!("expected an invalid key error, got {}", e),
+    }
+}
@@ -0,0 +1,366 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tracking of the data read through a fork.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    ops::Bound::{self, Excluded, Included, Unbounded},
+};
+
+use crate::db::{next_prefix, Change, Iter, Iterator as BytesIterator, Patch};
+
+/// Range of full keys within a column family.
+type KeyRange = (Bound<Vec<u8>>, Bound<Vec<u8>>);
+
+/// Keys and key ranges read from the database through a [`Fork`].
+///
+/// A read set is collected by [`Fork::start_read_tracking`] and
+/// [`Fork::finish_read_tracking`]. It allows to determine whether the data observed
+/// through the fork would be the same if the changes from a certain [`Patch`] were
+/// applied to the database beforehand. Iteration is tracked by the ranges of keys
+/// the iterators have actually passed, so the read set is conservative: it may report
+/// a patch affecting it even if the values yielded to the caller were not changed.
+///
+/// [`Fork`]: struct.Fork.html
+/// [`Fork::start_read_tracking`]: struct.Fork.html#method.start_read_tracking
+/// [`Fork::finish_read_tracking`]: struct.Fork.html#method.finish_read_tracking
+/// [`Patch`]: struct.Patch.html
+#[derive(Debug, Clone, Default)]
+pub struct ReadSet {
+    keys: HashMap<String, BTreeSet<Vec<u8>>>,
+    ranges: HashMap<String, Vec<KeyRange>>,
+}
+
+impl ReadSet {
+    /// Creates an empty read set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if nothing has been read.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty() && self.ranges.is_empty()
+    }
+
+    /// Returns `true` if the `patch` changes any key from the read set, either
+    /// directly or by removing a prefix of the key.
+    pub fn is_affected_by(&self, patch: &Patch) -> bool {
+        patch.iter().any(|(name, changes)| {
+            let prefixes = changes.prefixes_to_remove();
+            let keys_affected = self.keys.get(name).map_or(false, |keys| {
+                keys.iter().any(|key| {
+                    changes.data.contains_key(key)
+                        || prefixes.iter().any(|prefix| key.starts_with(prefix))
+                })
+            });
+            keys_affected
+                || self.ranges.get(name).map_or(false, |ranges| {
+                    ranges.iter().any(|range| {
+                        has_key_in_range(&changes.data, range)
+                            || prefixes
+                                .iter()
+                                .any(|prefix| prefix_intersects(prefix, range))
+                    })
+                })
+        })
+    }
+
+    pub(crate) fn add_key(&mut self, name: &str, key: &[u8]) {
+        if let Some(keys) = self.keys.get_mut(name) {
+            keys.insert(key.to_vec());
+            return;
+        }
+        let mut keys = BTreeSet::new();
+        keys.insert(key.to_vec());
+        self.keys.insert(name.to_owned(), keys);
+    }
+
+    fn add_range(&mut self, name: &str, range: KeyRange) {
+        self.ranges
+            .entry(name.to_owned())
+            .or_insert_with(Vec::new)
+            .push(range);
+    }
+}
+
+/// Checks if the changes contain a key within the `range`.
+fn has_key_in_range(data: &BTreeMap<Vec<u8>, Change>, range: &KeyRange) -> bool {
+    let from = match range.0 {
+        Included(ref key) => Included(key.as_slice()),
+        Excluded(ref key) => Excluded(key.as_slice()),
+        Unbounded => Unbounded,
+    };
+    data.range::<[u8], _>((from, Unbounded))
+        .next()
+        .map_or(false, |(key, _)| match range.1 {
+            Included(ref end) => key <= end,
+            Excluded(ref end) => key < end,
+            Unbounded => true,
+        })
+}
+
+/// Checks if the keys starting with the `prefix` may be within the `range`.
+fn prefix_intersects(prefix: &[u8], range: &KeyRange) -> bool {
+    let starts_before_prefix_end = match (&range.0, next_prefix(prefix)) {
+        (Included(start), Some(ref prefix_end)) | (Excluded(start), Some(ref prefix_end)) => {
+            start < prefix_end
+        }
+        _ => true,
+    };
+    let ends_after_prefix_start = match range.1 {
+        Included(ref end) => end.as_slice() >= prefix,
+        Excluded(ref end) => end.as_slice() > prefix,
+        Unbounded => true,
+    };
+    starts_before_prefix_end && ends_after_prefix_start
+}
+
+/// Iterator recording the range of keys it has passed into the read set on drop.
+pub(crate) struct TrackedIter<'a> {
+    inner: Iter<'a>,
+    reads: &'a RefCell<Option<ReadSet>>,
+    name: String,
+    // Bound the iteration has started from.
+    bound: Bound<Vec<u8>>,
+    // The farthest key passed by the iterator, or `Unbounded` if it has ended.
+    observed: Option<Bound<Vec<u8>>>,
+    reversed: bool,
+}
+
+impl<'a> TrackedIter<'a> {
+    pub(crate) fn new(
+        inner: Iter<'a>,
+        reads: &'a RefCell<Option<ReadSet>>,
+        name: &str,
+        bound: Bound<&[u8]>,
+        reversed: bool,
+    ) -> Self {
+        let bound = match bound {
+            Included(key) => Included(key.to_vec()),
+            Excluded(key) => Excluded(key.to_vec()),
+            Unbounded => Unbounded,
+        };
+        Self {
+            inner,
+            reads,
+            name: name.to_owned(),
+            bound,
+            observed: None,
+            reversed,
+        }
+    }
+}
+
+fn observe(observed: &mut Option<Bound<Vec<u8>>>, reversed: bool, key: Option<&[u8]>) {
+    let key = match key {
+        Some(key) => key,
+        None => {
+            *observed = Some(Unbounded);
+            return;
+        }
+    };
+    let is_farther = match observed {
+        None => true,
+        Some(Included(prev)) if reversed => key < prev.as_slice(),
+        Some(Included(prev)) => key > prev.as_slice(),
+        Some(_) => false,
+    };
+    if is_farther {
+        *observed = Some(Included(key.to_vec()));
+    }
+}
+
+impl<'a> BytesIterator for TrackedIter<'a> {
+    fn next(&mut self) -> Option<(&[u8], &[u8])> {
+        let item = self.inner.next();
+        observe(&mut self.observed, self.reversed, item.map(|(key, _)| key));
+        item
+    }
+
+    fn peek(&mut self) -> Option<(&[u8], &[u8])> {
+        let item = self.inner.peek();
+        observe(&mut self.observed, self.reversed, item.map(|(key, _)| key));
+        item
+    }
+}
+
+impl Drop for TrackedIter<'_> {
+    fn drop(&mut self) {
+        let observed = match self.observed.take() {
+            Some(observed) => observed,
+            None => return,
+        };
+        let bound = std::mem::replace(&mut self.bound, Unbounded);
+        let range = if self.reversed {
+            (observed, bound)
+        } else {
+            (bound, observed)
+        };
+        if let Some(reads) = self.reads.borrow_mut().as_mut() {
+            reads.add_range(&self.name, range);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound::{Excluded, Included, Unbounded};
+
+    use super::{prefix_intersects, ReadSet};
+    use crate::{Database, Fork, ListIndex, MapIndex, MemoryDB, TemporaryDB};
+
+    fn fill_map<T: Database>(db: &T) {
+        let fork = db.fork();
+        {
+            let mut map = MapIndex::new("map", &fork);
+            for i in 0_u8..10 {
+                map.put(&(i * 10), i);
+            }
+            ListIndex::new("list", &fork).push(1_u64);
+        }
+        db.merge(fork.into_patch()).unwrap();
+    }
+
+    fn read_set(fork: &Fork, read: impl FnOnce(&Fork)) -> ReadSet {
+        fork.start_read_tracking();
+        read(fork);
+        fork.finish_read_tracking()
+    }
+
+    fn patch_with<T: Database>(db: &T, update: impl FnOnce(&Fork)) -> crate::Patch {
+        let fork = db.fork();
+        update(&fork);
+        fork.into_patch()
+    }
+
+    fn _read_keys<T: Database>(db: &T) {
+        fill_map(db);
+        let fork = db.fork();
+        let reads = read_set(&fork, |fork| {
+            let map = MapIndex::<_, u8, u8>::new("map", fork);
+            assert_eq!(map.get(&20), Some(2));
+            assert!(!map.contains(&25));
+        });
+        assert!(!reads.is_empty());
+
+        let put = patch_with(db, |fork| {
+            MapIndex::<_, u8, u8>::new("map", fork).put(&20, 0)
+        });
+        assert!(reads.is_affected_by(&put));
+        let insert = patch_with(db, |fork| {
+            MapIndex::<_, u8, u8>::new("map", fork).put(&25, 0)
+        });
+        assert!(reads.is_affected_by(&insert));
+        let other_key = patch_with(db, |fork| {
+            MapIndex::<_, u8, u8>::new("map", fork).put(&30, 0)
+        });
+        assert!(!reads.is_affected_by(&other_key));
+        let other_index = patch_with(db, |fork| ListIndex::new("list", fork).push(2_u64));
+        assert!(!reads.is_affected_by(&other_index));
+        let clear = patch_with(db, |fork| MapIndex::<_, u8, u8>::new("map", fork).clear());
+        assert!(reads.is_affected_by(&clear));
+    }
+
+    fn _read_ranges<T: Database>(db: &T) {
+        fill_map(db);
+        let fork = db.fork();
+        let reads = read_set(&fork, |fork| {
+            let map = MapIndex::<_, u8, u8>::new("map", fork);
+            let keys: Vec<_> = map.keys_from(&35).take(2).collect();
+            assert_eq!(keys, vec![40, 50]);
+        });
+
+        let inside = patch_with(db, |fork| {
+            MapIndex::<_, u8, u8>::new("map", fork).put(&45, 0)
+        });
+        assert!(reads.is_affected_by(&inside));
+        let before = patch_with(db, |fork| {
+            MapIndex::<_, u8, u8>::new("map", fork).put(&30, 0)
+        });
+        assert!(!reads.is_affected_by(&before));
+        let after = patch_with(db, |fork| {
+            MapIndex::<_, u8, u8>::new("map", fork).put(&90, 0)
+        });
+        assert!(!reads.is_affected_by(&after));
+
+        let reads = read_set(&fork, |fork| {
+            let map = MapIndex::<_, u8, u8>::new("map", fork);
+            assert_eq!(map.keys_from(&35).count(), 6);
+        });
+        assert!(reads.is_affected_by(&after));
+        assert!(!reads.is_affected_by(&before));
+    }
+
+    fn _changes_in_fork_are_not_tracked<T: Database>(db: &T) {
+        fill_map(db);
+        let fork = db.fork();
+        let reads = read_set(&fork, |fork| {
+            let mut map = MapIndex::<_, u8, u8>::new("map", fork);
+            map.put(&20, 0);
+            assert_eq!(map.get(&20), Some(0));
+        });
+
+        let put = patch_with(db, |fork| {
+            MapIndex::<_, u8, u8>::new("map", fork).put(&20, 1)
+        });
+        assert!(!reads.is_affected_by(&put));
+        assert!(read_set(&fork, |_| ()).is_empty());
+    }
+
+    #[test]
+    fn read_keys() {
+        _read_keys(&TemporaryDB::new());
+    }
+
+    #[test]
+    fn read_keys_in_memory() {
+        _read_keys(&MemoryDB::new());
+    }
+
+    #[test]
+    fn read_ranges() {
+        _read_ranges(&TemporaryDB::new());
+    }
+
+    #[test]
+    fn read_ranges_in_memory() {
+        _read_ranges(&MemoryDB::new());
+    }
+
+    #[test]
+    fn changes_in_fork_are_not_tracked() {
+        _changes_in_fork_are_not_tracked(&TemporaryDB::new());
+    }
+
+    #[test]
+    fn changes_in_fork_are_not_tracked_in_memory() {
+        _changes_in_fork_are_not_tracked(&MemoryDB::new());
+    }
+
+    #[test]
+    fn prefix_intersection() {
+        let range = |from: &[u8], to: &[u8]| (Included(from.to_vec()), Excluded(to.to_vec()));
+        assert!(prefix_intersects(&[1], &range(&[0], &[1, 0])));
+        assert!(prefix_intersects(&[1], &range(&[1, 5], &[3])));
+        assert!(!prefix_intersects(&[1], &range(&[0], &[1])));
+        assert!(!prefix_intersects(&[1], &range(&[2], &[3])));
+        assert!(prefix_intersects(&[1], &(Unbounded, Unbounded)));
+        assert!(prefix_intersects(
+            &[255],
+            &(Included(vec![255, 1]), Unbounded)
+        ));
+    }
+}
@@ -20,6 +20,7 @@
 use std::{
     io::{Read, Write},
     marker::PhantomData,
+    ops::RangeBounds,
 };
 
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
@@ -86,10 +87,14 @@ pub struct SparseListIndex<T: IndexAccess, V> {
 
 /// Returns an iterator over the items of a `SparseListIndex`.
 ///
-/// This struct is created by the [`iter`] method on [`SparseListIndex`].
-/// See its documentation for details.
+/// This struct is created by the [`iter`], [`iter_from`], [`iter_rev`], [`range`]
+/// or [`range_rev`] method on [`SparseListIndex`]. See its documentation for details.
 ///
 /// [`iter`]: struct.SparseListIndex.html#method.iter
+/// [`iter_from`]: struct.SparseListIndex.html#method.iter_from
+/// [`iter_rev`]: struct.SparseListIndex.html#method.iter_rev
+/// [`range`]: struct.SparseListIndex.html#method.range
+/// [`range_rev`]: struct.SparseListIndex.html#method.range_rev
 /// [`SparseListIndex`]: struct.SparseListIndex.html
 #[derive(Debug)]
 pub struct SparseListIndexIter<'a, V> {
@@ -434,6 +439,78 @@ where
         }
     }
 
+    /// Returns an iterator over the list in reverse order, i.e., starting from the element
+    /// with the greatest index. The iterator element type is (u64, V).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, SparseListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = SparseListIndex::new("name", &fork);
+    ///
+    /// index.extend([1, 2, 3, 4, 5].iter().cloned());
+    /// index.remove(3);
+    ///
+    /// let latest = index.iter_rev().take(2).collect::<Vec<(u64, i32)>>();
+    /// assert_eq!(latest, vec![(4, 5), (2, 3)]);
+    /// ```
+    pub fn iter_rev(&self) -> SparseListIndexIter<V> {
+        SparseListIndexIter {
+            base_iter: self.base.iter_rev(&()),
+        }
+    }
+
+    /// Returns an iterator over the elements of the list with indices within the specified
+    /// range. The iterator element type is (u64, V).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, SparseListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = SparseListIndex::new("name", &fork);
+    ///
+    /// index.extend([1, 2, 3, 4, 5].iter().cloned());
+    /// index.remove(2);
+    ///
+    /// let items = index.range(1..4).collect::<Vec<(u64, i32)>>();
+    /// assert_eq!(items, vec![(1, 2), (3, 4)]);
+    /// ```
+    pub fn range<R: RangeBounds<u64>>(&self, range: R) -> SparseListIndexIter<V> {
+        SparseListIndexIter {
+            base_iter: self.base.range(&(), range),
+        }
+    }
+
+    /// Returns an iterator over the elements of the list with indices within the specified
+    /// range in reverse order. The iterator element type is (u64, V).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, SparseListIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// let mut index = SparseListIndex::new("name", &fork);
+    ///
+    /// index.extend([1, 2, 3, 4, 5].iter().cloned());
+    /// index.remove(2);
+    ///
+    /// let items = index.range_rev(1..4).collect::<Vec<(u64, i32)>>();
+    /// assert_eq!(items, vec![(3, 4), (1, 2)]);
+    /// ```
+    pub fn range_rev<R: RangeBounds<u64>>(&self, range: R) -> SparseListIndexIter<V> {
+        SparseListIndexIter {
+            base_iter: self.base.range_rev(&(), range),
+        }
+    }
+
     /// Appends an element to the back of the 'SparseListIndex'.
     ///
     /// # Examples
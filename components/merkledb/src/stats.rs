@@ -0,0 +1,70 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Statistics of the database storage.
+
+use serde_derive::{Deserialize, Serialize};
+
+/// Storage statistics of a single column family.
+///
+/// Most of the values are estimates maintained by the storage engine,
+/// so they are not guaranteed to be exact.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnFamilyStats {
+    /// Name of the column family.
+    pub name: String,
+    /// Total size of the SST files of the column family in bytes.
+    pub sst_files_size: u64,
+    /// Approximate size of the active and unflushed memtables in bytes.
+    pub memtables_size: u64,
+    /// Estimated number of bytes that have to be rewritten by the compaction
+    /// to bring all levels of the column family down under their target sizes.
+    pub pending_compaction_bytes: u64,
+    /// Estimated number of keys in the column family.
+    pub estimated_keys: u64,
+}
+
+/// Storage statistics of a database.
+///
+/// See [`Database::storage_stats`] for details.
+///
+/// [`Database::storage_stats`]: trait.Database.html#method.storage_stats
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbStats {
+    /// Statistics of the column families, sorted by the column family name.
+    pub column_families: Vec<ColumnFamilyStats>,
+}
+
+impl DbStats {
+    /// Returns the statistics of the column family with the specified name.
+    pub fn column_family(&self, name: &str) -> Option<&ColumnFamilyStats> {
+        self.column_families.iter().find(|stats| stats.name == name)
+    }
+
+    /// Returns the total size of the SST files of all column families in bytes.
+    pub fn total_sst_files_size(&self) -> u64 {
+        self.column_families
+            .iter()
+            .map(|stats| stats.sst_files_size)
+            .sum()
+    }
+
+    /// Returns the total size of the memtables of all column families in bytes.
+    pub fn total_memtables_size(&self) -> u64 {
+        self.column_families
+            .iter()
+            .map(|stats| stats.memtables_size)
+            .sum()
+    }
+}
@@ -43,11 +43,12 @@ pub struct ValueSetIndex<T: IndexAccess, V> {
 
 /// Returns an iterator over the items of a `ValueSetIndex`.
 ///
-/// This struct is created by the [`iter`] or
-/// [`iter_from`] method on [`ValueSetIndex`]. See its documentation for details.
+/// This struct is created by the [`iter`], [`iter_from`] or [`iter_rev`] method
+/// on [`ValueSetIndex`]. See its documentation for details.
 ///
 /// [`iter`]: struct.ValueSetIndex.html#method.iter
 /// [`iter_from`]: struct.ValueSetIndex.html#method.iter_from
+/// [`iter_rev`]: struct.ValueSetIndex.html#method.iter_rev
 /// [`ValueSetIndex`]: struct.ValueSetIndex.html
 #[derive(Debug)]
 pub struct ValueSetIndexIter<'a, V> {
@@ -274,6 +275,29 @@ where
         }
     }
 
+    /// Returns an iterator visiting all elements in descending order of their hashes.
+    /// The iterator element type is (Hash, V).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{TemporaryDB, Database, ValueSetIndex};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let name = "name";
+    /// let snapshot = db.snapshot();
+    /// let index: ValueSetIndex<_, u8> = ValueSetIndex::new(name, &snapshot);
+    ///
+    /// for val in index.iter_rev() {
+    ///     println!("{:?}", val);
+    /// }
+    /// ```
+    pub fn iter_rev(&self) -> ValueSetIndexIter<V> {
+        ValueSetIndexIter {
+            base_iter: self.base.iter_rev(&()),
+        }
+    }
+
     /// Returns an iterator visiting hashes of all elements in ascending order. The iterator element type
     /// is [Hash](../../exonum_crypto/struct.Hash.html).
     ///
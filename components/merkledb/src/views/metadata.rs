@@ -20,9 +20,9 @@ use failure::{self, ensure, format_err};
 use num_traits::FromPrimitive;
 use serde_derive::{Deserialize, Serialize};
 
-use crate::BinaryValue;
+use crate::{BinaryValue, Error, Result as StorageResult};
 
-use super::{IndexAccess, IndexAddress, View};
+use super::{IndexAccess, IndexAddress, View, INDEX_NAME_SEPARATOR};
 
 /// Name of the column family used to store `IndexesPool`.
 const INDEXES_POOL_NAME: &str = "__INDEXES_POOL__";
@@ -32,14 +32,29 @@ const INDEXES_POOL_NAME: &str = "__INDEXES_POOL__";
 #[derive(Debug, Copy, Clone, PartialEq, Primitive, Serialize, Deserialize)]
 #[repr(u32)]
 pub enum IndexType {
+    /// `MapIndex`.
     Map = 1,
+    /// `ListIndex`.
     List = 2,
+    /// `Entry`.
     Entry = 3,
+    /// `ValueSetIndex`.
     ValueSet = 4,
+    /// `KeySetIndex`.
     KeySet = 5,
+    /// `SparseListIndex`.
     SparseList = 6,
+    /// `ProofListIndex`.
     ProofList = 7,
+    /// `ProofMapIndex`.
     ProofMap = 8,
+    /// `ProofKeySetIndex`.
+    ProofKeySet = 9,
+    /// `MountainRangeIndex`.
+    MountainRange = 10,
+    /// `ProofMapIndex` with raw keys.
+    RawProofMap = 11,
+    /// Index of an unknown type.
     Unknown = 255,
 }
 
@@ -155,6 +170,114 @@ impl<V> IndexMetadata<V> {
     }
 }
 
+/// Index state kept as raw bytes, used to read metadata of indexes of any type.
+#[derive(Debug, Clone, Default)]
+struct RawState(Vec<u8>);
+
+impl BinaryAttribute for RawState {
+    fn size(&self) -> usize {
+        self.0.len()
+    }
+
+    fn write<W: std::io::Write>(&self, buffer: &mut W) {
+        buffer.write_all(&self.0).unwrap()
+    }
+
+    fn read<R: std::io::Read>(buffer: &mut R) -> Self {
+        let mut bytes = Vec::new();
+        buffer.read_to_end(&mut bytes).unwrap();
+        RawState(bytes)
+    }
+}
+
+/// Information about an index stored in the database.
+///
+/// See [`ObjectAccess::indexes`] for details.
+///
+/// [`ObjectAccess::indexes`]: trait.ObjectAccess.html#method.indexes
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexInfo {
+    /// Name of the index.
+    pub name: String,
+    /// Identifier of the index within its family, or `None` if the index
+    /// does not belong to a family.
+    pub family_id: Option<Vec<u8>>,
+    /// Unique numeric identifier assigned to the index on its creation.
+    pub identifier: u64,
+    /// Type of the index.
+    pub index_type: IndexType,
+    /// Serialized state of the index, e.g., the length of a list. Empty for indexes
+    /// without a state.
+    pub state: Vec<u8>,
+    /// Number of entries stored in the database for the index, or `None` if the entries
+    /// have not been counted. Besides the items of the index, the number includes
+    /// auxiliary entries (e.g., nodes of Merkle trees), so it should be treated
+    /// as an approximation of the index size.
+    pub entries_count: Option<u64>,
+}
+
+/// Returns information about all indexes accessible via `index_access`, ordered
+/// by their fully qualified names. Entries of the indexes are counted only
+/// if `count_entries` is set, since this requires iterating over the whole database.
+pub fn indexes_info<T: IndexAccess>(
+    index_access: T,
+    count_entries: bool,
+) -> StorageResult<Vec<IndexInfo>> {
+    let pool_entries = {
+        let pool = View::new(index_access.clone(), INDEXES_POOL_NAME);
+        let mut iter = pool.iter_bytes(&[]);
+        let mut entries = Vec::new();
+        while let Some((key, value)) = iter.next() {
+            // The empty key is used to store the size of the pool.
+            if !key.is_empty() {
+                entries.push((key.to_vec(), value.to_vec()));
+            }
+        }
+        entries
+    };
+
+    pool_entries
+        .into_iter()
+        .map(|(index_name, metadata)| {
+            let metadata = IndexMetadata::<RawState>::from_bytes(metadata.into()).map_err(|e| {
+                Error::new(format!(
+                    "Malformed metadata of index {:?}: {}",
+                    String::from_utf8_lossy(&index_name),
+                    e
+                ))
+            })?;
+            let (name, family_id) = match index_name
+                .iter()
+                .position(|&byte| byte == INDEX_NAME_SEPARATOR[0])
+            {
+                Some(pos) => (&index_name[..pos], Some(index_name[pos + 1..].to_vec())),
+                None => (&index_name[..], None),
+            };
+
+            let entries_count = if count_entries {
+                let view = View::new(index_access.clone(), metadata.index_address());
+                let mut iter = view.iter_bytes(&[]);
+                let mut entries_count = 0;
+                while iter.next().is_some() {
+                    entries_count += 1;
+                }
+                Some(entries_count)
+            } else {
+                None
+            };
+
+            Ok(IndexInfo {
+                name: String::from_utf8_lossy(name).into_owned(),
+                family_id,
+                identifier: metadata.identifier,
+                index_type: metadata.index_type,
+                state: metadata.state.0,
+                entries_count,
+            })
+        })
+        .collect()
+}
+
 /// Returns index metadata based on provided `index_address` and `index_type`.
 ///
 /// Creates new metadata if it does not exist.
@@ -188,6 +311,46 @@ where
     (index_address, index_state)
 }
 
+/// Removes the data and metadata of the index with the provided `index_address`.
+/// Returns `false` if the index does not exist.
+pub fn drop_index<T: IndexAccess>(index_access: T, index_address: &IndexAddress) -> bool {
+    let index_name = index_address.fully_qualified_name();
+    let mut pool = IndexesPool::new(index_access.clone());
+    let metadata = match pool.index_metadata::<RawState>(&index_name) {
+        Some(metadata) => metadata,
+        None => return false,
+    };
+
+    View::new(index_access, metadata.index_address()).clear();
+    pool.remove_index_metadata(&index_name);
+    true
+}
+
+/// Moves the index from the address `from` to the address `to`. The data of the index
+/// remains in place, since it is addressed by the index identifier rather than by its name.
+pub fn rename_index<T: IndexAccess>(
+    index_access: T,
+    from: &IndexAddress,
+    to: &IndexAddress,
+) -> StorageResult<()> {
+    if to.name().is_empty() {
+        return Err(Error::new("Index name must not be empty"));
+    }
+
+    let (from_name, to_name) = (from.fully_qualified_name(), to.fully_qualified_name());
+    let mut pool = IndexesPool::new(index_access);
+    if pool.index_metadata::<RawState>(&to_name).is_some() {
+        return Err(Error::new(format!("Index {:?} already exists", to)));
+    }
+
+    let metadata = pool
+        .index_metadata::<RawState>(&from_name)
+        .ok_or_else(|| Error::new(format!("Index {:?} does not exist", from)))?;
+    pool.remove_index_metadata(&from_name);
+    pool.0.put(to_name.as_slice(), metadata.to_bytes());
+    Ok(())
+}
+
 /// Persistent pool used to store indexes metadata in the database.
 /// Pool size is used as an identifier of newly created indexes.
 struct IndexesPool<T: IndexAccess>(View<T>);
@@ -207,11 +370,15 @@ impl<T: IndexAccess> IndexesPool<T> {
 
     fn index_metadata<V>(&self, index_name: &[u8]) -> Option<IndexMetadata<V>>
     where
-        V: BinaryAttribute + Default + Copy,
+        V: BinaryAttribute,
     {
         self.0.get(index_name)
     }
 
+    fn remove_index_metadata(&mut self, index_name: &[u8]) {
+        self.0.remove(index_name)
+    }
+
     fn create_index_metadata<V>(
         &mut self,
         index_name: &[u8],
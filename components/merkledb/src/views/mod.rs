@@ -15,14 +15,23 @@
 #![warn(missing_docs)]
 
 pub use self::{
-    metadata::{BinaryAttribute, IndexState, IndexType},
+    metadata::{BinaryAttribute, IndexInfo, IndexState, IndexType},
     refs::{AnyObject, ObjectAccess, Ref, RefMut},
 };
 
-use std::{borrow::Cow, fmt, iter::Peekable, marker::PhantomData, ops::Deref};
+use std::{
+    borrow::Cow,
+    fmt,
+    iter::Peekable,
+    marker::PhantomData,
+    ops::{
+        Bound::{self, Excluded, Included, Unbounded},
+        Deref, RangeBounds,
+    },
+};
 
 use super::{
-    db::{Change, ChangesRef, ForkIter, ViewChanges},
+    db::{next_prefix, Change, ChangesRef, ForkIter, ViewChanges},
     BinaryKey, BinaryValue, Iter as BytesIter, Iterator as BytesIterator, Snapshot,
 };
 
@@ -357,6 +366,30 @@ fn key_bytes<K: BinaryKey + ?Sized>(key: &K) -> Vec<u8> {
     concat_keys!(key)
 }
 
+/// Range of keys serialized as byte arrays.
+type KeyRange = (Bound<Vec<u8>>, Bound<Vec<u8>>);
+
+fn key_range<K, R>(range: &R) -> KeyRange
+where
+    K: BinaryKey + ?Sized,
+    R: RangeBounds<K>,
+{
+    let key_bound = |bound: Bound<&K>| match bound {
+        Included(key) => Included(key_bytes(key)),
+        Excluded(key) => Excluded(key_bytes(key)),
+        Unbounded => Unbounded,
+    };
+    (key_bound(range.start_bound()), key_bound(range.end_bound()))
+}
+
+fn slice_bound(bound: &Bound<Cow<[u8]>>) -> Bound<&[u8]> {
+    match bound {
+        Included(key) => Included(key.as_ref()),
+        Excluded(key) => Excluded(key.as_ref()),
+        Unbounded => Unbounded,
+    }
+}
+
 impl<T: IndexAccess> View<T> {
     ///TODO: add documentation
     pub fn new<I: Into<IndexAddress>>(index_access: T, address: I) -> Self {
@@ -410,8 +443,6 @@ impl<T: IndexAccess> View<T> {
     }
 
     fn iter_bytes(&self, from: &[u8]) -> BytesIter {
-        use std::collections::Bound::*;
-
         let (name, key) = self.address.keyed(from);
         let prefix = self.address.bytes.clone().unwrap_or_else(|| vec![]);
 
@@ -433,6 +464,75 @@ impl<T: IndexAccess> View<T> {
         }
     }
 
+    fn iter_bytes_rev(&self, to: Bound<&[u8]>) -> BytesIter {
+        let prefix = self.address.bytes.clone().unwrap_or_else(|| vec![]);
+
+        let changes_iter = self
+            .changes
+            .as_ref()
+            .map(|changes| changes.data.range::<[u8], _>((Unbounded, to)).rev());
+
+        let is_empty = self.changes.as_ref().map_or(false, ViewChanges::is_empty);
+
+        if is_empty {
+            // Ignore all changes from the snapshot
+            Box::new(ChangesIter::new(changes_iter.unwrap()))
+        } else {
+            // If the iteration starts from the end of the view, entries of the snapshot
+            // are bounded by the prefix following the view prefix.
+            let snapshot_to = match to {
+                Included(key) => Included(self.address.keyed(key).1),
+                Excluded(key) => Excluded(self.address.keyed(key).1),
+                Unbounded => next_prefix(&prefix).map_or(Unbounded, |key| Excluded(key.into())),
+            };
+            let snapshot_iter = SnapshotIter::new_rev(
+                self.snapshot(),
+                self.address.name(),
+                prefix,
+                slice_bound(&snapshot_to),
+            );
+            Box::new(ForkIter::new_rev(Box::new(snapshot_iter), changes_iter))
+        }
+    }
+
+    fn range_iter<K, V>(&self, iter_prefix: Vec<u8>, range: KeyRange, reversed: bool) -> Iter<K, V>
+    where
+        K: BinaryKey,
+        V: BinaryValue,
+    {
+        let base_iter = if reversed {
+            let prefix_end = next_prefix(&iter_prefix);
+            let to = match (&range.1, &prefix_end) {
+                (Included(end), Some(prefix_end)) | (Excluded(end), Some(prefix_end))
+                    if end >= prefix_end =>
+                {
+                    Excluded(prefix_end.as_slice())
+                }
+                (Included(end), _) => Included(end.as_slice()),
+                (Excluded(end), _) => Excluded(end.as_slice()),
+                (Unbounded, Some(prefix_end)) => Excluded(prefix_end.as_slice()),
+                (Unbounded, None) => Unbounded,
+            };
+            self.iter_bytes_rev(to)
+        } else {
+            let from = match range.0 {
+                Included(ref start) | Excluded(ref start) if *start > iter_prefix => start,
+                _ => &iter_prefix,
+            };
+            self.iter_bytes(from)
+        };
+
+        Iter {
+            base_iter,
+            prefix: iter_prefix,
+            range,
+            reversed,
+            ended: false,
+            _k: PhantomData,
+            _v: PhantomData,
+        }
+    }
+
     /// Returns a value of *any* type corresponding to the key of *any* type.
     pub fn get<K, V>(&self, key: &K) -> Option<V>
     where
@@ -466,6 +566,8 @@ impl<T: IndexAccess> View<T> {
         Iter {
             base_iter: self.iter_bytes(&iter_prefix),
             prefix: iter_prefix,
+            range: (Unbounded, Unbounded),
+            reversed: false,
             ended: false,
             _k: PhantomData,
             _v: PhantomData,
@@ -487,12 +589,54 @@ impl<T: IndexAccess> View<T> {
         Iter {
             base_iter: self.iter_bytes(&iter_from),
             prefix: iter_prefix,
+            range: (Unbounded, Unbounded),
+            reversed: false,
             ended: false,
             _k: PhantomData,
             _v: PhantomData,
         }
     }
 
+    /// Returns an iterator over the entries of the index in descending order. The iterator
+    /// element type is *any* key-value pair. An argument `subprefix` allows specifying a subset
+    /// of keys for iteration.
+    pub fn iter_rev<P, K, V>(&self, subprefix: &P) -> Iter<K, V>
+    where
+        P: BinaryKey + ?Sized,
+        K: BinaryKey,
+        V: BinaryValue,
+    {
+        self.range_iter(key_bytes(subprefix), (Unbounded, Unbounded), true)
+    }
+
+    /// Returns an iterator over the entries of the index with keys within the specified `range`
+    /// in ascending order. The iterator element type is *any* key-value pair. An argument
+    /// `subprefix` allows specifying a subset of keys for iteration; bounds of the `range`
+    /// are full keys including the subprefix.
+    pub fn range<P, F, R, K, V>(&self, subprefix: &P, range: R) -> Iter<K, V>
+    where
+        P: BinaryKey + ?Sized,
+        F: BinaryKey + ?Sized,
+        R: RangeBounds<F>,
+        K: BinaryKey,
+        V: BinaryValue,
+    {
+        self.range_iter(key_bytes(subprefix), key_range(&range), false)
+    }
+
+    /// Returns an iterator over the entries of the index with keys within the specified `range`
+    /// in descending order. See [`range`](#method.range) for details.
+    pub fn range_rev<P, F, R, K, V>(&self, subprefix: &P, range: R) -> Iter<K, V>
+    where
+        P: BinaryKey + ?Sized,
+        F: BinaryKey + ?Sized,
+        R: RangeBounds<F>,
+        K: BinaryKey,
+        V: BinaryValue,
+    {
+        self.range_iter(key_bytes(subprefix), key_range(&range), true)
+    }
+
     /// Inserts a key-value pair into the fork.
     pub fn put<K, V>(&mut self, key: &K, value: V)
     where
@@ -550,6 +694,14 @@ impl<'a> SnapshotIter<'a> {
             ended: false,
         }
     }
+
+    fn new_rev(snapshot: &'a dyn Snapshot, name: &str, prefix: Vec<u8>, to: Bound<&[u8]>) -> Self {
+        SnapshotIter {
+            inner: snapshot.iter_rev(name, to),
+            prefix,
+            ended: false,
+        }
+    }
 }
 
 impl<'a> BytesIterator for SnapshotIter<'a> {
@@ -637,15 +789,20 @@ where
 
 /// An iterator over the entries of a `View`.
 ///
-/// This struct is created by the [`iter`] or
-/// [`iter_from`] method on [`View`]. See its documentation for details.
+/// This struct is created by the [`iter`], [`iter_from`], [`iter_rev`], [`range`]
+/// or [`range_rev`] method on [`View`]. See its documentation for details.
 ///
-/// [`iter`]: struct.BaseIndex.html#method.iter
-/// [`iter_from`]: struct.BaseIndex.html#method.iter_from
-/// [`BaseIndex`]: struct.BaseIndex.html
+/// [`iter`]: struct.View.html#method.iter
+/// [`iter_from`]: struct.View.html#method.iter_from
+/// [`iter_rev`]: struct.View.html#method.iter_rev
+/// [`range`]: struct.View.html#method.range
+/// [`range_rev`]: struct.View.html#method.range_rev
+/// [`View`]: struct.View.html
 pub struct Iter<'a, K, V> {
     base_iter: BytesIter<'a>,
     prefix: Vec<u8>,
+    range: KeyRange,
+    reversed: bool,
     ended: bool,
     _k: PhantomData<K>,
     _v: PhantomData<V>,
@@ -669,14 +826,40 @@ where
             return None;
         }
 
-        if let Some((k, v)) = self.base_iter.next() {
-            if k.starts_with(&self.prefix) {
-                return Some((
-                    K::read(k),
-                    V::from_bytes(Cow::Borrowed(v))
-                        .expect("Unable to decode value from bytes, an error occurred"),
-                ));
+        while let Some((k, v)) = self.base_iter.next() {
+            if !k.starts_with(&self.prefix) {
+                break;
             }
+
+            let before_start = match self.range.0 {
+                Included(ref start) => k < start.as_slice(),
+                Excluded(ref start) => k <= start.as_slice(),
+                Unbounded => false,
+            };
+            let after_end = match self.range.1 {
+                Included(ref end) => k > end.as_slice(),
+                Excluded(ref end) => k >= end.as_slice(),
+                Unbounded => false,
+            };
+            // Keys preceding the range in the direction of iteration are skipped, and
+            // the iteration stops at the first key following the range.
+            let (preceding, following) = if self.reversed {
+                (after_end, before_start)
+            } else {
+                (before_start, after_end)
+            };
+            if following {
+                break;
+            }
+            if preceding {
+                continue;
+            }
+
+            return Some((
+                K::read(k),
+                V::from_bytes(Cow::Borrowed(v))
+                    .expect("Unable to decode value from bytes, an error occurred"),
+            ));
         }
 
         self.ended = true;
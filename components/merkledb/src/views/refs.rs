@@ -15,9 +15,11 @@
 use std::ops::{Deref, DerefMut};
 
 use crate::{
-    views::{IndexAddress, IndexType, View},
-    BinaryKey, BinaryValue, Entry, Fork, IndexAccess, KeySetIndex, ListIndex, MapIndex, ObjectHash,
-    ProofListIndex, ProofMapIndex, Snapshot, SparseListIndex, ValueSetIndex,
+    proof_map_index::ToProofPath,
+    views::{metadata, IndexAddress, IndexInfo, IndexType, View},
+    BinaryKey, BinaryValue, Entry, Fork, IndexAccess, KeySetIndex, ListIndex, MapIndex,
+    MountainRangeIndex, ObjectHash, ProofKeySetIndex, ProofListIndex, ProofMapIndex, Result,
+    Snapshot, SparseListIndex, ValueSetIndex,
 };
 
 pub trait AnyObject<T: IndexAccess> {
@@ -90,11 +92,40 @@ where
     }
 }
 
-impl<T, K, V> FromView<T> for ProofMapIndex<T, K, V>
+impl<T, V> FromView<T> for MountainRangeIndex<T, V>
 where
     T: IndexAccess,
-    K: BinaryKey + ObjectHash,
+    V: BinaryValue,
+{
+    fn create<I: Into<IndexAddress>>(address: I, access: T) -> Self {
+        Self::create_from(address, access)
+    }
+
+    fn get<I: Into<IndexAddress>>(address: I, access: T) -> Option<Self> {
+        Self::get_from(address, access)
+    }
+}
+
+impl<T, K, V, KeyMode> FromView<T> for ProofMapIndex<T, K, V, KeyMode>
+where
+    T: IndexAccess,
+    K: BinaryKey,
     V: BinaryValue + ObjectHash,
+    KeyMode: ToProofPath<K>,
+{
+    fn create<I: Into<IndexAddress>>(address: I, access: T) -> Self {
+        Self::create_from(address, access)
+    }
+
+    fn get<I: Into<IndexAddress>>(address: I, access: T) -> Option<Self> {
+        Self::get_from(address, access)
+    }
+}
+
+impl<T, K> FromView<T> for ProofKeySetIndex<T, K>
+where
+    T: IndexAccess,
+    K: BinaryKey + ObjectHash,
 {
     fn create<I: Into<IndexAddress>>(address: I, access: T) -> Self {
         Self::create_from(address, access)
@@ -216,6 +247,32 @@ pub trait ObjectAccess: IndexAccess {
             },
         }
     }
+
+    /// Returns information about all indexes in the database, including their names,
+    /// types and states. Indexes are ordered by their fully qualified names.
+    ///
+    /// If `count_entries` is set, the number of entries of each index is calculated.
+    /// This requires iterating over the whole database and may take a long time.
+    ///
+    /// ```
+    /// use exonum_merkledb::{Database, TemporaryDB, IndexType, ListIndex, ObjectAccess};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// ListIndex::new("list", &fork).extend(vec![1_u8, 2, 3]);
+    /// ListIndex::new_in_family("family", &7_u8, &fork).push(4_u8);
+    ///
+    /// let indexes = (&fork).indexes(true).unwrap();
+    /// assert_eq!(indexes.len(), 2);
+    /// assert_eq!(indexes[0].name, "family");
+    /// assert_eq!(indexes[0].family_id, Some(vec![7]));
+    /// assert_eq!(indexes[1].name, "list");
+    /// assert_eq!(indexes[1].index_type, IndexType::List);
+    /// assert_eq!(indexes[1].entries_count, Some(3));
+    /// ```
+    fn indexes(&self, count_entries: bool) -> Result<Vec<IndexInfo>> {
+        metadata::indexes_info(self.clone(), count_entries)
+    }
 }
 
 impl ObjectAccess for &Box<dyn Snapshot> {}
@@ -265,6 +322,77 @@ impl Fork {
     {
         T::get(address, self).map(|value| RefMut { value })
     }
+
+    /// Drops the index with the specified address, removing both its data and metadata.
+    /// Returns `false` if the index does not exist.
+    ///
+    /// Unlike clearing an index, dropping does not require to remove keys of the index
+    /// one by one: the whole key range of the index is removed when the changes
+    /// are merged into the database. After the index is dropped, an index with the same
+    /// address can be created anew, possibly with a different type.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the index is borrowed in the fork (e.g., there is a live `ListIndex`
+    ///   instance for the dropped index).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{Database, ListIndex, ObjectAccess, TemporaryDB};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// ListIndex::new("list", &fork).extend(vec![1_u32, 2, 3]);
+    /// db.merge(fork.into_patch()).unwrap();
+    ///
+    /// let fork = db.fork();
+    /// assert!(fork.drop_index("list"));
+    /// assert!(!fork.drop_index("list"));
+    /// db.merge(fork.into_patch()).unwrap();
+    /// let snapshot = db.snapshot();
+    /// assert!((&snapshot).indexes(false).unwrap().is_empty());
+    /// ```
+    pub fn drop_index<I: Into<IndexAddress>>(&self, address: I) -> bool {
+        metadata::drop_index(self, &address.into())
+    }
+
+    /// Atomically moves the index from the address `from` to the address `to`.
+    /// The data of the index is not copied, so renaming is cheap regardless of
+    /// the index size.
+    ///
+    /// Instances of the index created before the renaming should not be used
+    /// afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the index at `from` does not exist, an index at `to`
+    /// already exists, or `to` has an empty name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exonum_merkledb::{Database, ListIndex, TemporaryDB};
+    ///
+    /// let db = TemporaryDB::new();
+    /// let fork = db.fork();
+    /// ListIndex::new("list", &fork).push(1_u32);
+    /// fork.rename_index("list", ("archive", &1_u8)).unwrap();
+    /// db.merge(fork.into_patch()).unwrap();
+    ///
+    /// let snapshot = db.snapshot();
+    /// let list: ListIndex<_, u32> = ListIndex::new_in_family("archive", &1_u8, &snapshot);
+    /// assert_eq!(list.get(0), Some(1));
+    /// let old_list: ListIndex<_, u32> = ListIndex::new("list", &snapshot);
+    /// assert!(old_list.is_empty());
+    /// ```
+    pub fn rename_index<I, J>(&self, from: I, to: J) -> Result<()>
+    where
+        I: Into<IndexAddress>,
+        J: Into<IndexAddress>,
+    {
+        metadata::rename_index(self, &from.into(), &to.into())
+    }
 }
 
 #[derive(Debug)]
@@ -307,7 +435,7 @@ mod tests {
     use crate::{
         db::Database,
         views::refs::{ObjectAccess, Ref, RefMut},
-        ListIndex, TemporaryDB,
+        ListIndex, MapIndex, TemporaryDB,
     };
 
     #[test]
@@ -369,4 +497,71 @@ mod tests {
 
         assert_eq!(list.get(0), Some(1));
     }
+
+    #[test]
+    fn drop_index() {
+        let db = TemporaryDB::new();
+        let fork = db.fork();
+        ListIndex::new("list", &fork).extend(vec![1_u32, 2, 3]);
+        MapIndex::new_in_family("map", &1_u8, &fork).put(&1_u8, 2_u8);
+        db.merge(fork.into_patch()).unwrap();
+
+        let fork = db.fork();
+        assert!(fork.drop_index("list"));
+        assert!(!fork.drop_index("list"));
+        assert!(!fork.drop_index(("map", &2_u8)));
+        {
+            let list: Option<RefMut<ListIndex<_, u32>>> = fork.get_object_existed_mut("list");
+            assert!(list.is_none());
+        }
+        db.merge(fork.into_patch()).unwrap();
+
+        let snapshot = &db.snapshot();
+        let indexes = snapshot.indexes(false).unwrap();
+        assert_eq!(indexes.len(), 1);
+        assert_eq!(indexes[0].name, "map");
+        let list: Option<Ref<ListIndex<_, u32>>> = snapshot.get_object_existed("list");
+        assert!(list.is_none());
+        let map: MapIndex<_, u8, u8> = MapIndex::new_in_family("map", &1_u8, snapshot);
+        assert_eq!(map.get(&1), Some(2));
+
+        // The dropped index can be recreated with another type.
+        let fork = db.fork();
+        {
+            let map: MapIndex<_, u32, u32> = MapIndex::new("list", &fork);
+            assert_eq!(map.iter().count(), 0);
+        }
+        let list: ListIndex<_, u32> = ListIndex::new("list", &db.snapshot());
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn rename_index() {
+        let db = TemporaryDB::new();
+        let fork = db.fork();
+        ListIndex::new("list", &fork).extend(vec![1_u32, 2, 3]);
+        ListIndex::new("other", &fork).push(4_u32);
+        db.merge(fork.into_patch()).unwrap();
+
+        let fork = db.fork();
+        fork.rename_index("list", ("archive", &1_u8)).unwrap();
+        assert!(fork.rename_index("list", "new_list").is_err());
+        assert!(fork.rename_index("other", ("archive", &1_u8)).is_err());
+        assert!(fork.rename_index("other", "").is_err());
+        db.merge(fork.into_patch()).unwrap();
+
+        let snapshot = &db.snapshot();
+        let indexes = snapshot.indexes(false).unwrap();
+        assert_eq!(indexes.len(), 2);
+        assert_eq!(indexes[0].name, "archive");
+        assert_eq!(indexes[0].family_id, Some(vec![1]));
+        assert_eq!(indexes[1].name, "other");
+
+        let list: ListIndex<_, u32> = ListIndex::new_in_family("archive", &1_u8, snapshot);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+        let old_list: Option<Ref<ListIndex<_, u32>>> = snapshot.get_object_existed("list");
+        assert!(old_list.is_none());
+        let other: ListIndex<_, u32> = ListIndex::new("other", snapshot);
+        assert_eq!(other.get(0), Some(4));
+    }
 }
@@ -12,10 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::ops::RangeBounds;
+
 use crate::{
     db,
     views::{IndexAccess, IndexAddress, IndexBuilder, IndexType, View},
-    Database, DbOptions, Fork, RocksDB, TemporaryDB,
+    Database, DbOptions, Fork, MemoryDB, RocksDB, TemporaryDB,
 };
 
 const IDX_NAME: &str = "idx_name";
@@ -31,6 +33,23 @@ fn assert_iter<T: IndexAccess>(view: &View<T>, from: u8, assumed: &[(u8, u8)]) {
     assert_eq!(values, assumed);
 }
 
+fn assert_range<T, R>(view: &View<T>, range: R, assumed: &[(u8, u8)])
+where
+    T: IndexAccess,
+    R: RangeBounds<u8> + Clone,
+{
+    let values = view
+        .range::<(), _, _, u8, u8>(&(), range.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(values, assumed);
+
+    let mut values = view
+        .range_rev::<(), _, _, u8, u8>(&(), range)
+        .collect::<Vec<_>>();
+    values.reverse();
+    assert_eq!(values, assumed);
+}
+
 fn assert_initial_state<T: IndexAccess>(view: &View<T>) {
     assert_eq!(view.get_bytes(&[1]), Some(vec![1]));
     assert_eq!(view.get_bytes(&[2]), Some(vec![2]));
@@ -342,6 +361,62 @@ where
     assert_iter(&view, 0, &[(10, 10), (20, 20), (30, 30)]);
 }
 
+fn _fork_iter_rev_and_range<T, I>(db: &T, address: I)
+where
+    T: Database,
+    I: Into<IndexAddress> + Copy,
+{
+    let fork = db.fork();
+    {
+        let mut view = View::new(&fork, address);
+        view.put(&10_u8, 10_u8);
+        view.put(&20_u8, 20_u8);
+        view.put(&30_u8, 30_u8);
+    }
+    db.merge(fork.into_patch()).unwrap();
+
+    // Stored
+    let mut fork = db.fork();
+    {
+        let mut view = View::new(&fork, address);
+        let values = view.iter_rev::<(), u8, u8>(&()).collect::<Vec<_>>();
+        assert_eq!(values, vec![(30, 30), (20, 20), (10, 10)]);
+        assert_range(&view, .., &[(10, 10), (20, 20), (30, 30)]);
+        assert_range(&view, 10..30, &[(10, 10), (20, 20)]);
+        assert_range(&view, 10..=30, &[(10, 10), (20, 20), (30, 30)]);
+        assert_range(&view, 11..=29, &[(20, 20)]);
+        assert_range(&view, ..20, &[(10, 10)]);
+        assert_range(&view, 25.., &[(30, 30)]);
+        assert_range(&view, 31.., &[]);
+        assert_range(&view, 21..25, &[]);
+
+        // Inserted and deleted
+        view.put(&5_u8, 5_u8);
+        view.put(&25_u8, 25_u8);
+        view.put(&35_u8, 35_u8);
+        view.remove(&20_u8);
+        assert_range(&view, .., &[(5, 5), (10, 10), (25, 25), (30, 30), (35, 35)]);
+        assert_range(&view, 10..35, &[(10, 10), (25, 25), (30, 30)]);
+        assert_range(&view, ..=5, &[(5, 5)]);
+        assert_range(&view, 15..25, &[]);
+    }
+    fork.flush();
+
+    // Flushed
+    let mut view = View::new(&fork, address);
+    view.put(&30_u8, 31_u8);
+    assert_range(&view, .., &[(5, 5), (10, 10), (25, 25), (30, 31), (35, 35)]);
+    assert_range(&view, 6..=30, &[(10, 10), (25, 25), (30, 31)]);
+
+    // Cleared
+    view.clear();
+    assert_range(&view, .., &[]);
+    view.put(&15_u8, 15_u8);
+    view.put(&40_u8, 40_u8);
+    assert_range(&view, .., &[(15, 15), (40, 40)]);
+    assert_range(&view, ..40, &[(15, 15)]);
+}
+
 #[test]
 fn test_database_check_correct_version() {
     let db = TemporaryDB::default();
@@ -381,6 +456,36 @@ fn fork_iter_prefixed() {
     _fork_iter(&TemporaryDB::new(), PREFIXED_IDX);
 }
 
+#[test]
+fn fork_iter_in_memory() {
+    _fork_iter(&MemoryDB::new(), IDX_NAME);
+}
+
+#[test]
+fn fork_iter_prefixed_in_memory() {
+    _fork_iter(&MemoryDB::new(), PREFIXED_IDX);
+}
+
+#[test]
+fn fork_iter_rev_and_range() {
+    _fork_iter_rev_and_range(&TemporaryDB::new(), IDX_NAME);
+}
+
+#[test]
+fn fork_iter_rev_and_range_prefixed() {
+    _fork_iter_rev_and_range(&TemporaryDB::new(), PREFIXED_IDX);
+}
+
+#[test]
+fn fork_iter_rev_and_range_in_memory() {
+    _fork_iter_rev_and_range(&MemoryDB::new(), IDX_NAME);
+}
+
+#[test]
+fn fork_iter_rev_and_range_prefixed_in_memory() {
+    _fork_iter_rev_and_range(&MemoryDB::new(), PREFIXED_IDX);
+}
+
 #[test]
 fn changelog() {
     _changelog(&TemporaryDB::new(), IDX_NAME);
@@ -391,6 +496,16 @@ fn changelog_prefixed() {
     _changelog(&TemporaryDB::new(), PREFIXED_IDX);
 }
 
+#[test]
+fn changelog_in_memory() {
+    _changelog(&MemoryDB::new(), IDX_NAME);
+}
+
+#[test]
+fn changelog_prefixed_in_memory() {
+    _changelog(&MemoryDB::new(), PREFIXED_IDX);
+}
+
 #[test]
 fn multiple_views() {
     let db = TemporaryDB::new();
@@ -589,6 +704,82 @@ fn rollbacks_for_indexes_in_same_family() {
     assert_eq!(list2.iter().collect::<Vec<_>>(), vec![2, 3, 5, 8]);
 }
 
+#[test]
+fn nested_savepoints() {
+    use crate::ListIndex;
+
+    fn items(fork: &Fork) -> Vec<u64> {
+        ListIndex::new("list", fork).iter().collect()
+    }
+
+    let db = TemporaryDB::new();
+    let mut fork = db.fork();
+    ListIndex::new("list", &fork).push(1_u64);
+    fork.flush();
+
+    ListIndex::new("list", &fork).push(2_u64);
+    let outer = fork.savepoint();
+    ListIndex::new("list", &fork).push(3_u64);
+    let inner = fork.savepoint();
+    ListIndex::new("list", &fork).push(4_u64);
+    assert_eq!(items(&fork), vec![1, 2, 3, 4]);
+
+    fork.rollback_to(inner);
+    assert_eq!(items(&fork), vec![1, 2, 3]);
+    let inner = fork.savepoint();
+    ListIndex::new("list", &fork).push(5_u64);
+    fork.release(inner);
+    assert_eq!(items(&fork), vec![1, 2, 3, 5]);
+
+    // Rolling back to the outer savepoint discards the changes of the nested ones.
+    let _nested = fork.savepoint();
+    ListIndex::new("list", &fork).clear();
+    assert_eq!(items(&fork), Vec::<u64>::new());
+    fork.rollback_to(outer);
+    assert_eq!(items(&fork), vec![1, 2]);
+
+    // Flushed changes are not affected by savepoints.
+    let savepoint = fork.savepoint();
+    fork.flush();
+    ListIndex::new("list", &fork).push(6_u64);
+    assert_eq!(items(&fork), vec![1, 2, 6]);
+    fork.rollback();
+    assert_eq!(items(&fork), vec![1, 2]);
+    drop(savepoint);
+
+    let savepoint = fork.savepoint();
+    ListIndex::new("list", &fork).push(7_u64);
+    fork.release(savepoint);
+    let patch = fork.into_patch();
+    db.merge(patch).unwrap();
+    let snapshot = db.snapshot();
+    let list: ListIndex<_, u64> = ListIndex::new("list", &snapshot);
+    assert_eq!(list.iter().collect::<Vec<_>>(), vec![1, 2, 7]);
+}
+
+#[test]
+#[should_panic(expected = "savepoint has already been released or rolled back")]
+fn savepoint_after_release() {
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let outer = fork.savepoint();
+    let inner = fork.savepoint();
+    fork.release(outer);
+    fork.rollback_to(inner);
+}
+
+#[test]
+#[should_panic(expected = "changes are still borrowed")]
+fn savepoint_with_alive_index() {
+    use crate::ListIndex;
+
+    let db = TemporaryDB::new();
+    let fork = db.fork();
+    let mut list = ListIndex::new("list", &fork);
+    list.push(1_u64);
+    let _savepoint = fork.savepoint();
+}
+
 #[test]
 fn clear_view() {
     _clear_view(&TemporaryDB::new(), IDX_NAME);
@@ -599,6 +790,16 @@ fn clear_prefixed_view() {
     _clear_view(&TemporaryDB::new(), PREFIXED_IDX);
 }
 
+#[test]
+fn clear_view_in_memory() {
+    _clear_view(&MemoryDB::new(), IDX_NAME);
+}
+
+#[test]
+fn clear_prefixed_view_in_memory() {
+    _clear_view(&MemoryDB::new(), PREFIXED_IDX);
+}
+
 #[test]
 fn clear_sibling_views() {
     const IDX_1: (&str, &[u8]) = ("foo", &[1_u8, 2] as &[u8]);
@@ -847,3 +1048,211 @@ fn multiple_patch() {
     let iter = index.iter();
     assert_eq!(index.len() as usize, iter.count());
 }
+
+#[test]
+fn undo_patch() {
+    use crate::ListIndex;
+
+    let db = MemoryDB::new();
+    let fork = db.fork();
+    ListIndex::new("list_index", &fork).extend(vec![1_u64, 2, 3]);
+    db.merge(fork.into_patch()).unwrap();
+
+    let fork = db.fork();
+    {
+        let mut index: ListIndex<_, u64> = ListIndex::new("list_index", &fork);
+        index.clear();
+        index.push(10);
+    }
+    let patch = fork.into_patch();
+    let undo = {
+        let snapshot = db.snapshot();
+        patch.clone().undo(&*snapshot)
+    };
+    db.merge(patch).unwrap();
+
+    let snapshot = db.snapshot();
+    let index: ListIndex<_, u64> = ListIndex::new("list_index", &snapshot);
+    assert_eq!(index.iter().collect::<Vec<_>>(), vec![10]);
+
+    // Applying the reverse patch restores the initial state of the index.
+    let snapshot = undo.clone().overlay(db.snapshot());
+    let index: ListIndex<_, u64> = ListIndex::new("list_index", &snapshot);
+    assert_eq!(index.iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+
+    db.merge(undo).unwrap();
+    let snapshot = db.snapshot();
+    let index: ListIndex<_, u64> = ListIndex::new("list_index", &snapshot);
+    assert_eq!(index.iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+}
+
+fn serialize_patch(patch: &db::Patch) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    patch.write_to(&mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn patch_binary_roundtrip() {
+    use crate::{ListIndex, MapIndex};
+
+    let db = MemoryDB::new();
+    let fork = db.fork();
+    ListIndex::new("list_index", &fork).extend(vec![1_u64, 2, 3]);
+    MapIndex::new("map_index", &fork).put(&1_u64, "one".to_owned());
+    db.merge(fork.into_patch()).unwrap();
+
+    let fork = db.fork();
+    {
+        let mut list: ListIndex<_, u64> = ListIndex::new("list_index", &fork);
+        list.clear();
+        list.push(10);
+        let mut map: MapIndex<_, u64, String> = MapIndex::new("map_index", &fork);
+        map.remove(&1);
+        map.put(&2, "two".to_owned());
+    }
+    let patch = fork.into_patch();
+    let bytes = serialize_patch(&patch);
+    // Serialization is deterministic.
+    assert_eq!(serialize_patch(&patch), bytes);
+
+    let restored = db::Patch::read_from(&bytes[..]).unwrap();
+    assert_eq!(restored.len(), patch.len());
+    assert_eq!(serialize_patch(&restored), bytes);
+
+    // Replaying the restored patch leads to the same state as merging the original one.
+    let replica = MemoryDB::new();
+    let fork = replica.fork();
+    ListIndex::new("list_index", &fork).extend(vec![1_u64, 2, 3]);
+    MapIndex::new("map_index", &fork).put(&1_u64, "one".to_owned());
+    replica.merge(fork.into_patch()).unwrap();
+    replica.merge(restored).unwrap();
+    db.merge(patch).unwrap();
+
+    for database in &[db, replica] {
+        let snapshot = database.snapshot();
+        let list: ListIndex<_, u64> = ListIndex::new("list_index", &snapshot);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![10]);
+        let map: MapIndex<_, u64, String> = MapIndex::new("map_index", &snapshot);
+        assert_eq!(map.iter().collect::<Vec<_>>(), vec![(2, "two".to_owned())]);
+    }
+}
+
+#[test]
+fn patch_binary_stream() {
+    let mut first = db::Patch::default();
+    first.insert("first", vec![1], db::Change::Put(vec![2]));
+    let mut second = db::Patch::default();
+    second.insert("second", vec![3], db::Change::Delete);
+
+    let mut bytes = Vec::new();
+    first.write_to(&mut bytes).unwrap();
+    second.write_to(&mut bytes).unwrap();
+    db::Patch::default().write_to(&mut bytes).unwrap();
+
+    let mut reader = &bytes[..];
+    let restored = db::Patch::read_from(&mut reader).unwrap();
+    assert_eq!(serialize_patch(&restored), serialize_patch(&first));
+    let restored = db::Patch::read_from(&mut reader).unwrap();
+    assert_eq!(serialize_patch(&restored), serialize_patch(&second));
+    let restored = db::Patch::read_from(&mut reader).unwrap();
+    assert!(restored.is_empty());
+    assert!(reader.is_empty());
+}
+
+#[test]
+fn patch_binary_invalid() {
+    let mut patch = db::Patch::default();
+    patch.insert("family", vec![1, 2], db::Change::Put(vec![3, 4, 5]));
+    patch.insert("family", vec![6], db::Change::Delete);
+    let bytes = serialize_patch(&patch);
+
+    // Any corruption of the content is detected.
+    for i in 0..bytes.len() {
+        let mut corrupted = bytes.clone();
+        corrupted[i] ^= 1;
+        assert!(db::Patch::read_from(&corrupted[..]).is_err());
+    }
+    // Truncated patches are rejected.
+    for len in 0..bytes.len() {
+        assert!(db::Patch::read_from(&bytes[..len]).is_err());
+    }
+
+    let mut unsupported = bytes.clone();
+    unsupported[4] = db::PATCH_FORMAT_VERSION + 1;
+    let err = db::Patch::read_from(&unsupported[..]).unwrap_err();
+    assert!(err.to_string().contains("Unsupported patch format version"));
+}
+
+#[test]
+fn indexes_info() {
+    use crate::{Entry, ListIndex, ObjectAccess, ProofMapIndex};
+
+    let db = MemoryDB::new();
+    let fork = db.fork();
+    assert!((&fork).indexes(true).unwrap().is_empty());
+
+    ListIndex::new("list", &fork).extend(vec![1_u64, 2, 3]);
+    Entry::new("entry", &fork).set(1_u64);
+    ListIndex::new_in_family("family", &5_u32, &fork).push(1_u64);
+    {
+        let mut map: ProofMapIndex<_, [u8; 32], u64> = ProofMapIndex::new("proof_map", &fork);
+        map.put(&[1; 32], 1_u64);
+        map.put(&[2; 32], 2_u64);
+    }
+
+    // Uncommitted changes are taken into account.
+    let indexes = (&fork).indexes(true).unwrap();
+    let names = indexes
+        .iter()
+        .map(|info| info.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["entry", "family", "list", "proof_map"]);
+
+    db.merge(fork.into_patch()).unwrap();
+    let snapshot = db.snapshot();
+    let committed = (&snapshot).indexes(true).unwrap();
+    assert_eq!(committed, indexes);
+
+    let entry = &committed[0];
+    assert_eq!(entry.index_type, IndexType::Entry);
+    assert_eq!(entry.family_id, None);
+    assert_eq!(entry.entries_count, Some(1));
+
+    let family = &committed[1];
+    assert_eq!(family.index_type, IndexType::List);
+    assert_eq!(family.family_id, Some(vec![0, 0, 0, 5]));
+    assert_eq!(family.entries_count, Some(1));
+
+    let list = &committed[2];
+    assert_eq!(list.index_type, IndexType::List);
+    assert_eq!(list.state, 3_u64.to_le_bytes().to_vec());
+    assert_eq!(list.entries_count, Some(3));
+    assert_ne!(list.identifier, family.identifier);
+
+    // Merkelized indexes store auxiliary entries besides the items.
+    let map = &committed[3];
+    assert_eq!(map.index_type, IndexType::ProofMap);
+    assert!(map.entries_count.unwrap() > 2);
+
+    // Entries are not counted unless requested.
+    let uncounted = (&snapshot).indexes(false).unwrap();
+    assert_eq!(uncounted.len(), committed.len());
+    assert!(uncounted.iter().all(|info| info.entries_count.is_none()));
+    assert_eq!(uncounted[2].state, list.state);
+}
+
+#[test]
+fn indexes_info_with_malformed_metadata() {
+    use crate::ObjectAccess;
+
+    let db = MemoryDB::new();
+    let fork = db.fork();
+    View::new(&fork, "__INDEXES_POOL__").put(&b"broken".to_vec(), vec![1_u8, 2, 3]);
+    db.merge(fork.into_patch()).unwrap();
+
+    let err = (&db.snapshot()).indexes(false).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Malformed metadata of index \"broken\""));
+}
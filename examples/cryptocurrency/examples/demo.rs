@@ -51,6 +51,9 @@ fn node_config() -> NodeConfig {
         mempool: Default::default(),
         services_configs: Default::default(),
         database: Default::default(),
+        state_history: Default::default(),
+        parallel_execution: Default::default(),
+        network_id: Default::default(),
         thread_pool_size: Default::default(),
     }
 }
@@ -17,7 +17,9 @@
 //! Private API includes requests that are available only to the blockchain
 //! administrators, e.g. view the list of services on the current node.
 
-use std::{collections::HashMap, net::SocketAddr};
+use exonum_merkledb::{IndexType, ObjectAccess};
+
+use std::{collections::HashMap, net::SocketAddr, path::Path};
 
 use crate::api::{Error as ApiError, ServiceApiScope, ServiceApiState};
 use crate::blockchain::{Service, SharedNodeState};
@@ -66,6 +68,38 @@ impl NodeInfo {
     }
 }
 
+/// Information about an index stored in the node database.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct IndexInfo {
+    /// Index name.
+    pub name: String,
+    /// Hex-encoded identifier of the index within its family, or `None` if the index
+    /// does not belong to a family.
+    pub family_id: Option<String>,
+    /// Unique numeric identifier of the index in the database.
+    pub identifier: u64,
+    /// Index type.
+    pub index_type: IndexType,
+    /// Hex-encoded index state.
+    pub state: String,
+    /// Approximate number of entries stored in the database for the index, or `None`
+    /// if the entries have not been counted.
+    pub entries_count: Option<u64>,
+}
+
+impl From<exonum_merkledb::IndexInfo> for IndexInfo {
+    fn from(info: exonum_merkledb::IndexInfo) -> Self {
+        Self {
+            name: info.name,
+            family_id: info.family_id.map(hex::encode),
+            identifier: info.identifier,
+            index_type: info.index_type,
+            state: hex::encode(info.state),
+            entries_count: info.entries_count,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Default)]
 struct ReconnectInfo {
     delay: u64,
@@ -101,6 +135,19 @@ struct ConsensusEnabledQuery {
     enabled: bool,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct IndexesQuery {
+    /// Whether to count entries of the indexes, which requires iterating
+    /// over the whole database.
+    #[serde(default)]
+    count_entries: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct BackupQuery {
+    path: String,
+}
+
 /// Private system API.
 #[derive(Clone, Debug)]
 pub struct SystemApi {
@@ -125,7 +172,11 @@ impl SystemApi {
             .handle_is_consensus_enabled("v1/consensus_enabled", api_scope)
             .handle_set_consensus_enabled("v1/consensus_enabled", api_scope)
             .handle_shutdown("v1/shutdown", api_scope)
-            .handle_rebroadcast("v1/rebroadcast", api_scope);
+            .handle_rebroadcast("v1/rebroadcast", api_scope)
+            .handle_indexes("v1/indexes", api_scope)
+            .handle_database_stats("v1/database_stats", api_scope)
+            .handle_cache_stats("v1/cache_stats", api_scope)
+            .handle_backup("v1/backup", api_scope);
         api_scope
     }
 
@@ -235,4 +286,57 @@ impl SystemApi {
         );
         self
     }
+
+    fn handle_indexes(self, name: &'static str, api_scope: &mut ServiceApiScope) -> Self {
+        api_scope.endpoint(
+            name,
+            move |state: &ServiceApiState,
+                  query: IndexesQuery|
+                  -> Result<Vec<IndexInfo>, ApiError> {
+                let snapshot = state.snapshot();
+                let indexes = (&snapshot)
+                    .indexes(query.count_entries)
+                    .map_err(|e| ApiError::InternalError(e.into()))?
+                    .into_iter()
+                    .map(IndexInfo::from)
+                    .collect();
+                Ok(indexes)
+            },
+        );
+        self
+    }
+
+    fn handle_database_stats(self, name: &'static str, api_scope: &mut ServiceApiScope) -> Self {
+        api_scope.endpoint(name, move |state: &ServiceApiState, _query: ()| {
+            state
+                .blockchain()
+                .storage_stats()
+                .map_err(|e| ApiError::InternalError(e.into()))
+        });
+        self
+    }
+
+    fn handle_cache_stats(self, name: &'static str, api_scope: &mut ServiceApiScope) -> Self {
+        api_scope.endpoint(name, move |state: &ServiceApiState, _query: ()| {
+            state
+                .blockchain()
+                .cache_stats()
+                .map_err(|e| ApiError::InternalError(e.into()))
+        });
+        self
+    }
+
+    fn handle_backup(self, name: &'static str, api_scope: &mut ServiceApiScope) -> Self {
+        api_scope.endpoint_mut(
+            name,
+            move |state: &ServiceApiState, query: BackupQuery| -> Result<(), ApiError> {
+                info!("Creating database backup at {}", query.path);
+                state
+                    .blockchain()
+                    .create_checkpoint(Path::new(&query.path))
+                    .map_err(|e| ApiError::InternalError(e.into()))
+            },
+        );
+        self
+    }
 }
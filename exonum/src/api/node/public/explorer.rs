@@ -31,7 +31,7 @@ use crate::{
         websocket::{Server, Session, SubscriptionType, TransactionFilter},
         Error as ApiError, ServiceApiBackend, ServiceApiScope, ServiceApiState,
     },
-    blockchain::{Block, SharedNodeState},
+    blockchain::{run_check, Block, Blockchain, Schema, SharedNodeState},
     crypto::Hash,
     explorer::{self, BlockchainExplorer, TransactionInfo},
     helpers::Height,
@@ -42,6 +42,34 @@ use crate::{
 /// the parameter limits the maximum execution time for such requests.
 pub const MAX_BLOCKS_PER_REQUEST: usize = 1000;
 
+/// Checks that the transaction submitted via the API can be parsed by its service,
+/// is bound to the network of the node, can be committed at the next height
+/// and passes `Transaction::check`, so that the rejection reason can be returned
+/// to the client.
+pub(crate) fn check_transaction(
+    blockchain: &Blockchain,
+    signed: &Signed<RawTransaction>,
+) -> Result<(), failure::Error> {
+    let raw = signed.payload();
+    let tx = blockchain.tx_from_raw(raw.clone())?;
+    if let Some(network_id) = blockchain.network_id() {
+        ensure!(
+            signed.network_id() == Some(network_id),
+            "Transaction rejected: not bound to network {:?}",
+            network_id
+        );
+    }
+    let snapshot = blockchain.snapshot();
+    let height = Schema::new(&snapshot).height().next();
+    ensure!(
+        raw.is_valid_at(height),
+        "Transaction rejected: not valid at height {}",
+        height
+    );
+    run_check(tx.as_ref(), snapshot.as_ref())
+        .map_err(|e| format_err!("Transaction rejected: {}", e))
+}
+
 /// Information on blocks coupled with the corresponding range in the blockchain.
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct BlocksRange {
@@ -253,6 +281,8 @@ impl ExplorerApi {
         let tx_hash = signed.hash();
         let signed = RawTransaction::try_from(Message::deserialize(signed)?)
             .map_err(|_| format_err!("Couldn't deserialize transaction message."))?;
+        check_transaction(state.blockchain(), &signed)
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?;
         let _ = state
             .sender()
             .broadcast_transaction(signed)
@@ -30,7 +30,7 @@ use std::{
 };
 
 use crate::api::{
-    node::public::explorer::{TransactionHex, TransactionResponse},
+    node::public::explorer::{check_transaction, TransactionHex, TransactionResponse},
     ServiceApiState,
 };
 use crate::blockchain::{Block, Schema, TransactionResult, TxLocation};
@@ -348,6 +348,7 @@ impl Handler<Transaction> for Server {
         let tx_hash = signed.hash();
         let signed = RawTransaction::try_from(ExonumMessage::deserialize(signed)?)
             .map_err(|_| format_err!("Couldn't deserialize transaction message."))?;
+        check_transaction(self.service_api_state.blockchain(), &signed)?;
         let _ = self
             .service_api_state
             .sender()
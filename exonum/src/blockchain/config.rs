@@ -26,7 +26,7 @@ use serde_json::Error as JsonError;
 
 use std::collections::{BTreeMap, HashSet};
 
-use crate::crypto::{hash, CryptoHash, Hash, PublicKey};
+use crate::crypto::{hash, CryptoHash, Hash, PublicKey, HASH_SIZE};
 use crate::helpers::{Height, Milliseconds};
 use crate::messages::EMPTY_SIGNED_MESSAGE_SIZE;
 use exonum_merkledb::{BinaryValue, ObjectHash};
@@ -192,7 +192,9 @@ impl StoredConfiguration {
     /// configuration. The method returns either the result of execution or an error.
     pub fn try_deserialize(serialized: &[u8]) -> Result<Self, JsonError> {
         const MINIMAL_BODY_SIZE: usize = 256;
-        const MINIMAL_MESSAGE_LENGTH: u32 = (MINIMAL_BODY_SIZE + EMPTY_SIGNED_MESSAGE_SIZE) as u32;
+        // Messages bound to the network contain the network identifier in addition to the body.
+        const MINIMAL_MESSAGE_LENGTH: u32 =
+            (MINIMAL_BODY_SIZE + HASH_SIZE + EMPTY_SIGNED_MESSAGE_SIZE) as u32;
 
         let config: Self = serde_json::from_slice(serialized)?;
 
@@ -0,0 +1,255 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Access to the blockchain state at past heights.
+//!
+//! When the state history is enabled, each committed block is accompanied by
+//! a reverse patch, i.e., the changes that revert the storage to the state
+//! before the block. Reverse patches are kept in the core schema, so the state
+//! at a past height can be reconstructed by applying the reverse patches
+//! of all subsequent blocks to the current snapshot.
+
+use byteorder::{BigEndian, ByteOrder};
+
+use std::borrow::Cow;
+
+use exonum_merkledb::{BinaryKey, BinaryValue, Change, Fork, Patch, Snapshot};
+
+use super::Schema;
+use crate::helpers::Height;
+
+/// Configuration of the blockchain state history.
+///
+/// # Examples
+///
+/// The history is configured in the `state_history` section of the node configuration:
+///
+/// ```toml
+/// [state_history]
+/// enabled = true
+/// depth = 10000
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StateHistoryConfig {
+    /// Whether the node should keep the data required to access the blockchain state
+    /// at past heights.
+    pub enabled: bool,
+    /// Number of the latest blocks for which the history is kept (the pruning horizon).
+    /// The state can be accessed at heights starting from `current_height - depth`.
+    ///
+    /// Defaults to `None`, meaning that the history is never pruned.
+    #[serde(default)]
+    pub depth: Option<u64>,
+}
+
+/// Key of a single change in the state history: the height of the block the change
+/// reverts, the name of the column family and the key within the family.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StateChangeKey {
+    height: u64,
+    name: String,
+    key: Vec<u8>,
+}
+
+impl StateChangeKey {
+    fn new(height: Height, name: &str, key: &[u8]) -> Self {
+        Self {
+            height: height.0,
+            name: name.to_owned(),
+            key: key.to_vec(),
+        }
+    }
+
+    fn height(&self) -> Height {
+        Height(self.height)
+    }
+}
+
+// Column family names cannot contain zero bytes, so zero is used as a separator
+// between the family name and the key.
+impl BinaryKey for StateChangeKey {
+    fn size(&self) -> usize {
+        8 + self.name.len() + 1 + self.key.len()
+    }
+
+    fn write(&self, buffer: &mut [u8]) -> usize {
+        let name_end = 8 + self.name.len();
+        BigEndian::write_u64(&mut buffer[..8], self.height);
+        buffer[8..name_end].copy_from_slice(self.name.as_bytes());
+        buffer[name_end] = 0;
+        buffer[name_end + 1..].copy_from_slice(&self.key);
+        self.size()
+    }
+
+    fn read(buffer: &[u8]) -> Self {
+        let height = BigEndian::read_u64(&buffer[..8]);
+        let name_len = buffer[8..]
+            .iter()
+            .position(|&byte| byte == 0)
+            .expect("Missing column family separator in the state history key");
+        let name_end = 8 + name_len;
+        Self {
+            height,
+            name: String::from_utf8(buffer[8..name_end].to_vec())
+                .expect("Invalid column family name in the state history key"),
+            key: buffer[name_end + 1..].to_vec(),
+        }
+    }
+}
+
+/// Value of a single change in the state history: the value of the key before
+/// the block, or `None` if the key was absent.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct StateChangeValue(Option<Vec<u8>>);
+
+impl From<&Change> for StateChangeValue {
+    fn from(change: &Change) -> Self {
+        match *change {
+            Change::Put(ref value) => StateChangeValue(Some(value.clone())),
+            Change::Delete => StateChangeValue(None),
+        }
+    }
+}
+
+impl From<StateChangeValue> for Change {
+    fn from(value: StateChangeValue) -> Self {
+        match value.0 {
+            Some(value) => Change::Put(value),
+            None => Change::Delete,
+        }
+    }
+}
+
+impl BinaryValue for StateChangeValue {
+    fn to_bytes(&self) -> Vec<u8> {
+        match self.0 {
+            Some(ref value) => {
+                let mut bytes = Vec::with_capacity(value.len() + 1);
+                bytes.push(1);
+                bytes.extend_from_slice(value);
+                bytes
+            }
+            None => vec![0],
+        }
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Result<Self, failure::Error> {
+        match bytes.split_first() {
+            Some((0, rest)) if rest.is_empty() => Ok(StateChangeValue(None)),
+            Some((1, value)) => Ok(StateChangeValue(Some(value.to_vec()))),
+            _ => bail!("Invalid state history value"),
+        }
+    }
+}
+
+/// Records the reverse patch of the block at the specified height into the state history
+/// and prunes the history according to the configuration.
+pub(crate) fn record(
+    fork: &Fork,
+    height: Height,
+    reverse_patch: &Patch,
+    config: StateHistoryConfig,
+) {
+    let schema = Schema::new(fork);
+    let mut history = schema.state_history();
+
+    // The history must be contiguous, so it is dropped if the previous block
+    // has not been recorded.
+    let previous_recorded = history
+        .keys_from(&StateChangeKey::new(height.previous(), "", &[]))
+        .next()
+        .map_or(false, |key| key.height() == height.previous());
+    if !previous_recorded && !history.is_empty() {
+        history.clear();
+    }
+
+    for (name, changes) in reverse_patch.iter() {
+        for (key, change) in changes.iter() {
+            history.put(&StateChangeKey::new(height, name, key), change.into());
+        }
+    }
+
+    if let Some(depth) = config.depth {
+        let horizon = height.0.saturating_sub(depth);
+        let pruned = history
+            .keys()
+            .take_while(|key| key.height <= horizon)
+            .collect::<Vec<_>>();
+        for key in pruned {
+            history.remove(&key);
+        }
+    }
+}
+
+/// Reconstructs the blockchain state at the specified height from the current `snapshot`.
+/// Returns `None` if the state history does not cover the requested height.
+pub(crate) fn snapshot_at(
+    snapshot: Box<dyn Snapshot>,
+    height: Height,
+) -> Option<Box<dyn Snapshot>> {
+    let changes = {
+        let schema = Schema::new(&snapshot);
+        let blocks_count = schema.block_hashes_by_height().len();
+        if height.0 >= blocks_count {
+            return None;
+        }
+        let current_height = Height(blocks_count - 1);
+        if height == current_height {
+            return Some(snapshot);
+        }
+
+        let changes = schema
+            .state_history()
+            .iter_from(&StateChangeKey::new(height.next(), "", &[]))
+            .collect::<Vec<_>>();
+        // Reverse patches should be present for all the blocks after the requested height.
+        let first = changes.first().map(|(key, _)| key.height());
+        let last = changes.last().map(|(key, _)| key.height());
+        if first != Some(height.next()) || last != Some(current_height) {
+            return None;
+        }
+        changes
+    };
+
+    // Reverse patches are applied starting from the latest block, so the changes
+    // from the earlier blocks take precedence.
+    let mut patch = Patch::default();
+    for (key, value) in changes.into_iter().rev() {
+        patch.insert(&key.name, key.key, value.into());
+    }
+    Some(patch.overlay(snapshot))
+}
+
+#[cfg(test)]
+mod tests {
+    use exonum_merkledb::BinaryKey;
+
+    use super::StateChangeKey;
+    use crate::helpers::Height;
+
+    #[test]
+    fn state_change_key_roundtrip() {
+        let key = StateChangeKey::new(Height(5), "family", &[0, 1, 2]);
+        let mut buffer = vec![0; key.size()];
+        key.write(&mut buffer);
+        assert_eq!(StateChangeKey::read(&buffer), key);
+
+        let key = StateChangeKey::new(Height(6), "", &[]);
+        let mut next_buffer = vec![0; key.size()];
+        key.write(&mut next_buffer);
+        assert_eq!(StateChangeKey::read(&next_buffer), key);
+        // Keys are ordered by the height first.
+        assert!(buffer < next_buffer);
+    }
+}
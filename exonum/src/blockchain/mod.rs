@@ -35,6 +35,8 @@ pub use self::{
     block::{Block, BlockProof},
     config::{ConsensusConfig, StoredConfiguration, ValidatorKeys},
     genesis::GenesisConfig,
+    history::StateHistoryConfig,
+    parallel::ParallelExecutionConfig,
     schema::{Schema, TxLocation},
     service::{Service, ServiceContext, SharedNodeState},
     transaction::{
@@ -43,6 +45,8 @@ pub use self::{
     },
 };
 
+pub(crate) use self::transaction::run_check;
+
 pub mod config;
 
 use byteorder::{ByteOrder, LittleEndian};
@@ -50,20 +54,28 @@ use byteorder::{ByteOrder, LittleEndian};
 use std::{
     collections::{BTreeMap, HashMap},
     fmt, iter, mem, panic,
+    path::Path,
     sync::Arc,
 };
 
+use self::parallel::ParallelExecutor;
 use crate::crypto::{self, CryptoHash, Hash, PublicKey, SecretKey};
 use crate::helpers::{Height, Round, ValidatorId};
-use crate::messages::{Connect, Message, Precommit, ProtocolMessage, RawTransaction, Signed};
+use crate::messages::{
+    Connect, Message, Precommit, ProtocolMessage, RawTransaction, Signed, MAX_SERVICE_ID,
+};
 use crate::node::ApiSender;
 use exonum_merkledb::{
-    self, Database, Error as StorageError, Fork, IndexAccess, ObjectHash, Patch,
+    self, CacheStats, Database, DbStats, Error as StorageError, Fork, IndexAccess,
+    MigrationProgress, MigrationReport, MigrationSet, MigrationStage, ObjectHash, Patch,
     Result as StorageResult, Snapshot,
 };
 
 mod block;
 mod genesis;
+mod history;
+mod parallel;
+mod pool;
 mod schema;
 mod service;
 #[macro_use]
@@ -84,6 +96,10 @@ pub struct Blockchain {
     #[doc(hidden)]
     pub service_keypair: (PublicKey, SecretKey),
     pub(crate) api_sender: ApiSender,
+    state_history: StateHistoryConfig,
+    parallel_execution: ParallelExecutionConfig,
+    parallel_executor: Option<ParallelExecutor>,
+    network_id: Option<Hash>,
 }
 
 impl Blockchain {
@@ -98,6 +114,12 @@ impl Blockchain {
         let mut service_map = HashMap::new();
         for service in services {
             let id = service.service_id();
+            if id > MAX_SERVICE_ID {
+                panic!(
+                    "Service id={} exceeds the maximum allowed value {}, please change it.",
+                    id, MAX_SERVICE_ID
+                );
+            }
             if service_map.contains_key(&id) {
                 panic!(
                     "Services have already contain service with id={}, please change it.",
@@ -112,9 +134,45 @@ impl Blockchain {
             service_map: Arc::new(service_map),
             service_keypair: (service_public_key, service_secret_key),
             api_sender,
+            state_history: StateHistoryConfig::default(),
+            parallel_execution: ParallelExecutionConfig::default(),
+            parallel_executor: None,
+            network_id: None,
         }
     }
 
+    /// Sets the configuration of the blockchain state history. The history is recorded
+    /// for the blocks committed after this call; see [`snapshot_at`] for details.
+    ///
+    /// [`snapshot_at`]: #method.snapshot_at
+    pub fn set_state_history_config(&mut self, config: StateHistoryConfig) {
+        self.state_history = config;
+    }
+
+    /// Sets the configuration of the parallel execution of transactions in
+    /// [`create_patch`]. The resulting patch does not depend on the configuration.
+    ///
+    /// [`create_patch`]: #method.create_patch
+    pub fn set_parallel_execution_config(&mut self, config: ParallelExecutionConfig) {
+        self.parallel_executor = ParallelExecutor::new(&config);
+        self.parallel_execution = config;
+    }
+
+    /// Sets the identifier of the network which the messages signed by the node are bound to.
+    /// The node rejects messages which are not bound to this identifier.
+    ///
+    /// See [`Message::concrete_for_network`] for details.
+    ///
+    /// [`Message::concrete_for_network`]: ../messages/enum.Message.html#method.concrete_for_network
+    pub fn set_network_id(&mut self, network_id: Option<Hash>) {
+        self.network_id = network_id;
+    }
+
+    /// Returns the identifier of the network which the messages signed by the node are bound to.
+    pub fn network_id(&self) -> Option<Hash> {
+        self.network_id
+    }
+
     /// Recreates the blockchain to reuse with a sandbox.
     #[doc(hidden)]
     pub fn clone_with_api_sender(&self, api_sender: ApiSender) -> Self {
@@ -135,12 +193,58 @@ impl Blockchain {
         self.db.snapshot()
     }
 
+    /// Creates a read-only snapshot of the storage state at the specified height, i.e.,
+    /// right after the block at this height has been committed.
+    ///
+    /// Past states are available only if the state history is enabled with
+    /// [`set_state_history_config`]. Returns `None` if the height is greater than
+    /// the current height or the history for the requested height has not been recorded
+    /// or has already been pruned.
+    ///
+    /// Note that the state history covers only the changes made by blocks; the changes
+    /// made to the storage between blocks (such as adding transactions to the pool)
+    /// are not reverted.
+    ///
+    /// [`set_state_history_config`]: #method.set_state_history_config
+    pub fn snapshot_at(&self, height: Height) -> Option<Box<dyn Snapshot>> {
+        history::snapshot_at(self.snapshot(), height)
+    }
+
     /// Creates a snapshot of the current storage state that can be later committed into the storage
     /// via the `merge` method.
     pub fn fork(&self) -> Fork {
         self.db.fork()
     }
 
+    /// Returns storage statistics of the underlying database.
+    ///
+    /// See [`Database::storage_stats`] for details.
+    ///
+    /// [`Database::storage_stats`]: ../../exonum_merkledb/trait.Database.html#method.storage_stats
+    pub fn storage_stats(&self) -> StorageResult<DbStats> {
+        self.db.storage_stats()
+    }
+
+    /// Returns statistics of the in-memory cache of the underlying database.
+    ///
+    /// See [`Database::cache_stats`] for details.
+    ///
+    /// [`Database::cache_stats`]: ../../exonum_merkledb/trait.Database.html#method.cache_stats
+    pub fn cache_stats(&self) -> StorageResult<CacheStats> {
+        self.db.cache_stats()
+    }
+
+    /// Creates a consistent copy of the underlying database in the directory at
+    /// the specified path, which must not exist. The blockchain remains operational
+    /// while the copy is being created.
+    ///
+    /// See [`Database::create_checkpoint`] for details.
+    ///
+    /// [`Database::create_checkpoint`]: ../../exonum_merkledb/trait.Database.html#method.create_checkpoint
+    pub fn create_checkpoint(&self, path: &Path) -> StorageResult<()> {
+        self.db.create_checkpoint(path)
+    }
+
     /// Tries to create a `Transaction` object from the given raw message.
     /// A raw message can be converted into a `Transaction` object only
     /// if the following conditions are met:
@@ -185,16 +289,64 @@ impl Blockchain {
     ///
     /// * If the genesis block was not committed.
     /// * If storage version is not specified or not supported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if pending migrations of the service schemas cannot be applied
+    /// (see [`migrate`](#method.migrate)).
     pub fn initialize(&mut self, cfg: GenesisConfig) -> Result<(), failure::Error> {
         let has_genesis_block = !Schema::new(&self.snapshot())
             .block_hashes_by_height()
             .is_empty();
-        if !has_genesis_block {
+        if has_genesis_block {
+            self.migrate(false)?;
+            self.restore_pool_entries()?;
+        } else {
             self.create_genesis_block(cfg)?;
         }
         Ok(())
     }
 
+    /// Applies pending migrations of the service schemas, which are not bound
+    /// to a specific height (see [`Service::migration_height`]), and returns reports
+    /// for the schemas with applied migrations. The migrations of all services are
+    /// applied atomically. If `dry_run` is `true`, the migrations are run without
+    /// changing the database.
+    ///
+    /// This method is called on the node startup; the progress of migrations is logged.
+    ///
+    /// [`Service::migration_height`]: trait.Service.html#method.migration_height
+    pub fn migrate(&mut self, dry_run: bool) -> Result<Vec<MigrationReport>, failure::Error> {
+        let fork = self.fork();
+        let mut reports = Vec::new();
+        for service in self.service_map.values() {
+            if service.migration_height().is_some() {
+                continue;
+            }
+            let report = migration_set(service.as_ref()).apply(&fork, log_migration_progress)?;
+            if !report.applied.is_empty() {
+                reports.push(report);
+            }
+        }
+
+        if !dry_run {
+            self.merge(fork.into_patch())?;
+        }
+        Ok(reports)
+    }
+
+    // Adds the transactions kept in the pool by the previous versions of the node
+    // to the auxiliary indexes of the pool, so that they can be proposed and evicted.
+    fn restore_pool_entries(&mut self) -> Result<(), failure::Error> {
+        let fork = self.fork();
+        let restored = Schema::new(&fork).restore_pool_entries();
+        if restored > 0 {
+            info!("Restored {} transactions in the pool indexes", restored);
+            self.merge(fork.into_patch())?;
+        }
+        Ok(())
+    }
+
     /// Creates and commits the genesis block with the given genesis configuration.
     fn create_genesis_block(&mut self, cfg: GenesisConfig) -> Result<(), failure::Error> {
         let mut config_propose = StoredConfiguration {
@@ -210,6 +362,8 @@ impl Blockchain {
             // Update service tables
             for (_, service) in self.service_map.iter() {
                 let cfg = service.initialize(&fork);
+                // Initialized schema has the latest layout.
+                migration_set(service.as_ref()).set_latest_version(&fork);
                 let name = service.service_name();
                 if config_propose.services.contains_key(name) {
                     panic!(
@@ -265,9 +419,9 @@ impl Blockchain {
                 service_id
             ));
         }
-        let msg = Message::sign_transaction(
-            tx.service_transaction(),
-            service_id,
+        let msg = Message::concrete_for_network(
+            tx,
+            self.network_id,
             self.service_keypair.0,
             &self.service_keypair.1,
         );
@@ -290,13 +444,48 @@ impl Blockchain {
         let block_hash = {
             // Get last hash.
             let last_hash = self.last_hash();
+            // Apply migrations scheduled for this height.
+            let mut has_migrations = false;
+            for service in self.service_map.values() {
+                if service.migration_height() == Some(height) {
+                    migrate_at_height(service.as_ref(), height, &mut fork);
+                    has_migrations = true;
+                }
+            }
+            // Execute transactions optimistically. Migrations change the state
+            // the transactions are executed against, so they disable the parallel execution.
+            let executor = self.parallel_executor.as_ref().filter(|_| {
+                !has_migrations && self.parallel_execution.is_applicable(tx_hashes.len())
+            });
+            let in_parallel = executor.is_some();
+            let mut executions = match executor {
+                Some(executor) => executor.execute_transactions(self, tx_hashes, height),
+                None => Vec::new(),
+            }
+            .into_iter();
+            let mut conflicts = 0;
             // Save & execute transactions.
             for (index, hash) in tx_hashes.iter().enumerate() {
+                if let Some(execution) = executions.next() {
+                    if let Some(tx_result) = execution.and_then(|e| e.apply(&mut fork)) {
+                        Self::save_transaction_result(*hash, tx_result, height, index, &mut fork);
+                        continue;
+                    }
+                    conflicts += 1;
+                }
                 self.execute_transaction(*hash, height, index, &mut fork)
                     // Execution could fail if the transaction
                     // cannot be deserialized or it isn't in the pool.
                     .expect("Transaction execution error.");
             }
+            if in_parallel {
+                debug!(
+                    "Executed {} transactions in parallel at height {}, {} of them re-executed",
+                    tx_hashes.len(),
+                    height,
+                    conflicts
+                );
+            }
 
             // Invoke execute method for all services.
             for service in self.service_map.values() {
@@ -377,42 +566,75 @@ impl Blockchain {
         index: usize,
         fork: &mut Fork,
     ) -> Result<(), failure::Error> {
-        let (tx, raw, service_name) = {
-            let new_fork = &*fork;
-            let snapshot = new_fork.snapshot();
-            let schema = Schema::new(snapshot);
+        let (tx, raw, service_name) = self.load_transaction((&*fork).snapshot(), tx_hash)?;
+        let tx_result =
+            Self::execute_in_fork(tx_hash, tx.as_ref(), &raw, service_name, height, fork);
+        Self::save_transaction_result(tx_hash, tx_result, height, index, fork);
+        Ok(())
+    }
+
+    /// Loads the transaction with the given hash along with the name of its service.
+    fn load_transaction(
+        &self,
+        snapshot: &dyn Snapshot,
+        tx_hash: Hash,
+    ) -> Result<(Box<dyn Transaction>, Signed<RawTransaction>, &str), failure::Error> {
+        let schema = Schema::new(snapshot);
+
+        let raw = schema.transactions().get(&tx_hash).ok_or_else(|| {
+            failure::err_msg(format!(
+                "BUG: Cannot find transaction in database. tx: {:?}",
+                tx_hash
+            ))
+        })?;
 
-            let raw = schema.transactions().get(&tx_hash).ok_or_else(|| {
+        let service_name = self
+            .service_map
+            .get(&raw.service_id())
+            .ok_or_else(|| {
                 failure::err_msg(format!(
-                    "BUG: Cannot find transaction in database. tx: {:?}",
-                    tx_hash
+                    "Service not found. Service id: {}",
+                    raw.service_id()
                 ))
-            })?;
-
-            let service_name = self
-                .service_map
-                .get(&raw.service_id())
-                .ok_or_else(|| {
-                    failure::err_msg(format!(
-                        "Service not found. Service id: {}",
-                        raw.service_id()
-                    ))
-                })?
-                .service_name();
-
-            let tx = self.tx_from_raw(raw.payload().clone()).map_err(|error| {
-                format_err!("Service <{}>: {}, tx: {:?}", service_name, error, tx_hash)
-            })?;
-
-            (tx, raw, service_name)
-        };
+            })?
+            .service_name();
+
+        let tx = self.tx_from_raw(raw.payload().clone()).map_err(|error| {
+            format_err!("Service <{}>: {}, tx: {:?}", service_name, error, tx_hash)
+        })?;
+
+        Ok((tx, raw, service_name))
+    }
+
+    /// Executes the transaction in the fork. Changes made by a failed transaction
+    /// are rolled back.
+    fn execute_in_fork(
+        tx_hash: Hash,
+        tx: &dyn Transaction,
+        raw: &Signed<RawTransaction>,
+        service_name: &str,
+        height: Height,
+        fork: &mut Fork,
+    ) -> TransactionResult {
+        // Transactions committed outside of their validity bounds are not executed.
+        if let Some(validity) = raw.payload().validity().filter(|v| !v.contains(height)) {
+            info!(
+                "Service <{}>: {:?} transaction is not valid at height {}",
+                service_name, tx_hash, height
+            );
+            return TransactionResult(Err(TransactionError::invalid_height(Some(format!(
+                "Transaction is valid only at heights from {} to {}",
+                validity.min_height(),
+                validity.max_height()
+            )))));
+        }
 
         let catch_result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
-            let context = TransactionContext::new(&*fork, service_name, &raw);
+            let context = TransactionContext::new(&*fork, service_name, raw);
             tx.execute(context)
         }));
 
-        let tx_result = TransactionResult(match catch_result {
+        TransactionResult(match catch_result {
             Ok(execution_result) => {
                 if let Err(ref e) = execution_result {
                     // Unlike panic, transaction failure isn't that rare, so logging the
@@ -438,8 +660,17 @@ impl Blockchain {
 
                 Err(TransactionError::from_panic(&err))
             }
-        });
+        })
+    }
 
+    /// Saves the result and the location of the executed transaction and flushes the fork.
+    fn save_transaction_result(
+        tx_hash: Hash,
+        tx_result: TransactionResult,
+        height: Height,
+        index: usize,
+        fork: &mut Fork,
+    ) {
         let mut schema = Schema::new(&*fork);
         schema.transaction_results().put(&tx_hash, tx_result);
         schema.commit_transaction(&tx_hash);
@@ -447,7 +678,6 @@ impl Blockchain {
         let location = TxLocation::new(height, index as u64);
         schema.transactions_locations().put(&tx_hash, location);
         fork.flush();
-        Ok(())
     }
 
     /// Commits to the blockchain a new block with the indicated changes (patch),
@@ -483,8 +713,32 @@ impl Blockchain {
                     .transactions_pool_len_index()
                     .set(txs_count - u64::from(txs_in_block));
                 schema.update_transaction_count(u64::from(txs_in_block));
+
+                let height = schema.height();
+                let expired = schema.remove_expired_transactions(height);
+                if expired > 0 {
+                    debug!(
+                        "Removed {} expired transactions from the pool at height {}",
+                        expired, height
+                    );
+                }
+            }
+
+            if self.state_history.enabled {
+                let height = Schema::new(&fork).height();
+                let patch = fork.into_patch();
+                let reverse_patch = patch.clone().undo(&*self.snapshot());
+
+                let fork = {
+                    let mut fork = self.db.fork();
+                    fork.merge(patch);
+                    fork
+                };
+                history::record(&fork, height, &reverse_patch, self.state_history);
+                fork.into_patch()
+            } else {
+                fork.into_patch()
             }
-            fork.into_patch()
         };
         self.merge(patch)?;
 
@@ -496,7 +750,8 @@ impl Blockchain {
                 self.api_sender.clone(),
                 self.fork(),
                 *service_id,
-            );
+            )
+            .with_network_id(self.network_id);
             service.after_commit(&context);
         }
         Ok(())
@@ -566,6 +821,47 @@ fn before_commit(service: &dyn Service, fork: &mut Fork) {
     }
 }
 
+fn migration_set(service: &dyn Service) -> MigrationSet {
+    service
+        .migrations()
+        .into_iter()
+        .fold(MigrationSet::new(service.service_name()), MigrationSet::add)
+}
+
+/// Applies migrations of the service scheduled for the block being created.
+///
+/// Migrations bound to a height change the state of the blockchain, so if a migration fails,
+/// the node cannot create the block agreed upon with other nodes. Similar to storage errors
+/// during the transaction execution, the failure halts the node.
+fn migrate_at_height(service: &dyn Service, height: Height, fork: &mut Fork) {
+    if let Err(err) = migration_set(service).apply(fork, log_migration_progress) {
+        panic!(
+            "{} service migration at height {} failed: {}",
+            service.service_name(),
+            height,
+            err
+        );
+    }
+    fork.flush();
+}
+
+fn log_migration_progress(progress: &MigrationProgress) {
+    match progress.stage {
+        MigrationStage::Started => info!(
+            "Applying migration {} of `{}` schema: {}",
+            progress.version, progress.namespace, progress.description
+        ),
+        MigrationStage::InProgress { done, total } => info!(
+            "Migration {} of `{}` schema: processed {} of {}",
+            progress.version, progress.namespace, done, total
+        ),
+        MigrationStage::Finished => info!(
+            "Migration {} of `{}` schema has been applied",
+            progress.version, progress.namespace
+        ),
+    }
+}
+
 impl fmt::Debug for Blockchain {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "Blockchain(..)")
@@ -579,6 +875,10 @@ impl Clone for Blockchain {
             service_map: Arc::clone(&self.service_map),
             api_sender: self.api_sender.clone(),
             service_keypair: self.service_keypair.clone(),
+            state_history: self.state_history,
+            parallel_execution: self.parallel_execution,
+            parallel_executor: self.parallel_executor.clone(),
+            network_id: self.network_id,
         }
     }
 }
@@ -0,0 +1,216 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Optimistic parallel execution of transactions.
+//!
+//! Transactions of a block are executed on several threads, each against the state
+//! of the blockchain before the block, while the data read by every transaction is tracked.
+//! The results are then applied in the order of transactions in the block. A transaction
+//! which has read data changed by the preceding transactions of the block is executed
+//! once again, so the resulting state is the same as with the sequential execution.
+//!
+//! Worker threads take their own snapshots of the database, so the database must not be
+//! modified while the block is being executed. This holds for the node, since blocks are
+//! executed and committed by the same thread which updates the pool of transactions.
+
+use futures::{future, sync::oneshot, Future};
+use tokio_threadpool::{Builder as ThreadPoolBuilder, ThreadPool};
+
+use std::{fmt, panic, sync::Arc};
+
+use super::{Blockchain, TransactionResult};
+use crate::crypto::Hash;
+use crate::helpers::Height;
+use exonum_merkledb::{Fork, Patch, ReadSet};
+
+/// Configuration of the parallel execution of transactions.
+///
+/// Transactions of a block are first executed optimistically on several threads.
+/// Transactions which read data modified by the preceding transactions of the block
+/// are then re-executed sequentially, so the parallel execution pays off only if most
+/// transactions in a block access disjoint data. In particular, every update of a Merkelized
+/// index, such as `ProofMapIndex`, reads the root node of the index, so transactions updating
+/// the same Merkelized index always conflict with each other.
+///
+/// # Examples
+///
+/// The parallel execution is configured in the `parallel_execution` section of the node
+/// configuration:
+///
+/// ```toml
+/// [parallel_execution]
+/// enabled = true
+/// threads = 4
+/// min_transactions = 16
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParallelExecutionConfig {
+    /// Whether the transactions are executed in parallel.
+    pub enabled: bool,
+    /// Number of threads executing the transactions.
+    pub threads: usize,
+    /// Minimum number of transactions in a block for which the parallel execution is used.
+    /// Smaller blocks are executed sequentially.
+    pub min_transactions: usize,
+}
+
+impl Default for ParallelExecutionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threads: 4,
+            min_transactions: 16,
+        }
+    }
+}
+
+impl ParallelExecutionConfig {
+    /// Checks if a block with the given number of transactions should be executed in parallel.
+    pub(crate) fn is_applicable(&self, tx_count: usize) -> bool {
+        self.enabled && self.threads > 1 && tx_count > 1 && tx_count >= self.min_transactions
+    }
+}
+
+/// Result of the optimistic execution of a transaction.
+pub(crate) struct OptimisticExecution {
+    result: TransactionResult,
+    changes: Patch,
+    reads: ReadSet,
+}
+
+impl OptimisticExecution {
+    /// Applies the changes made by the transaction to the fork, provided that the data read
+    /// by the transaction has not been changed in the fork. Returns the result of the
+    /// transaction, or `None` if the transaction should be executed once again.
+    pub(crate) fn apply(self, fork: &mut Fork) -> Option<TransactionResult> {
+        // Removal of the whole indexes cannot be merged into a fork.
+        let removes_prefixes = self
+            .changes
+            .iter()
+            .any(|(_, changes)| !changes.prefixes_to_remove().is_empty());
+        if removes_prefixes || self.reads.is_affected_by(fork.flushed_patch()) {
+            return None;
+        }
+        fork.merge(self.changes);
+        Some(self.result)
+    }
+}
+
+/// Pool of threads executing transactions. The pool is created along with the configuration
+/// and is shared by the clones of the blockchain, so the threads are not spawned anew
+/// for every block.
+#[derive(Clone)]
+pub(crate) struct ParallelExecutor {
+    pool: Arc<ThreadPool>,
+    threads: usize,
+}
+
+impl fmt::Debug for ParallelExecutor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ParallelExecutor")
+            .field("threads", &self.threads)
+            .finish()
+    }
+}
+
+impl ParallelExecutor {
+    /// Creates an executor for the given configuration, or `None` if the configuration
+    /// disables the parallel execution.
+    pub(crate) fn new(config: &ParallelExecutionConfig) -> Option<Self> {
+        if !config.enabled || config.threads <= 1 {
+            return None;
+        }
+        let pool = ThreadPoolBuilder::new().pool_size(config.threads).build();
+        Some(Self {
+            pool: Arc::new(pool),
+            threads: config.threads,
+        })
+    }
+
+    /// Executes transactions against the current state of the blockchain on several threads.
+    /// Returns the results in the order of transactions; the result is `None` if
+    /// the transaction cannot be executed optimistically, e.g., due to a storage error.
+    pub(crate) fn execute_transactions(
+        &self,
+        blockchain: &Blockchain,
+        tx_hashes: &[Hash],
+        height: Height,
+    ) -> Vec<Option<OptimisticExecution>> {
+        let threads = self.threads.min(tx_hashes.len());
+        let tx_hashes: Arc<[Hash]> = tx_hashes.into();
+        let workers: Vec<_> = (0..threads)
+            .map(|first| {
+                let (sender, receiver) = oneshot::channel();
+                let blockchain = blockchain.clone();
+                let tx_hashes = Arc::clone(&tx_hashes);
+                self.pool.spawn(future::lazy(move || {
+                    let results = tx_hashes
+                        .iter()
+                        .enumerate()
+                        .skip(first)
+                        .step_by(threads)
+                        .map(|(index, &tx_hash)| {
+                            (index, execute_transaction(&blockchain, tx_hash, height))
+                        })
+                        .collect::<Vec<_>>();
+                    // The blockchain owns the pool, so its clone must not outlive the results:
+                    // otherwise, the pool could be dropped on its own thread.
+                    drop(blockchain);
+                    let _ = sender.send(results);
+                    Ok(())
+                }));
+                receiver
+            })
+            .collect();
+
+        let mut executions: Vec<_> = tx_hashes.iter().map(|_| None).collect();
+        for worker in workers {
+            // If the worker has failed, its transactions are executed sequentially.
+            if let Ok(results) = worker.wait() {
+                for (index, execution) in results {
+                    executions[index] = execution;
+                }
+            }
+        }
+        executions
+    }
+}
+
+fn execute_transaction(
+    blockchain: &Blockchain,
+    tx_hash: Hash,
+    height: Height,
+) -> Option<OptimisticExecution> {
+    let snapshot = blockchain.snapshot();
+    let (tx, raw, service_name) = blockchain
+        .load_transaction(snapshot.as_ref(), tx_hash)
+        .ok()?;
+
+    let mut fork = blockchain.fork();
+    fork.start_read_tracking();
+    // Only storage errors escape the execution; such transactions are executed once again
+    // in order to handle the error in the same way as with the sequential execution.
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        Blockchain::execute_in_fork(tx_hash, tx.as_ref(), &raw, service_name, height, &mut fork)
+    }))
+    .ok()?;
+    let reads = fork.finish_read_tracking();
+
+    Some(OptimisticExecution {
+        result,
+        changes: fork.into_patch(),
+        reads,
+    })
+}
@@ -0,0 +1,210 @@
+// Copyright 2019 The Exonum Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Auxiliary indexes of the pool of unconfirmed transactions.
+
+use byteorder::{BigEndian, ByteOrder};
+use exonum_merkledb::BinaryKey;
+
+use crate::crypto::{Hash, PublicKey, HASH_SIZE, PUBLIC_KEY_LENGTH};
+use crate::helpers::Height;
+
+/// Key of a transaction in the priority queue of the pool.
+///
+/// Keys are ordered by the decreasing priority of transactions; transactions with
+/// the same priority are ordered by their hashes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PoolQueueKey {
+    priority: u64,
+    tx_hash: Hash,
+}
+
+impl PoolQueueKey {
+    pub(crate) fn new(priority: u64, tx_hash: Hash) -> Self {
+        Self { priority, tx_hash }
+    }
+
+    pub(crate) fn priority(&self) -> u64 {
+        self.priority
+    }
+
+    pub(crate) fn tx_hash(&self) -> Hash {
+        self.tx_hash
+    }
+}
+
+// The priority is inverted, so that transactions with higher priorities go first.
+impl BinaryKey for PoolQueueKey {
+    fn size(&self) -> usize {
+        8 + HASH_SIZE
+    }
+
+    fn write(&self, buffer: &mut [u8]) -> usize {
+        BigEndian::write_u64(&mut buffer[..8], !self.priority);
+        self.tx_hash.write(&mut buffer[8..]);
+        self.size()
+    }
+
+    fn read(buffer: &[u8]) -> Self {
+        Self {
+            priority: !BigEndian::read_u64(&buffer[..8]),
+            tx_hash: Hash::read(&buffer[8..]),
+        }
+    }
+}
+
+/// Key of a transaction in the index of the pool transactions grouped by authors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PoolAuthorKey {
+    author: PublicKey,
+    tx_hash: Hash,
+}
+
+impl PoolAuthorKey {
+    pub(crate) fn new(author: PublicKey, tx_hash: Hash) -> Self {
+        Self { author, tx_hash }
+    }
+
+    /// Returns the smallest key among the keys of the specified author.
+    pub(crate) fn first(author: PublicKey) -> Self {
+        Self::new(author, Hash::zero())
+    }
+
+    pub(crate) fn author(&self) -> PublicKey {
+        self.author
+    }
+}
+
+impl BinaryKey for PoolAuthorKey {
+    fn size(&self) -> usize {
+        PUBLIC_KEY_LENGTH + HASH_SIZE
+    }
+
+    fn write(&self, buffer: &mut [u8]) -> usize {
+        self.author.write(&mut buffer[..PUBLIC_KEY_LENGTH]);
+        self.tx_hash.write(&mut buffer[PUBLIC_KEY_LENGTH..]);
+        self.size()
+    }
+
+    fn read(buffer: &[u8]) -> Self {
+        Self {
+            author: PublicKey::read(&buffer[..PUBLIC_KEY_LENGTH]),
+            tx_hash: Hash::read(&buffer[PUBLIC_KEY_LENGTH..]),
+        }
+    }
+}
+
+/// Key of a transaction with validity bounds in the index of the pool transactions
+/// ordered by their maximum heights.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PoolExpirationKey {
+    max_height: Height,
+    tx_hash: Hash,
+}
+
+impl PoolExpirationKey {
+    pub(crate) fn new(max_height: Height, tx_hash: Hash) -> Self {
+        Self {
+            max_height,
+            tx_hash,
+        }
+    }
+
+    pub(crate) fn max_height(&self) -> Height {
+        self.max_height
+    }
+
+    pub(crate) fn tx_hash(&self) -> Hash {
+        self.tx_hash
+    }
+}
+
+impl BinaryKey for PoolExpirationKey {
+    fn size(&self) -> usize {
+        8 + HASH_SIZE
+    }
+
+    fn write(&self, buffer: &mut [u8]) -> usize {
+        BigEndian::write_u64(&mut buffer[..8], self.max_height.0);
+        self.tx_hash.write(&mut buffer[8..]);
+        self.size()
+    }
+
+    fn read(buffer: &[u8]) -> Self {
+        Self {
+            max_height: Height(BigEndian::read_u64(&buffer[..8])),
+            tx_hash: Hash::read(&buffer[8..]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use exonum_merkledb::BinaryKey;
+
+    use super::{PoolAuthorKey, PoolExpirationKey, PoolQueueKey};
+    use crate::crypto::{gen_keypair, hash};
+    use crate::helpers::Height;
+
+    fn to_bytes<K: BinaryKey>(key: &K) -> Vec<u8> {
+        let mut buffer = vec![0; key.size()];
+        key.write(&mut buffer);
+        buffer
+    }
+
+    #[test]
+    fn pool_queue_key_order() {
+        let (low, high) = (hash(&[1]), hash(&[2]));
+        let keys = vec![
+            PoolQueueKey::new(u64::max_value(), high),
+            PoolQueueKey::new(10, low.min(high)),
+            PoolQueueKey::new(10, low.max(high)),
+            PoolQueueKey::new(0, low),
+        ];
+        let serialized: Vec<_> = keys.iter().map(to_bytes).collect();
+        let mut sorted = serialized.clone();
+        sorted.sort();
+        assert_eq!(serialized, sorted);
+
+        for (key, bytes) in keys.iter().zip(&serialized) {
+            assert_eq!(PoolQueueKey::read(bytes), *key);
+        }
+    }
+
+    #[test]
+    fn pool_author_key_roundtrip() {
+        let (author, _) = gen_keypair();
+        let key = PoolAuthorKey::new(author, hash(&[1]));
+        let bytes = to_bytes(&key);
+        assert_eq!(PoolAuthorKey::read(&bytes), key);
+        assert!(to_bytes(&PoolAuthorKey::first(author)) <= bytes);
+    }
+
+    #[test]
+    fn pool_expiration_key_order() {
+        let keys = vec![
+            PoolExpirationKey::new(Height(1), hash(&[2])),
+            PoolExpirationKey::new(Height(2), hash(&[1])),
+            PoolExpirationKey::new(Height(256), hash(&[0])),
+        ];
+        let serialized: Vec<_> = keys.iter().map(to_bytes).collect();
+        let mut sorted = serialized.clone();
+        sorted.sort();
+        assert_eq!(serialized, sorted);
+
+        for (key, bytes) in keys.iter().zip(&serialized) {
+            assert_eq!(PoolExpirationKey::read(bytes), *key);
+        }
+    }
+}
@@ -17,7 +17,12 @@ use exonum_merkledb::{
     ProofMapIndex,
 };
 
-use super::{config::StoredConfiguration, Block, BlockProof, Blockchain, TransactionResult};
+use super::{
+    config::StoredConfiguration,
+    history::{StateChangeKey, StateChangeValue},
+    pool::{PoolAuthorKey, PoolExpirationKey, PoolQueueKey},
+    Block, BlockProof, Blockchain, TransactionResult,
+};
 use crate::{
     crypto::{CryptoHash, Hash, PublicKey},
     helpers::{Height, Round},
@@ -42,6 +47,11 @@ define_names!(
     TRANSACTIONS_LEN => "transactions_len";
     TRANSACTIONS_POOL => "transactions_pool";
     TRANSACTIONS_POOL_LEN => "transactions_pool_len";
+    TRANSACTIONS_POOL_PRIORITIES => "transactions_pool_priorities";
+    TRANSACTIONS_POOL_QUEUE => "transactions_pool_queue";
+    TRANSACTIONS_POOL_AUTHORS => "transactions_pool_authors";
+    TRANSACTIONS_POOL_EXPIRATIONS => "transactions_pool_expirations";
+    EXPIRED_TRANSACTIONS => "expired_transactions";
     TRANSACTIONS_LOCATIONS => "transactions_locations";
     BLOCKS => "blocks";
     BLOCK_HASHES_BY_HEIGHT => "block_hashes_by_height";
@@ -53,6 +63,7 @@ define_names!(
     PEERS_CACHE => "peers_cache";
     CONSENSUS_MESSAGES_CACHE => "consensus_messages_cache";
     CONSENSUS_ROUND => "consensus_round";
+    STATE_HISTORY => "state_history";
 );
 
 /// Configuration index.
@@ -178,6 +189,58 @@ where
         pool.get().unwrap_or(0)
     }
 
+    /// Returns a table that keeps the priority of every transaction in the pool.
+    ///
+    /// See [`Transaction::priority`](trait.Transaction.html#method.priority) for details.
+    pub fn transactions_pool_priorities(&self) -> MapIndex<T, Hash, u64> {
+        MapIndex::new(TRANSACTIONS_POOL_PRIORITIES, self.access.clone())
+    }
+
+    /// Returns a set of the pool transactions ordered by their priorities.
+    pub(crate) fn transactions_pool_queue(&self) -> KeySetIndex<T, PoolQueueKey> {
+        KeySetIndex::new(TRANSACTIONS_POOL_QUEUE, self.access.clone())
+    }
+
+    /// Returns a set of the pool transactions grouped by their authors.
+    pub(crate) fn transactions_pool_authors(&self) -> KeySetIndex<T, PoolAuthorKey> {
+        KeySetIndex::new(TRANSACTIONS_POOL_AUTHORS, self.access.clone())
+    }
+
+    /// Returns a set of the pool transactions with validity bounds ordered by their
+    /// maximum heights.
+    pub(crate) fn transactions_pool_expirations(&self) -> KeySetIndex<T, PoolExpirationKey> {
+        KeySetIndex::new(TRANSACTIONS_POOL_EXPIRATIONS, self.access.clone())
+    }
+
+    /// Returns a set of hashes of transactions which have been removed from the pool
+    /// since their validity bounds had expired. Such transactions are kept
+    /// in the `transactions` table, but will never be committed.
+    ///
+    /// See [`ValidityBounds`](../messages/struct.ValidityBounds.html) for details.
+    pub fn expired_transactions(&self) -> KeySetIndex<T, Hash> {
+        KeySetIndex::new(EXPIRED_TRANSACTIONS, self.access.clone())
+    }
+
+    /// Returns hashes of at most `limit` transactions from the pool with the highest
+    /// priorities. Transactions with the same priority are ordered by their hashes.
+    pub fn prioritized_transactions_pool(&self, limit: usize) -> Vec<Hash> {
+        self.transactions_pool_queue()
+            .iter()
+            .take(limit)
+            .map(|key| key.tx_hash())
+            .collect()
+    }
+
+    /// Returns the number of transactions of the specified author in the pool,
+    /// counting no more than `limit` transactions.
+    pub(crate) fn author_transactions_in_pool(&self, author: &PublicKey, limit: u64) -> u64 {
+        self.transactions_pool_authors()
+            .iter_from(&PoolAuthorKey::first(*author))
+            .take_while(|key| key.author() == *author)
+            .take(limit as usize)
+            .count() as u64
+    }
+
     /// Returns a table that keeps the block height and transaction position inside the block for every
     /// transaction hash.
     pub fn transactions_locations(&self) -> MapIndex<T, Hash, TxLocation> {
@@ -257,6 +320,14 @@ where
             .unwrap_or_else(Round::first)
     }
 
+    /// Returns changes reverting the blockchain state to the preceding heights. Changes are
+    /// ordered by the height of the block they revert.
+    ///
+    /// The index is filled only if the state history is enabled in the node configuration.
+    pub(crate) fn state_history(&self) -> MapIndex<T, StateChangeKey, StateChangeValue> {
+        MapIndex::new(STATE_HISTORY, self.access.clone())
+    }
+
     /// Returns the block hash for the given height.
     pub fn block_hash_by_height(&self, height: Height) -> Option<Hash> {
         self.block_hashes_by_height().get(height.into())
@@ -456,15 +527,115 @@ where
     /// be sure to decrement it when transaction committed.
     #[doc(hidden)]
     pub fn add_transaction_into_pool(&mut self, tx: Signed<RawTransaction>) {
-        self.transactions_pool().insert(tx.hash());
+        self.add_transaction_into_pool_with_priority(tx, 0);
+    }
+
+    /// Adds transaction with the specified priority into the persistent pool.
+    pub(crate) fn add_transaction_into_pool_with_priority(
+        &mut self,
+        tx: Signed<RawTransaction>,
+        priority: u64,
+    ) {
+        let tx_hash = tx.hash();
+        self.transactions_pool().insert(tx_hash);
         let x = self.transactions_pool_len_index().get().unwrap_or(0);
         self.transactions_pool_len_index().set(x + 1);
-        self.transactions().put(&tx.hash(), tx);
+        self.transactions_pool_priorities().put(&tx_hash, priority);
+        self.transactions_pool_queue()
+            .insert(PoolQueueKey::new(priority, tx_hash));
+        self.transactions_pool_authors()
+            .insert(PoolAuthorKey::new(tx.author(), tx_hash));
+        if let Some(validity) = tx.payload().validity() {
+            self.transactions_pool_expirations()
+                .insert(PoolExpirationKey::new(validity.max_height(), tx_hash));
+        }
+        self.transactions().put(&tx_hash, tx);
     }
 
     /// Changes the transaction status from `in_pool`, to `committed`.
     pub(crate) fn commit_transaction(&mut self, hash: &Hash) {
         self.transactions_pool().remove(hash);
+        self.remove_pool_entries(hash);
+    }
+
+    /// Removes transaction from the persistent pool and decrements `transactions_pool_len_index`.
+    /// The transaction itself is kept in the `transactions` table.
+    ///
+    /// Returns `false` if the transaction is not in the pool.
+    pub(crate) fn remove_transaction_from_pool(&mut self, hash: &Hash) -> bool {
+        if !self.transactions_pool().contains(hash) {
+            return false;
+        }
+        self.transactions_pool().remove(hash);
+        self.remove_pool_entries(hash);
+        let x = self.transactions_pool_len_index().get().unwrap();
+        self.transactions_pool_len_index().set(x - 1);
+        true
+    }
+
+    // Removes the transaction from the auxiliary indexes of the pool.
+    fn remove_pool_entries(&mut self, hash: &Hash) {
+        // Transactions added to the pool before the priorities were introduced
+        // have the default priority.
+        let priority = self.transactions_pool_priorities().get(hash).unwrap_or(0);
+        self.transactions_pool_priorities().remove(hash);
+        self.transactions_pool_queue()
+            .remove(&PoolQueueKey::new(priority, *hash));
+        if let Some(tx) = self.transactions().get(hash) {
+            self.transactions_pool_authors()
+                .remove(&PoolAuthorKey::new(tx.author(), *hash));
+            if let Some(validity) = tx.payload().validity() {
+                self.transactions_pool_expirations()
+                    .remove(&PoolExpirationKey::new(validity.max_height(), *hash));
+            }
+        }
+    }
+
+    /// Adds the pool transactions missing from the auxiliary indexes of the pool, i.e.,
+    /// the transactions added to the pool before the priorities were introduced,
+    /// to these indexes with the default priority.
+    ///
+    /// Returns the number of added transactions.
+    pub(crate) fn restore_pool_entries(&mut self) -> usize {
+        let missing: Vec<_> = {
+            let priorities = self.transactions_pool_priorities();
+            self.transactions_pool()
+                .iter()
+                .filter(|tx_hash| !priorities.contains(tx_hash))
+                .collect()
+        };
+        for tx_hash in &missing {
+            self.transactions_pool_priorities().put(tx_hash, 0);
+            self.transactions_pool_queue()
+                .insert(PoolQueueKey::new(0, *tx_hash));
+            if let Some(tx) = self.transactions().get(tx_hash) {
+                self.transactions_pool_authors()
+                    .insert(PoolAuthorKey::new(tx.author(), *tx_hash));
+                if let Some(validity) = tx.payload().validity() {
+                    self.transactions_pool_expirations()
+                        .insert(PoolExpirationKey::new(validity.max_height(), *tx_hash));
+                }
+            }
+        }
+        missing.len()
+    }
+
+    /// Removes transactions which cannot be committed after the block at the specified
+    /// height from the pool and marks them as expired.
+    ///
+    /// Returns the number of removed transactions.
+    pub(crate) fn remove_expired_transactions(&mut self, height: Height) -> usize {
+        let expired: Vec<_> = self
+            .transactions_pool_expirations()
+            .iter()
+            .take_while(|key| key.max_height() <= height)
+            .map(|key| key.tx_hash())
+            .collect();
+        for tx_hash in &expired {
+            self.remove_transaction_from_pool(tx_hash);
+            self.expired_transactions().insert(*tx_hash);
+        }
+        expired.len()
     }
 
     /// Updates transaction count of the blockchain.
@@ -477,13 +648,10 @@ where
     /// Removes transaction from the persistent pool.
     #[cfg(test)]
     pub(crate) fn reject_transaction(&mut self, hash: &Hash) -> Result<(), ()> {
-        let contains = self.transactions_pool().contains(hash);
-        self.transactions_pool().remove(hash);
+        let contains = self.remove_transaction_from_pool(hash);
         self.transactions().remove(hash);
 
         if contains {
-            let x = self.transactions_pool_len_index().get().unwrap();
-            self.transactions_pool_len_index().set(x - 1);
             Ok(())
         } else {
             Err(())
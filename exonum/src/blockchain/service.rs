@@ -15,7 +15,7 @@
 //! This module defines the Exonum services interfaces. Like smart contracts in some other
 //! blockchain platforms, Exonum services encapsulate business logic of the blockchain application.
 
-use exonum_merkledb::{Fork, Snapshot};
+use exonum_merkledb::{Fork, Migration, Snapshot};
 
 use actix::Addr;
 use serde_json::Value;
@@ -207,6 +207,38 @@ pub trait Service: Send + Sync + 'static {
     /// *Try not to perform long operations in this handler*.
     fn after_commit(&self, context: &ServiceContext) {}
 
+    /// Returns migrations of the service schema in the increasing order of their versions.
+    /// The version of the schema is stored in the database with the service name
+    /// as the namespace.
+    ///
+    /// Pending migrations are applied at the node startup or at the height returned
+    /// by [`migration_height`](#method.migration_height). When the blockchain is created,
+    /// all migrations are considered applied, since [`initialize`](#method.initialize)
+    /// creates the latest layout of the service indexes.
+    ///
+    /// *Default implementation returns an empty vector*
+    fn migrations(&self) -> Vec<Migration> {
+        Vec::new()
+    }
+
+    /// Returns the height of the block, before transactions of which pending migrations
+    /// of the service schema are applied. Migrations applied at a certain height change
+    /// the blockchain state the same way on all nodes, so they can change the data
+    /// which the service state hash depends on.
+    ///
+    /// If the height is not specified, migrations are applied locally at the node startup.
+    /// Since nodes are restarted at different times, such migrations must not change
+    /// the data covered by [`state_hash`](#tymethod.state_hash); otherwise, the node
+    /// would compute a state hash different from other nodes and fork from the network.
+    ///
+    /// If a migration bound to a height fails, the node panics, since it cannot create
+    /// the block at this height.
+    ///
+    /// *Default implementation returns `None`*
+    fn migration_height(&self) -> Option<Height> {
+        None
+    }
+
     /// Extends API by handlers of this service. The request handlers are mounted on
     /// the `/api/services/{service_name}` path at the listen address of every
     /// full node in the blockchain network.
@@ -228,6 +260,7 @@ pub struct ServiceContext {
     stored_configuration: StoredConfiguration,
     height: Height,
     service_id: u16,
+    network_id: Option<Hash>,
 }
 
 impl ServiceContext {
@@ -263,9 +296,20 @@ impl ServiceContext {
             stored_configuration,
             service_id,
             height,
+            network_id: None,
         }
     }
 
+    /// Binds transactions signed by the context to the specified network identifier.
+    ///
+    /// See [`Blockchain::set_network_id`] for details.
+    ///
+    /// [`Blockchain::set_network_id`]: struct.Blockchain.html#method.set_network_id
+    pub fn with_network_id(mut self, network_id: Option<Hash>) -> Self {
+        self.network_id = network_id;
+        self
+    }
+
     /// If the current node is a validator, returns its identifier.
     /// For other nodes return `None`.
     pub fn validator_id(&self) -> Option<ValidatorId> {
@@ -298,6 +342,11 @@ impl ServiceContext {
         &self.service_keypair.1
     }
 
+    /// Returns the identifier of the network which transactions should be bound to, if any.
+    pub fn network_id(&self) -> Option<Hash> {
+        self.network_id
+    }
+
     /// Returns the actual consensus configuration.
     pub fn actual_consensus_config(&self) -> &ConsensusConfig {
         &self.stored_configuration.consensus
@@ -313,9 +362,10 @@ impl ServiceContext {
     where
         T: Into<ServiceTransaction> + Transaction,
     {
-        let msg = Message::sign_transaction(
+        let msg = Message::sign_transaction_for_network(
             tx,
             self.service_id,
+            self.network_id,
             self.service_keypair.0,
             &self.service_keypair.1,
         );
@@ -16,17 +16,22 @@
 
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
 
-use std::borrow::Cow;
+use std::{borrow::Cow, iter};
 
 use crate::blockchain::{
-    Blockchain, ExecutionError, ExecutionResult, Schema, Service, Transaction, TransactionContext,
-    TransactionSet,
+    Blockchain, ExecutionError, ExecutionResult, GenesisConfig, ParallelExecutionConfig, Schema,
+    Service, StateHistoryConfig, Transaction, TransactionContext, TransactionErrorType,
+    TransactionSet, ValidatorKeys,
 };
 use crate::crypto::{gen_keypair, Hash};
+use crate::explorer::BlockchainExplorer;
 use crate::helpers::{Height, ValidatorId};
-use crate::messages::{Message, RawTransaction};
+use crate::messages::{Message, RawTransaction, Signed, ValidityBounds};
 use crate::proto;
-use exonum_merkledb::{Database, Error as StorageError, Fork, ListIndex, Snapshot};
+use exonum_merkledb::{
+    schema_version, Database, Error as StorageError, Fork, ListIndex, MapIndex, Migration,
+    ObjectHash, ProofMapIndex, Snapshot,
+};
 
 const IDX_NAME: &str = "idx_name";
 const TEST_SERVICE_ID: u16 = 255;
@@ -81,6 +86,123 @@ impl Transaction for Tx {
     }
 }
 
+const MAP_IDX_NAME: &str = "map_idx_name";
+const MAP_SERVICE_ID: u16 = 254;
+
+struct MapService;
+
+impl Service for MapService {
+    fn service_id(&self) -> u16 {
+        MAP_SERVICE_ID
+    }
+
+    fn service_name(&self) -> &'static str {
+        "map service"
+    }
+
+    fn state_hash(&self, _: &dyn Snapshot) -> Vec<Hash> {
+        vec![]
+    }
+
+    fn tx_from_raw(&self, raw: RawTransaction) -> Result<Box<dyn Transaction>, failure::Error> {
+        Ok(MapServiceTxs::tx_from_raw(raw)?.into())
+    }
+}
+
+#[derive(Serialize, Deserialize, ProtobufConvert, Debug, Clone)]
+#[exonum(pb = "proto::schema::tests::TestServiceTx", crate = "crate")]
+struct MapTx {
+    value: u64,
+}
+
+impl MapTx {
+    fn new(value: u64) -> Self {
+        Self { value }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, TransactionSet, Debug)]
+#[exonum(crate = "crate")]
+enum MapServiceTxs {
+    MapTx(MapTx),
+}
+
+impl Transaction for MapTx {
+    // The result depends on the order of transactions updating the same key.
+    fn execute(&self, tc: TransactionContext) -> ExecutionResult {
+        let mut index = MapIndex::new(MAP_IDX_NAME, tc.fork());
+        let key = self.value / 10;
+        let previous: u64 = index.get(&key).unwrap_or_default();
+        index.put(&key, previous * 2 + self.value);
+        match self.value % 10 {
+            7 => Err(ExecutionError::new(7)),
+            9 => panic!("9"),
+            _ => Ok(()),
+        }
+    }
+}
+
+const WALLETS_IDX_NAME: &str = "wallets_idx_name";
+const TRANSFER_SERVICE_ID: u16 = 253;
+const WALLETS_COUNT: u64 = 10;
+
+struct TransferService;
+
+impl Service for TransferService {
+    fn service_id(&self) -> u16 {
+        TRANSFER_SERVICE_ID
+    }
+
+    fn service_name(&self) -> &'static str {
+        "transfer service"
+    }
+
+    fn state_hash(&self, snapshot: &dyn Snapshot) -> Vec<Hash> {
+        let index: ProofMapIndex<_, u64, u64> = ProofMapIndex::new(WALLETS_IDX_NAME, snapshot);
+        vec![index.object_hash()]
+    }
+
+    fn tx_from_raw(&self, raw: RawTransaction) -> Result<Box<dyn Transaction>, failure::Error> {
+        Ok(TransferServiceTxs::tx_from_raw(raw)?.into())
+    }
+}
+
+#[derive(Serialize, Deserialize, ProtobufConvert, Debug, Clone)]
+#[exonum(pb = "proto::schema::tests::TestServiceTx", crate = "crate")]
+struct TransferTx {
+    value: u64,
+}
+
+impl TransferTx {
+    fn new(value: u64) -> Self {
+        Self { value }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, TransactionSet, Debug)]
+#[exonum(crate = "crate")]
+enum TransferServiceTxs {
+    TransferTx(TransferTx),
+}
+
+impl Transaction for TransferTx {
+    // Wallets with the missing balance are credited with 100 tokens.
+    fn execute(&self, tc: TransactionContext) -> ExecutionResult {
+        let mut index = ProofMapIndex::new(WALLETS_IDX_NAME, tc.fork());
+        let from = self.value % WALLETS_COUNT;
+        let to = (self.value / WALLETS_COUNT) % WALLETS_COUNT;
+        let amount = self.value / (WALLETS_COUNT * WALLETS_COUNT);
+        let from_balance: u64 = index.get(&from).unwrap_or(100);
+        if from == to || from_balance < amount {
+            return Err(ExecutionError::new(0));
+        }
+        let to_balance: u64 = index.get(&to).unwrap_or(100);
+        index.put(&from, from_balance - amount);
+        index.put(&to, to_balance + amount);
+        Ok(())
+    }
+}
+
 fn gen_tempdir_name() -> String {
     thread_rng().sample_iter(&Alphanumeric).take(10).collect()
 }
@@ -346,6 +468,74 @@ impl Service for ServicePanicStorageError {
     }
 }
 
+struct ServiceWithMigrations {
+    versions: u32,
+    height: Option<Height>,
+}
+
+impl Service for ServiceWithMigrations {
+    fn service_id(&self) -> u16 {
+        TEST_SERVICE_ID
+    }
+
+    fn service_name(&self) -> &'static str {
+        "service with migrations"
+    }
+
+    fn state_hash(&self, _snapshot: &dyn Snapshot) -> Vec<Hash> {
+        vec![]
+    }
+
+    fn tx_from_raw(&self, _raw: RawTransaction) -> Result<Box<dyn Transaction>, failure::Error> {
+        unimplemented!()
+    }
+
+    fn migrations(&self) -> Vec<Migration> {
+        (1..=self.versions)
+            .map(|version| {
+                Migration::new(version, format!("Push {}", version), move |context| {
+                    ListIndex::new(IDX_NAME, context.fork()).push(version);
+                    Ok(())
+                })
+            })
+            .collect()
+    }
+
+    fn migration_height(&self) -> Option<Height> {
+        self.height
+    }
+}
+
+struct ServiceWithFailedMigration;
+
+impl Service for ServiceWithFailedMigration {
+    fn service_id(&self) -> u16 {
+        TEST_SERVICE_ID
+    }
+
+    fn service_name(&self) -> &'static str {
+        "service with failed migration"
+    }
+
+    fn state_hash(&self, _snapshot: &dyn Snapshot) -> Vec<Hash> {
+        vec![]
+    }
+
+    fn tx_from_raw(&self, _raw: RawTransaction) -> Result<Box<dyn Transaction>, failure::Error> {
+        unimplemented!()
+    }
+
+    fn migrations(&self) -> Vec<Migration> {
+        vec![Migration::new(1, "Fail", |_| {
+            Err(format_err!("Migration error"))
+        })]
+    }
+
+    fn migration_height(&self) -> Option<Height> {
+        Some(Height(1))
+    }
+}
+
 fn assert_service_execute(blockchain: &Blockchain, db: &mut dyn Database) {
     let (_, patch) = blockchain.create_patch(ValidatorId::zero(), Height(1), &[]);
     db.merge(patch).unwrap();
@@ -363,15 +553,399 @@ fn assert_service_execute_panic(blockchain: &Blockchain, db: &mut dyn Database)
     assert!(index.is_empty());
 }
 
+fn assert_state_history(blockchain: &mut Blockchain) {
+    blockchain.set_state_history_config(StateHistoryConfig {
+        enabled: true,
+        depth: Some(2),
+    });
+    let validator_keys = ValidatorKeys {
+        consensus_key: gen_keypair().0,
+        service_key: gen_keypair().0,
+    };
+    blockchain
+        .initialize(GenesisConfig::new(iter::once(validator_keys)))
+        .unwrap();
+
+    for height in 1..=4 {
+        let (block_hash, patch) = blockchain.create_patch(ValidatorId::zero(), Height(height), &[]);
+        blockchain
+            .commit(&patch, block_hash, iter::empty())
+            .unwrap();
+    }
+
+    for height in 2..=4 {
+        let snapshot = blockchain.snapshot_at(Height(height)).unwrap();
+        assert_eq!(Schema::new(&snapshot).height(), Height(height));
+        let index: ListIndex<_, u32> = ListIndex::new(IDX_NAME, &snapshot);
+        assert_eq!(index.len(), height);
+    }
+    // The history for older heights has been pruned.
+    assert!(blockchain.snapshot_at(Height(1)).is_none());
+    assert!(blockchain.snapshot_at(Height(5)).is_none());
+}
+
+fn assert_prioritized_pool(blockchain: &mut Blockchain) {
+    let (pk, sec_key) = gen_keypair();
+    let txs: Vec<_> = (1..=4)
+        .map(|value| Message::sign_transaction(Tx::new(value), TEST_SERVICE_ID, pk, &sec_key))
+        .collect();
+    let priorities = [1, 5, 5, 0];
+
+    let fork = blockchain.fork();
+    {
+        let mut schema = Schema::new(&fork);
+        for (tx, &priority) in txs.iter().zip(&priorities) {
+            schema.add_transaction_into_pool_with_priority(tx.clone(), priority);
+        }
+    }
+    blockchain.merge(fork.into_patch()).unwrap();
+
+    // Transactions with the same priority are ordered by their hashes.
+    let (first, second) = if txs[1].hash() < txs[2].hash() {
+        (txs[1].hash(), txs[2].hash())
+    } else {
+        (txs[2].hash(), txs[1].hash())
+    };
+    let snapshot = blockchain.snapshot();
+    let schema = Schema::new(&snapshot);
+    assert_eq!(
+        schema.prioritized_transactions_pool(10),
+        vec![first, second, txs[0].hash(), txs[3].hash()]
+    );
+    assert_eq!(schema.prioritized_transactions_pool(2), vec![first, second]);
+    assert_eq!(schema.author_transactions_in_pool(&pk, 10), 4);
+    assert_eq!(schema.author_transactions_in_pool(&pk, 2), 2);
+    assert_eq!(schema.author_transactions_in_pool(&gen_keypair().0, 10), 0);
+
+    let fork = blockchain.fork();
+    {
+        let mut schema = Schema::new(&fork);
+        schema.commit_transaction(&first);
+        assert!(schema.remove_transaction_from_pool(&txs[3].hash()));
+        assert!(!schema.remove_transaction_from_pool(&txs[3].hash()));
+    }
+    blockchain.merge(fork.into_patch()).unwrap();
+
+    let snapshot = blockchain.snapshot();
+    let schema = Schema::new(&snapshot);
+    assert_eq!(
+        schema.prioritized_transactions_pool(10),
+        vec![second, txs[0].hash()]
+    );
+    assert_eq!(schema.author_transactions_in_pool(&pk, 10), 2);
+    // Transactions removed from the pool are kept in the `transactions` table.
+    assert!(schema.transactions().contains(&txs[3].hash()));
+}
+
+fn assert_legacy_pool_restored(blockchain: &mut Blockchain) {
+    let genesis = GenesisConfig::new(iter::once(ValidatorKeys {
+        consensus_key: gen_keypair().0,
+        service_key: gen_keypair().0,
+    }));
+    blockchain.initialize(genesis.clone()).unwrap();
+
+    let (pk, sec_key) = gen_keypair();
+    let txs: Vec<_> = (1..=3)
+        .map(|value| Message::sign_transaction(Tx::new(value), TEST_SERVICE_ID, pk, &sec_key))
+        .collect();
+    let new_tx = Message::sign_transaction(Tx::new(4), TEST_SERVICE_ID, pk, &sec_key);
+
+    // Previous versions of the node kept only the set of transaction hashes in the pool.
+    let fork = blockchain.fork();
+    {
+        let mut schema = Schema::new(&fork);
+        for tx in &txs {
+            schema.transactions().put(&tx.hash(), tx.clone());
+            schema.transactions_pool().insert(tx.hash());
+        }
+        schema.transactions_pool_len_index().set(txs.len() as u64);
+        schema.add_transaction_into_pool_with_priority(new_tx.clone(), 1);
+    }
+    blockchain.merge(fork.into_patch()).unwrap();
+    let snapshot = blockchain.snapshot();
+    let schema = Schema::new(&snapshot);
+    assert_eq!(
+        schema.prioritized_transactions_pool(10),
+        vec![new_tx.hash()]
+    );
+
+    // The pool indexes are restored on the node startup.
+    blockchain.initialize(genesis).unwrap();
+    let mut legacy_hashes: Vec<_> = txs.iter().map(|tx| tx.hash()).collect();
+    legacy_hashes.sort();
+    let mut expected = vec![new_tx.hash()];
+    expected.extend_from_slice(&legacy_hashes);
+
+    let snapshot = blockchain.snapshot();
+    let schema = Schema::new(&snapshot);
+    assert_eq!(schema.prioritized_transactions_pool(10), expected);
+    assert_eq!(schema.author_transactions_in_pool(&pk, 10), 4);
+    assert_eq!(
+        schema.transactions_pool_priorities().get(&txs[0].hash()),
+        Some(0)
+    );
+
+    // Restored transactions can be removed from the pool.
+    let fork = blockchain.fork();
+    {
+        let mut schema = Schema::new(&fork);
+        assert!(schema.remove_transaction_from_pool(&legacy_hashes[0]));
+        assert_eq!(schema.prioritized_transactions_pool(10).len(), 3);
+        assert_eq!(schema.author_transactions_in_pool(&pk, 10), 3);
+        assert_eq!(schema.transactions_pool_len(), 3);
+    }
+}
+
+fn assert_validity_bounds(blockchain: &mut Blockchain) {
+    let validator_keys = ValidatorKeys {
+        consensus_key: gen_keypair().0,
+        service_key: gen_keypair().0,
+    };
+    blockchain
+        .initialize(GenesisConfig::new(iter::once(validator_keys)))
+        .unwrap();
+
+    let (pk, sec_key) = gen_keypair();
+    let sign = |value, validity| {
+        Message::sign_transaction_with_validity(
+            Tx::new(value),
+            TEST_SERVICE_ID,
+            validity,
+            pk,
+            &sec_key,
+        )
+    };
+    let expiring_tx = sign(1, ValidityBounds::until(Height(1)));
+    let valid_tx = sign(2, ValidityBounds::until(Height(2)));
+    let future_tx = sign(3, ValidityBounds::new(Height(5), Height(10)));
+    let unbounded_tx = Message::sign_transaction(Tx::new(4), TEST_SERVICE_ID, pk, &sec_key);
+
+    let fork = blockchain.fork();
+    {
+        let mut schema = Schema::new(&fork);
+        schema.add_transaction_into_pool(expiring_tx.clone());
+        schema.add_transaction_into_pool(valid_tx.clone());
+        schema.add_transaction_into_pool(future_tx.clone());
+        schema.add_transaction_into_pool(unbounded_tx.clone());
+    }
+    blockchain.merge(fork.into_patch()).unwrap();
+
+    // The transaction cannot be committed after the first block, so it is removed from the pool.
+    let (block_hash, patch) = blockchain.create_patch(ValidatorId::zero(), Height(1), &[]);
+    blockchain
+        .commit(&patch, block_hash, iter::empty())
+        .unwrap();
+
+    let snapshot = blockchain.snapshot();
+    let schema = Schema::new(&snapshot);
+    assert!(!schema.transactions_pool().contains(&expiring_tx.hash()));
+    assert!(schema.expired_transactions().contains(&expiring_tx.hash()));
+    assert!(schema.transactions().contains(&expiring_tx.hash()));
+    assert_eq!(schema.transactions_pool_len(), 3);
+    assert_eq!(schema.prioritized_transactions_pool(10).len(), 3);
+    let tx_info = BlockchainExplorer::new(blockchain)
+        .transaction(&expiring_tx.hash())
+        .unwrap();
+    assert!(tx_info.is_expired());
+
+    // A transaction included into a block outside of its bounds is not executed.
+    let tx_hashes = [valid_tx.hash(), future_tx.hash()];
+    let (block_hash, patch) = blockchain.create_patch(ValidatorId::zero(), Height(2), &tx_hashes);
+    blockchain
+        .commit(&patch, block_hash, iter::empty())
+        .unwrap();
+
+    let snapshot = blockchain.snapshot();
+    let schema = Schema::new(&snapshot);
+    assert_eq!(schema.transactions_pool_len(), 1);
+    let results = schema.transaction_results();
+    assert!(results.get(&valid_tx.hash()).unwrap().0.is_ok());
+    let error = results.get(&future_tx.hash()).unwrap().0.unwrap_err();
+    assert_eq!(error.error_type(), TransactionErrorType::InvalidHeight);
+    let index: ListIndex<_, u64> = ListIndex::new(IDX_NAME, &snapshot);
+    assert_eq!(index.iter().collect::<Vec<_>>(), vec![2, 21]);
+}
+
+fn assert_migrations<F>(create_blockchain: F)
+where
+    F: Fn(u32, Option<Height>) -> Blockchain,
+{
+    fn migrated_values(blockchain: &Blockchain) -> Vec<u32> {
+        let snapshot = blockchain.snapshot();
+        let index = ListIndex::new(IDX_NAME, &snapshot);
+        let mut values = Vec::new();
+        values.extend(index.iter());
+        values
+    }
+
+    let genesis = || {
+        let validator_keys = ValidatorKeys {
+            consensus_key: gen_keypair().0,
+            service_key: gen_keypair().0,
+        };
+        GenesisConfig::new(iter::once(validator_keys))
+    };
+
+    // Migrations are considered applied for the newly created service schema.
+    let mut blockchain = create_blockchain(2, None);
+    blockchain.initialize(genesis()).unwrap();
+    assert!(migrated_values(&blockchain).is_empty());
+
+    // Pending migrations are applied on the startup.
+    let mut blockchain = create_blockchain(4, None);
+    let reports = blockchain.migrate(true).unwrap();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(
+        (reports[0].initial_version, reports[0].applied.clone()),
+        (2, vec![3, 4])
+    );
+    assert!(migrated_values(&blockchain).is_empty());
+    blockchain.initialize(genesis()).unwrap();
+    assert_eq!(migrated_values(&blockchain), vec![3, 4]);
+
+    // Migrations bound to a height are applied in the block at this height.
+    let mut blockchain = create_blockchain(5, Some(Height(2)));
+    blockchain.initialize(genesis()).unwrap();
+    for height in 1..=3 {
+        let (block_hash, patch) = blockchain.create_patch(ValidatorId::zero(), Height(height), &[]);
+        blockchain
+            .commit(&patch, block_hash, iter::empty())
+            .unwrap();
+        let expected = if height < 2 {
+            vec![3, 4]
+        } else {
+            vec![3, 4, 5]
+        };
+        assert_eq!(migrated_values(&blockchain), expected);
+    }
+    assert_eq!(
+        schema_version(&blockchain.snapshot(), "service with migrations"),
+        5
+    );
+}
+
+fn assert_failed_migration_at_height(blockchain: &Blockchain) {
+    blockchain.create_patch(ValidatorId::zero(), Height(1), &[]);
+}
+
+/// Commits a block with the given transactions to both blockchains, executing the transactions
+/// in parallel in the first one, and checks that the results are the same.
+fn commit_block_in_parallel(
+    parallel: &mut Blockchain,
+    sequential: &mut Blockchain,
+    txs: &[Signed<RawTransaction>],
+) {
+    parallel.set_parallel_execution_config(ParallelExecutionConfig {
+        enabled: true,
+        threads: 4,
+        min_transactions: 2,
+    });
+    let validator_keys = ValidatorKeys {
+        consensus_key: gen_keypair().0,
+        service_key: gen_keypair().0,
+    };
+    let genesis = GenesisConfig::new(iter::once(validator_keys));
+    parallel.initialize(genesis.clone()).unwrap();
+    sequential.initialize(genesis).unwrap();
+
+    let tx_hashes: Vec<_> = txs.iter().map(|tx| tx.hash()).collect();
+    for blockchain in &mut [&mut *parallel, &mut *sequential] {
+        let fork = blockchain.fork();
+        {
+            let mut schema = Schema::new(&fork);
+            for tx in txs {
+                schema.add_transaction_into_pool(tx.clone());
+            }
+        }
+        blockchain.merge(fork.into_patch()).unwrap();
+    }
+
+    let (block_hash, patch) = parallel.create_patch(ValidatorId::zero(), Height(1), &tx_hashes);
+    parallel.commit(&patch, block_hash, iter::empty()).unwrap();
+    let (expected_hash, patch) =
+        sequential.create_patch(ValidatorId::zero(), Height(1), &tx_hashes);
+    sequential
+        .commit(&patch, expected_hash, iter::empty())
+        .unwrap();
+    assert_eq!(block_hash, expected_hash);
+
+    let (snapshot, expected_snapshot) = (parallel.snapshot(), sequential.snapshot());
+    let (schema, expected_schema) = (Schema::new(&snapshot), Schema::new(&expected_snapshot));
+    for tx_hash in &tx_hashes {
+        assert_eq!(
+            schema.transaction_results().get(tx_hash),
+            expected_schema.transaction_results().get(tx_hash)
+        );
+    }
+}
+
+fn assert_parallel_execution(mut parallel: Blockchain, mut sequential: Blockchain) {
+    let (pk, sec_key) = gen_keypair();
+    let mut txs: Vec<_> = (0..40)
+        .map(|value| Message::sign_transaction(MapTx::new(value), MAP_SERVICE_ID, pk, &sec_key))
+        .collect();
+    // Transactions of `TestService` update the same list, so they always conflict.
+    txs.extend(
+        (1..=3)
+            .map(|value| Message::sign_transaction(Tx::new(value), TEST_SERVICE_ID, pk, &sec_key)),
+    );
+    txs.swap(2, 40);
+    txs.swap(11, 41);
+    commit_block_in_parallel(&mut parallel, &mut sequential, &txs);
+
+    let (snapshot, expected_snapshot) = (parallel.snapshot(), sequential.snapshot());
+    let schema = Schema::new(&snapshot);
+    let results = schema.transaction_results();
+    assert!(results.get(&txs[7].hash()).unwrap().0.is_err());
+    assert!(results.get(&txs[9].hash()).unwrap().0.is_err());
+
+    let index: MapIndex<_, u64, u64> = MapIndex::new(MAP_IDX_NAME, &snapshot);
+    let expected_index: MapIndex<_, u64, u64> = MapIndex::new(MAP_IDX_NAME, &expected_snapshot);
+    assert_eq!(
+        index.iter().collect::<Vec<_>>(),
+        expected_index.iter().collect::<Vec<_>>()
+    );
+    let index: ListIndex<_, u64> = ListIndex::new(IDX_NAME, &snapshot);
+    assert_eq!(index.iter().collect::<Vec<_>>(), vec![1, 42, 2, 21, 3, 14]);
+}
+
+fn assert_parallel_execution_with_proof_map(mut parallel: Blockchain, mut sequential: Blockchain) {
+    let (pk, sec_key) = gen_keypair();
+    // Transfers between wallets stored in a Merkelized index. Every update of the index
+    // reads its root node, so all transfers except for the first one are re-executed.
+    let txs: Vec<_> = (0..50)
+        .map(|i| {
+            let value = i * 7919 % (20 * WALLETS_COUNT * WALLETS_COUNT);
+            Message::sign_transaction(TransferTx::new(value), TRANSFER_SERVICE_ID, pk, &sec_key)
+        })
+        .collect();
+    commit_block_in_parallel(&mut parallel, &mut sequential, &txs);
+
+    let (snapshot, expected_snapshot) = (parallel.snapshot(), sequential.snapshot());
+    let index: ProofMapIndex<_, u64, u64> = ProofMapIndex::new(WALLETS_IDX_NAME, &snapshot);
+    let expected_index: ProofMapIndex<_, u64, u64> =
+        ProofMapIndex::new(WALLETS_IDX_NAME, &expected_snapshot);
+    let balances: Vec<_> = index.iter().collect();
+    assert_eq!(balances, expected_index.iter().collect::<Vec<_>>());
+    assert_eq!(index.object_hash(), expected_index.object_hash());
+    let total: u64 = balances.iter().map(|(_, balance)| balance).sum();
+    assert_eq!(total, 100 * balances.len() as u64);
+}
+
 mod memorydb_tests {
     use futures::sync::mpsc;
 
+    use std::sync::Arc;
+
     use crate::blockchain::{Blockchain, Service};
     use crate::crypto::gen_keypair;
     use crate::node::ApiSender;
     use exonum_merkledb::{Database, TemporaryDB};
 
-    use super::{ServiceGood, ServicePanic, ServicePanicStorageError};
+    use super::{
+        MapService, ServiceGood, ServicePanic, ServicePanicStorageError,
+        ServiceWithFailedMigration, ServiceWithMigrations, TransferService,
+    };
 
     fn create_database() -> Box<dyn Database> {
         Box::new(TemporaryDB::new())
@@ -435,6 +1009,85 @@ mod memorydb_tests {
         let mut db = create_database();
         super::assert_service_execute(&blockchain, db.as_mut());
     }
+
+    #[test]
+    #[should_panic(expected = "service migration at height 1 failed: Migration error")]
+    fn failed_migration_at_height() {
+        let blockchain = create_blockchain_with_service(Box::new(ServiceWithFailedMigration));
+        super::assert_failed_migration_at_height(&blockchain);
+    }
+
+    #[test]
+    fn state_history() {
+        let mut blockchain = create_blockchain_with_service(Box::new(ServiceGood));
+        super::assert_state_history(&mut blockchain);
+    }
+
+    #[test]
+    fn prioritized_pool() {
+        let mut blockchain = create_blockchain();
+        super::assert_prioritized_pool(&mut blockchain);
+    }
+
+    #[test]
+    fn legacy_pool_restored() {
+        let mut blockchain = create_blockchain();
+        super::assert_legacy_pool_restored(&mut blockchain);
+    }
+
+    #[test]
+    fn validity_bounds() {
+        let mut blockchain = create_blockchain();
+        super::assert_validity_bounds(&mut blockchain);
+    }
+
+    fn create_blockchain_for_parallel_execution() -> Blockchain {
+        let service_keypair = gen_keypair();
+        let api_channel = mpsc::unbounded();
+        Blockchain::new(
+            TemporaryDB::new(),
+            vec![
+                Box::new(super::TestService) as Box<dyn Service>,
+                Box::new(MapService),
+                Box::new(TransferService),
+            ],
+            service_keypair.0,
+            service_keypair.1,
+            ApiSender::new(api_channel.0),
+        )
+    }
+
+    #[test]
+    fn parallel_execution() {
+        super::assert_parallel_execution(
+            create_blockchain_for_parallel_execution(),
+            create_blockchain_for_parallel_execution(),
+        );
+    }
+
+    #[test]
+    fn parallel_execution_with_proof_map() {
+        super::assert_parallel_execution_with_proof_map(
+            create_blockchain_for_parallel_execution(),
+            create_blockchain_for_parallel_execution(),
+        );
+    }
+
+    #[test]
+    fn service_migrations() {
+        let db: Arc<dyn Database> = Arc::new(TemporaryDB::new());
+        super::assert_migrations(|versions, height| {
+            let service_keypair = gen_keypair();
+            let api_channel = mpsc::unbounded();
+            Blockchain::new(
+                Arc::clone(&db),
+                vec![Box::new(ServiceWithMigrations { versions, height }) as Box<dyn Service>],
+                service_keypair.0,
+                service_keypair.1,
+                ApiSender::new(api_channel.0),
+            )
+        });
+    }
 }
 
 mod rocksdb_tests {
@@ -448,7 +1101,10 @@ mod rocksdb_tests {
     use crate::node::ApiSender;
     use exonum_merkledb::{Database, DbOptions, RocksDB};
 
-    use super::{ServiceGood, ServicePanic, ServicePanicStorageError};
+    use super::{
+        MapService, ServiceGood, ServicePanic, ServicePanicStorageError,
+        ServiceWithFailedMigration, TransferService,
+    };
 
     fn create_database(path: &Path) -> Box<dyn Database> {
         let opts = DbOptions::default();
@@ -528,4 +1184,68 @@ mod rocksdb_tests {
         let mut db = create_database(dir.path());
         super::assert_service_execute(&blockchain, db.as_mut());
     }
+
+    #[test]
+    #[should_panic(expected = "service migration at height 1 failed: Migration error")]
+    fn failed_migration_at_height() {
+        let dir = create_temp_dir();
+        let blockchain =
+            create_blockchain_with_service(dir.path(), Box::new(ServiceWithFailedMigration));
+        super::assert_failed_migration_at_height(&blockchain);
+    }
+
+    #[test]
+    fn prioritized_pool() {
+        let dir = create_temp_dir();
+        let mut blockchain = create_blockchain(dir.path());
+        super::assert_prioritized_pool(&mut blockchain);
+    }
+
+    #[test]
+    fn legacy_pool_restored() {
+        let dir = create_temp_dir();
+        let mut blockchain = create_blockchain(dir.path());
+        super::assert_legacy_pool_restored(&mut blockchain);
+    }
+
+    #[test]
+    fn validity_bounds() {
+        let dir = create_temp_dir();
+        let mut blockchain = create_blockchain(dir.path());
+        super::assert_validity_bounds(&mut blockchain);
+    }
+
+    fn create_blockchain_for_parallel_execution(path: &Path) -> Blockchain {
+        let service_keypair = gen_keypair();
+        let api_channel = mpsc::unbounded();
+        Blockchain::new(
+            create_database(path),
+            vec![
+                Box::new(super::TestService) as Box<dyn Service>,
+                Box::new(MapService),
+                Box::new(TransferService),
+            ],
+            service_keypair.0,
+            service_keypair.1,
+            ApiSender::new(api_channel.0),
+        )
+    }
+
+    #[test]
+    fn parallel_execution() {
+        let (dir, other_dir) = (create_temp_dir(), create_temp_dir());
+        super::assert_parallel_execution(
+            create_blockchain_for_parallel_execution(dir.path()),
+            create_blockchain_for_parallel_execution(other_dir.path()),
+        );
+    }
+
+    #[test]
+    fn parallel_execution_with_proof_map() {
+        let (dir, other_dir) = (create_temp_dir(), create_temp_dir());
+        super::assert_parallel_execution_with_proof_map(
+            create_blockchain_for_parallel_execution(dir.path()),
+            create_blockchain_for_parallel_execution(other_dir.path()),
+        );
+    }
 }
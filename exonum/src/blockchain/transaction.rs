@@ -18,12 +18,12 @@ use hex::ToHex;
 use protobuf::Message;
 use serde::{de::DeserializeOwned, Serialize};
 
-use std::{any::Any, borrow::Cow, convert::Into, error::Error, fmt, u8};
+use std::{any::Any, borrow::Cow, convert::Into, error::Error, fmt, panic, u8};
 
 use crate::crypto::{CryptoHash, Hash, PublicKey};
 use crate::messages::{HexStringRepresentation, RawTransaction, Signed, SignedMessage};
 use crate::proto::{self, ProtobufConvert};
-use exonum_merkledb::{BinaryValue, Fork, ObjectHash};
+use exonum_merkledb::{BinaryValue, Fork, ObjectHash, Snapshot};
 
 //  User-defined error codes (`TransactionErrorType::Code(u8)`) have a `0...255` range.
 #[cfg_attr(feature = "cargo-clippy", allow(clippy::cast_lossless))]
@@ -32,6 +32,8 @@ const MAX_ERROR_CODE: u16 = u8::max_value() as u16;
 const TRANSACTION_STATUS_OK: u16 = MAX_ERROR_CODE + 1;
 // `Err(TransactionErrorType::Panic)`.
 const TRANSACTION_STATUS_PANIC: u16 = TRANSACTION_STATUS_OK + 1;
+// `Err(TransactionErrorType::InvalidHeight)`.
+const TRANSACTION_STATUS_INVALID_HEIGHT: u16 = TRANSACTION_STATUS_PANIC + 1;
 
 /// Returns a result of the `Transaction` `execute` method. This result may be
 /// either an empty unit type, in case of success, or an `ExecutionError`, if execution has
@@ -172,6 +174,37 @@ pub trait Transaction: ::std::fmt::Debug + Send + 'static + ::erased_serde::Seri
     /// }
     /// # fn main() {}
     fn execute<'a>(&self, context: TransactionContext<'a>) -> ExecutionResult;
+
+    /// Checks the transaction before it is added to the pool of unconfirmed transactions.
+    /// The check is performed for transactions received from the API and from other nodes;
+    /// rejected transactions are neither stored in the pool nor broadcast, and the returned
+    /// error is reported as the rejection reason. A panic in the check rejects
+    /// the transaction as well.
+    ///
+    /// The check should be cheap and should reject only the transactions which would
+    /// obviously fail in `execute` (e.g., ones with malformed or out-of-range fields),
+    /// since the snapshot may differ from the state in which the transaction is executed.
+    /// A rejected transaction is still accepted if it is included into a propose or a block
+    /// by other validators, so `execute` must not rely on the check having passed.
+    ///
+    /// *Default implementation accepts all transactions*
+    #[allow(unused_variables)]
+    fn check(&self, snapshot: &dyn Snapshot) -> Result<(), failure::Error> {
+        Ok(())
+    }
+
+    /// Returns the priority of the transaction in the pool of unconfirmed transactions.
+    ///
+    /// Transactions with higher priorities are included into proposes first; transactions
+    /// with the same priority are ordered by their hashes. If the pool is full, a new
+    /// transaction evicts the transaction with the lowest priority, provided that the
+    /// priority of the new transaction is higher. The priority can be derived, for example,
+    /// from the fee paid by the transaction author.
+    ///
+    /// *Default implementation returns zero*
+    fn priority(&self) -> u64 {
+        0
+    }
 }
 
 //TODO: Add doc/examples.
@@ -268,6 +301,9 @@ impl ExecutionError {
 pub enum TransactionErrorType {
     /// Panic occurred during transaction execution.
     Panic,
+    /// Transaction was included into a block outside of its validity bounds,
+    /// so it has not been executed.
+    InvalidHeight,
     /// User-defined error code. Can have different meanings for different transactions and
     /// services.
     Code(u8),
@@ -340,6 +376,12 @@ impl TransactionError {
         Self::new(TransactionErrorType::Panic, description)
     }
 
+    /// Creates a new `TransactionError` representing a transaction committed outside of its
+    /// validity bounds.
+    pub(crate) fn invalid_height(description: Option<String>) -> Self {
+        Self::new(TransactionErrorType::InvalidHeight, description)
+    }
+
     /// Creates a new `TransactionError` instance from `std::thread::Result`'s `Err`.
     pub(crate) fn from_panic(panic: &Box<dyn Any + Send>) -> Self {
         Self::panic(panic_description(panic))
@@ -367,6 +409,9 @@ impl fmt::Display for TransactionError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.error_type {
             TransactionErrorType::Panic => write!(f, "Panic during execution")?,
+            TransactionErrorType::InvalidHeight => {
+                write!(f, "Transaction is not valid at the block height")?
+            }
             TransactionErrorType::Code(c) => write!(f, "Error code: {}", c)?,
         }
 
@@ -420,6 +465,7 @@ impl ProtobufConvert for TransactionResult {
             value @ 0..=MAX_ERROR_CODE => Err(TransactionError::code(value as u8, description)),
             TRANSACTION_STATUS_OK => Ok(()),
             TRANSACTION_STATUS_PANIC => Err(TransactionError::panic(description)),
+            TRANSACTION_STATUS_INVALID_HEIGHT => Err(TransactionError::invalid_height(description)),
             value => bail!("Invalid TransactionResult value: {}", value),
         }))
     }
@@ -448,6 +494,7 @@ fn status_as_u16(status: &TransactionResult) -> u16 {
         Ok(()) => TRANSACTION_STATUS_OK,
         Err(ref e) => match e.error_type {
             TransactionErrorType::Panic => TRANSACTION_STATUS_PANIC,
+            TransactionErrorType::InvalidHeight => TRANSACTION_STATUS_INVALID_HEIGHT,
             TransactionErrorType::Code(c) => u16::from(c),
         },
     }
@@ -464,6 +511,19 @@ pub trait TransactionSet:
     fn tx_from_raw(raw: RawTransaction) -> Result<Self, failure::Error>;
 }
 
+/// Invokes `Transaction::check` for the transaction. Unlike panics during the execution,
+/// a panic in the check cannot affect the blockchain state, so it is treated
+/// as the rejection of the transaction.
+pub(crate) fn run_check(
+    tx: &dyn Transaction,
+    snapshot: &dyn Snapshot,
+) -> Result<(), failure::Error> {
+    panic::catch_unwind(panic::AssertUnwindSafe(|| tx.check(snapshot))).unwrap_or_else(|err| {
+        let description = panic_description(&err).unwrap_or_default();
+        Err(format_err!("check panicked: {}", description))
+    })
+}
+
 /// Tries to get a meaningful description from the given panic.
 fn panic_description(any: &Box<dyn Any + Send>) -> Option<String> {
     if let Some(s) = any.downcast_ref::<&str>() {
@@ -583,6 +643,7 @@ mod tests {
                 255,
                 Some("(Not) really long error description".to_owned()),
             )),
+            Err(TransactionError::invalid_height(None)),
         ]
         .iter()
         .map(|res| TransactionResult(res.to_owned()))
@@ -676,6 +737,20 @@ mod tests {
         assert_eq!(None, panic_description(&error));
     }
 
+    #[test]
+    fn panic_in_check_rejects_transaction() {
+        let db = TemporaryDB::new();
+        let snapshot = db.snapshot();
+        assert!(run_check(&TxResult { value: 1 }, snapshot.as_ref()).is_ok());
+
+        let tx = TxResult {
+            value: u64::max_value(),
+        };
+        let err = run_check(&tx, snapshot.as_ref()).unwrap_err();
+        assert!(err.to_string().contains("check panicked"));
+        assert!(err.to_string().contains("Value is too large"));
+    }
+
     fn make_panic<T: Send + 'static>(val: T) -> Box<dyn Any + Send> {
         panic::catch_unwind(panic::AssertUnwindSafe(|| panic!(val))).unwrap_err()
     }
@@ -730,6 +805,11 @@ mod tests {
             entry.set(self.value);
             EXECUTION_STATUS.lock().unwrap().clone()
         }
+
+        fn check(&self, _: &dyn Snapshot) -> Result<(), failure::Error> {
+            assert_ne!(self.value, u64::max_value(), "Value is too large");
+            Ok(())
+        }
     }
 
     fn create_entry(fork: &Fork) -> Entry<&Fork, u64> {
@@ -332,6 +332,13 @@ impl<'a> IntoIterator for &'a BlockWithTransactions {
 /// { type: 'panic', description?: string }
 /// ```
 ///
+/// For transactions that have been committed outside of their validity bounds and thus
+/// have not been executed, `status` is
+///
+/// ```javascript
+/// { type: 'invalid-height', description?: string }
+/// ```
+///
 /// [`Transaction`]: ../blockchain/trait.Transaction.html
 /// [`TxLocation`]: ../blockchain/struct.TxLocation.html
 /// [`ListProof`]: ../../exonum_merkledb/enum.ListProof.html
@@ -411,6 +418,7 @@ pub struct CommittedTransaction {
 pub(crate) enum TxStatus<'a> {
     Success,
     Panic { description: &'a str },
+    InvalidHeight { description: &'a str },
     Error { code: u8, description: &'a str },
 }
 
@@ -442,6 +450,7 @@ impl<'a> From<&'a TransactionResult> for TxStatus<'a> {
                 let description = e.description().unwrap_or_default();
                 match e.error_type() {
                     Panic => TxStatus::Panic { description },
+                    InvalidHeight => TxStatus::InvalidHeight { description },
                     Code(code) => TxStatus::Error { code, description },
                 }
             }
@@ -462,6 +471,9 @@ impl<'a> From<TxStatus<'a>> for TransactionResult {
         TransactionResult(match status {
             TxStatus::Success => Ok(()),
             TxStatus::Panic { description } => Err(TransactionError::panic(to_option(description))),
+            TxStatus::InvalidHeight { description } => {
+                Err(TransactionError::invalid_height(to_option(description)))
+            }
             TxStatus::Error { code, description } => {
                 Err(TransactionError::code(code, to_option(description)))
             }
@@ -516,6 +528,11 @@ impl CommittedTransaction {
 /// - `type` field contains transaction type (`"in-pool"`).
 /// - `content` is JSON serialization of the transaction.
 ///
+/// ## Expired transactions
+///
+/// Expired transactions are represented in the same way as transactions in pool,
+/// with the `type` field equal to `"expired"`.
+///
 /// # Examples
 ///
 /// Use of the custom type parameter for deserialization:
@@ -578,6 +595,13 @@ pub enum TransactionInfo {
 
     /// Transaction is already committed to the blockchain.
     Committed(CommittedTransaction),
+
+    /// Transaction has been removed from the memory pool after its validity bounds
+    /// had expired, so it will never be committed.
+    Expired {
+        /// Transaction contents.
+        content: TransactionMessage,
+    },
 }
 
 impl TransactionInfo {
@@ -586,6 +610,7 @@ impl TransactionInfo {
         match *self {
             TransactionInfo::InPool { ref content } => content,
             TransactionInfo::Committed(ref tx) => tx.content(),
+            TransactionInfo::Expired { ref content } => content,
         }
     }
 
@@ -605,6 +630,14 @@ impl TransactionInfo {
         }
     }
 
+    /// Is this an expired transaction?
+    pub fn is_expired(&self) -> bool {
+        match *self {
+            TransactionInfo::Expired { .. } => true,
+            _ => false,
+        }
+    }
+
     /// Returns a reference to the inner committed transaction if this transaction is committed.
     /// For transactions in pool, returns `None`.
     pub fn as_committed(&self) -> Option<&CommittedTransaction> {
@@ -653,6 +686,9 @@ impl<'a> BlockchainExplorer<'a> {
         if schema.transactions_pool().contains(tx_hash) {
             return Some(TransactionInfo::InPool { content });
         }
+        if schema.expired_transactions().contains(tx_hash) {
+            return Some(TransactionInfo::Expired { content });
+        }
 
         let tx = self.committed_transaction(tx_hash, Some(content));
         Some(TransactionInfo::Committed(tx))
@@ -39,7 +39,7 @@ use crate::blockchain::{config::ValidatorKeys, GenesisConfig};
 use crate::crypto::{generate_keys_file, PublicKey};
 use crate::helpers::{config::ConfigFile, ZeroizeOnDrop};
 use crate::node::{ConnectListConfig, NodeApiConfig, NodeConfig};
-use exonum_merkledb::{Database, DbOptions, RocksDB};
+use exonum_merkledb::{CachedDB, Database, DbOptions, RocksDB};
 
 const CONSENSUS_KEY_PASS_METHOD: &str = "CONSENSUS_KEY_PASS_METHOD";
 const DATABASE_PATH: &str = "DATABASE_PATH";
@@ -57,12 +57,17 @@ const SERVICE_KEY_PASS_METHOD: &str = "SERVICE_KEY_PASS_METHOD";
 pub struct Run;
 
 impl Run {
-    /// Returns created database instance.
+    /// Returns created database instance. The database is wrapped into a cache
+    /// if the cache size is specified in the database options.
     pub fn db_helper(ctx: &Context, options: &DbOptions) -> Box<dyn Database> {
         let path = ctx
             .arg::<String>(DATABASE_PATH)
             .unwrap_or_else(|_| panic!("{} not found.", DATABASE_PATH));
-        Box::new(RocksDB::open(Path::new(&path), options).expect("Can't load database file"))
+        let db = RocksDB::open(Path::new(&path), options).expect("Can't load database file");
+        match options.cache_size {
+            Some(cache_size) => Box::new(CachedDB::new(db, cache_size)),
+            None => Box::new(db),
+        }
     }
 
     fn node_config_path(ctx: &Context) -> String {
@@ -819,6 +824,9 @@ impl Command for Finalize {
                 mempool: Default::default(),
                 services_configs: Default::default(),
                 database: Default::default(),
+                state_history: Default::default(),
+                parallel_execution: Default::default(),
+                network_id: Default::default(),
                 connect_list,
                 thread_pool_size: Default::default(),
             }
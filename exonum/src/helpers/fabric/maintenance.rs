@@ -20,10 +20,11 @@ use super::{
     internal::{CollectedCommand, Command, Feedback},
     Argument, CommandName, Context,
 };
+use crate::api::node::private::IndexInfo;
 use crate::blockchain::Schema;
 use crate::helpers::config::ConfigFile;
 use crate::node::NodeConfig;
-use exonum_merkledb::{Database, DbOptions, RocksDB};
+use exonum_merkledb::{Database, DbOptions, ObjectAccess, RocksDB};
 
 // Context entry for the path to the node config.
 const NODE_CONFIG_PATH: &str = "NODE_CONFIG_PATH";
@@ -31,10 +32,27 @@ const NODE_CONFIG_PATH: &str = "NODE_CONFIG_PATH";
 const DATABASE_PATH: &str = "DATABASE_PATH";
 // Context entry for the type of action to be performed.
 const MAINTENANCE_ACTION_PATH: &str = "MAINTENANCE_ACTION_PATH";
+// Context entry for the path to the database backup.
+const BACKUP_PATH: &str = "BACKUP_PATH";
+// Context entry for the column family to compact.
+const COLUMN_FAMILY: &str = "COLUMN_FAMILY";
+// Context entry for the hex-encoded start key of the compacted range.
+const START_KEY: &str = "START_KEY";
+// Context entry for the hex-encoded end key of the compacted range.
+const END_KEY: &str = "END_KEY";
 
 /// Maintenance command. Supported actions:
 ///
 /// - `clear-cache` - clear message cache.
+/// - `backup` - create a consistent copy of the database in the directory specified
+///   by the `--to` argument. The database is locked by a running node, so the action
+///   works only if the node is stopped; use the `v1/backup` private API endpoint
+///   to back up the database of a running node.
+/// - `indexes` - print information about all indexes stored in the database as JSON.
+/// - `stats` - print storage statistics of the database column families as JSON.
+/// - `compact` - compact the database. The compaction can be limited to a single column
+///   family with the `--column-family` argument and to a key range with the hex-encoded
+///   `--start-key` and `--end-key` arguments.
 #[derive(Debug)]
 pub struct Maintenance;
 
@@ -47,10 +65,14 @@ impl Maintenance {
     }
 
     fn database(ctx: &Context, options: &DbOptions) -> Box<dyn Database> {
+        Box::new(Self::rocksdb(ctx, options))
+    }
+
+    fn rocksdb(ctx: &Context, options: &DbOptions) -> RocksDB {
         let path = ctx
             .arg::<String>(DATABASE_PATH)
             .unwrap_or_else(|_| panic!("{} not found.", DATABASE_PATH));
-        Box::new(RocksDB::open(Path::new(&path), options).expect("Can't load database file"))
+        RocksDB::open(Path::new(&path), options).expect("Can't load database file")
     }
 
     fn clear_cache(context: &Context) {
@@ -64,6 +86,77 @@ impl Maintenance {
 
         info!("Cache cleared successfully");
     }
+
+    fn backup(context: &Context) {
+        let backup_path = context
+            .arg::<String>(BACKUP_PATH)
+            .unwrap_or_else(|_| panic!("{} not found.", BACKUP_PATH));
+        info!("Creating database backup at {}", backup_path);
+
+        let config = Self::node_config(context);
+        let db = Self::rocksdb(context, &config.database);
+        db.create_checkpoint(Path::new(&backup_path))
+            .expect("Can't create database backup");
+
+        info!("Backup created successfully");
+    }
+
+    fn indexes(context: &Context) {
+        let config = Self::node_config(context);
+        let db = Self::database(context, &config.database);
+        let snapshot = db.snapshot();
+        let indexes = (&snapshot)
+            .indexes(true)
+            .expect("Can't read indexes metadata")
+            .into_iter()
+            .map(IndexInfo::from)
+            .collect::<Vec<_>>();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&indexes).expect("Can't serialize indexes info")
+        );
+    }
+
+    fn stats(context: &Context) {
+        let config = Self::node_config(context);
+        let db = Self::rocksdb(context, &config.database);
+        let stats = db.stats().expect("Can't read database stats");
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&stats).expect("Can't serialize database stats")
+        );
+    }
+
+    fn compact(context: &Context) {
+        let column_family = context.arg::<String>(COLUMN_FAMILY).ok();
+        let key = |name: &str| {
+            context.arg::<String>(name).ok().map(|key| {
+                hex::decode(&key).unwrap_or_else(|_| panic!("Invalid hex value of {}", name))
+            })
+        };
+        let (start_key, end_key) = (key(START_KEY), key(END_KEY));
+        info!(
+            "Compacting column family {} in range [{}, {}]",
+            column_family.as_ref().map_or("<all>", String::as_str),
+            start_key
+                .as_ref()
+                .map_or_else(|| "<start>".to_owned(), hex::encode),
+            end_key
+                .as_ref()
+                .map_or_else(|| "<end>".to_owned(), hex::encode),
+        );
+
+        let config = Self::node_config(context);
+        let db = Self::rocksdb(context, &config.database);
+        db.compact_range(
+            column_family.as_ref().map(String::as_str),
+            start_key.as_ref().map(Vec::as_slice),
+            end_key.as_ref().map(Vec::as_slice),
+        )
+        .expect("Can't compact database");
+
+        info!("Database compacted successfully");
+    }
 }
 
 impl Command for Maintenance {
@@ -93,6 +186,39 @@ impl Command for Maintenance {
                 "action",
                 false,
             ),
+            Argument::new_named(
+                BACKUP_PATH,
+                false,
+                "Path to the directory to store the database backup in.",
+                None,
+                "to",
+                false,
+            ),
+            Argument::new_named(
+                COLUMN_FAMILY,
+                false,
+                "Name of the column family to compact. All column families are compacted \
+                 if not specified.",
+                None,
+                "column-family",
+                false,
+            ),
+            Argument::new_named(
+                START_KEY,
+                false,
+                "Hex-encoded first key of the range to compact.",
+                None,
+                "start-key",
+                false,
+            ),
+            Argument::new_named(
+                END_KEY,
+                false,
+                "Hex-encoded last key of the range to compact.",
+                None,
+                "end-key",
+                false,
+            ),
         ]
     }
 
@@ -101,7 +227,7 @@ impl Command for Maintenance {
     }
 
     fn about(&self) -> &str {
-        "Maintenance module. Available actions: clear-cache."
+        "Maintenance module. Available actions: clear-cache, backup, indexes, stats, compact."
     }
 
     fn execute(
@@ -116,6 +242,14 @@ impl Command for Maintenance {
 
         if action == "clear-cache" {
             Self::clear_cache(&context);
+        } else if action == "backup" {
+            Self::backup(&context);
+        } else if action == "indexes" {
+            Self::indexes(&context);
+        } else if action == "stats" {
+            Self::stats(&context);
+        } else if action == "compact" {
+            Self::compact(&context);
         } else {
             println!("Unsupported maintenance action: {}", action);
         }
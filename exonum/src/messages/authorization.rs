@@ -5,9 +5,14 @@ use std::fmt;
 
 use super::EMPTY_SIGNED_MESSAGE_SIZE;
 use crate::crypto::{
-    self, hash, Hash, PublicKey, SecretKey, Signature, PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH,
+    self, hash, Hash, PublicKey, SecretKey, Signature, HASH_SIZE, PUBLIC_KEY_LENGTH,
+    SIGNATURE_LENGTH,
 };
 
+// The most significant bit of the message class signals that the message is bound
+// to a network identifier.
+const NETWORK_ID_FLAG: u8 = 0x80;
+
 /// `SignedMessage` can be constructed from a raw byte buffer which must have the following
 /// data layout:
 ///
@@ -19,6 +24,11 @@ use crate::crypto::{
 /// | `34..N`   | payload                 |
 /// | `N..N+64` | signature               |
 ///
+/// A message can be bound to a network identifier, such as the hash of the genesis block,
+/// so that its signature is not valid in other networks. In this case, the most significant
+/// bit of the message class is set, and the identifier is placed at `34..66` before
+/// the payload. The identifier is covered by the signature, just like the rest of the message.
+///
 /// `SignedMessage` will verify the size of the buffer and the signature provided in it.
 /// This allows to keep the raw message buffer, but avoid verifying its signature again
 /// as every `SignedMessage` instance is guaranteed to have a correct signature.
@@ -36,10 +46,30 @@ impl SignedMessage {
         author: PublicKey,
         secret_key: &SecretKey,
     ) -> SignedMessage {
-        let mut buffer = Vec::with_capacity(2 + value.len() + PUBLIC_KEY_LENGTH + SIGNATURE_LENGTH);
+        Self::new_for_network(class, tag, value, None, author, secret_key)
+    }
+
+    /// Creates `SignedMessage` from parts, binding it to the specified network identifier.
+    pub(crate) fn new_for_network(
+        class: u8,
+        tag: u8,
+        value: &[u8],
+        network_id: Option<&Hash>,
+        author: PublicKey,
+        secret_key: &SecretKey,
+    ) -> SignedMessage {
+        debug_assert_eq!(class & NETWORK_ID_FLAG, 0);
+        let mut buffer =
+            Vec::with_capacity(2 + HASH_SIZE + value.len() + PUBLIC_KEY_LENGTH + SIGNATURE_LENGTH);
         buffer.extend_from_slice(author.as_ref());
-        buffer.push(class);
-        buffer.push(tag);
+        if let Some(network_id) = network_id {
+            buffer.push(class | NETWORK_ID_FLAG);
+            buffer.push(tag);
+            buffer.extend_from_slice(network_id.as_ref());
+        } else {
+            buffer.push(class);
+            buffer.push(tag);
+        }
         buffer.extend_from_slice(value);
         let signature = Self::sign(&buffer, secret_key).expect("Couldn't form signature");
         buffer.extend_from_slice(signature.as_ref());
@@ -73,6 +103,13 @@ impl SignedMessage {
             buffer.len()
         );
         let signed = SignedMessage { raw: buffer };
+        if signed.has_network_id() {
+            ensure!(
+                signed.raw.len() > EMPTY_SIGNED_MESSAGE_SIZE + HASH_SIZE,
+                "Message too short message_len = {}",
+                signed.raw.len()
+            );
+        }
         let pk = signed.author();
         let signature = signed.signature();
 
@@ -99,7 +136,21 @@ impl SignedMessage {
 
     /// Returns message class, which is an ID inside protocol.
     pub(in crate::messages) fn message_class(&self) -> u8 {
-        self.raw[PUBLIC_KEY_LENGTH]
+        self.raw[PUBLIC_KEY_LENGTH] & !NETWORK_ID_FLAG
+    }
+
+    fn has_network_id(&self) -> bool {
+        self.raw[PUBLIC_KEY_LENGTH] & NETWORK_ID_FLAG != 0
+    }
+
+    /// Returns the identifier of the network the message is bound to, if any.
+    pub fn network_id(&self) -> Option<Hash> {
+        if self.has_network_id() {
+            let start = PUBLIC_KEY_LENGTH + 2;
+            Some(Hash::from_slice(&self.raw[start..start + HASH_SIZE]).expect("Couldn't read Hash"))
+        } else {
+            None
+        }
     }
 
     /// Returns message type, which is an ID inside some class of messages.
@@ -110,7 +161,12 @@ impl SignedMessage {
     /// Returns serialized payload of the message.
     pub(in crate::messages) fn payload(&self) -> &[u8] {
         let sign_idx = self.raw.len() - SIGNATURE_LENGTH;
-        &self.raw[PUBLIC_KEY_LENGTH + 2..sign_idx]
+        let start = if self.has_network_id() {
+            PUBLIC_KEY_LENGTH + 2 + HASH_SIZE
+        } else {
+            PUBLIC_KEY_LENGTH + 2
+        };
+        &self.raw[start..sign_idx]
     }
 
     /// Returns ed25519 signature for this message.
@@ -40,6 +40,7 @@ use serde::ser::{Serialize, Serializer};
 use std::{borrow::Cow, cmp::PartialEq, fmt, mem, ops::Deref};
 
 use crate::crypto::{hash, CryptoHash, Hash, PublicKey, Signature};
+use crate::helpers::Height;
 
 pub(crate) use self::helpers::HexStringRepresentation;
 pub use self::{authorization::SignedMessage, helpers::to_hex_string, protocol::*};
@@ -54,6 +55,11 @@ mod tests;
 /// Version of the protocol. Different versions are incompatible.
 pub const PROTOCOL_MAJOR_VERSION: u8 = 1;
 pub(crate) const RAW_TRANSACTION_HEADER: usize = mem::size_of::<u16>() * 2;
+/// The most significant bit of the serialized service identifier signals that
+/// the transaction has validity bounds. Thus, service identifiers cannot exceed this value.
+pub const MAX_SERVICE_ID: u16 = VALIDITY_BOUNDS_FLAG - 1;
+const VALIDITY_BOUNDS_FLAG: u16 = 0x8000;
+const VALIDITY_BOUNDS_SIZE: usize = mem::size_of::<u64>() * 2;
 
 /// Transaction raw buffer.
 /// This struct is used to transfer transactions in network.
@@ -61,6 +67,52 @@ pub(crate) const RAW_TRANSACTION_HEADER: usize = mem::size_of::<u16>() * 2;
 pub struct RawTransaction {
     service_id: u16,
     service_transaction: ServiceTransaction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    validity: Option<ValidityBounds>,
+}
+
+/// Range of blockchain heights, inclusive from both sides, at which a transaction
+/// can be committed.
+///
+/// A transaction with validity bounds is not accepted into the pool of unconfirmed
+/// transactions outside of its bounds, and is removed from the pool once the blockchain
+/// height exceeds its maximum height. If such a transaction is included into a block
+/// outside of its bounds anyway, it is not executed and its result is
+/// `TransactionErrorType::InvalidHeight`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ValidityBounds {
+    min_height: Height,
+    max_height: Height,
+}
+
+impl ValidityBounds {
+    /// Creates bounds for the heights from `min_height` to `max_height` inclusive.
+    pub fn new(min_height: Height, max_height: Height) -> Self {
+        Self {
+            min_height,
+            max_height,
+        }
+    }
+
+    /// Creates bounds for the heights up to `max_height` inclusive.
+    pub fn until(max_height: Height) -> Self {
+        Self::new(Height::zero(), max_height)
+    }
+
+    /// Returns the minimum height at which the transaction can be committed.
+    pub fn min_height(&self) -> Height {
+        self.min_height
+    }
+
+    /// Returns the maximum height at which the transaction can be committed.
+    pub fn max_height(&self) -> Height {
+        self.max_height
+    }
+
+    /// Returns `true` if the transaction can be committed at the specified height.
+    pub fn contains(&self, height: Height) -> bool {
+        self.min_height <= height && height <= self.max_height
+    }
 }
 
 /// Concrete raw transaction transaction inside `TransactionSet`.
@@ -98,6 +150,21 @@ impl RawTransaction {
         RawTransaction {
             service_id,
             service_transaction,
+            validity: None,
+        }
+    }
+
+    /// Creates a new instance of RawTransaction which can be committed
+    /// only within the specified validity bounds.
+    pub fn with_validity(
+        service_id: u16,
+        service_transaction: ServiceTransaction,
+        validity: ValidityBounds,
+    ) -> RawTransaction {
+        RawTransaction {
+            service_id,
+            service_transaction,
+            validity: Some(validity),
         }
     }
 
@@ -111,15 +178,35 @@ impl RawTransaction {
         self.service_id
     }
 
+    /// Returns validity bounds of the transaction, if any.
+    pub fn validity(&self) -> Option<ValidityBounds> {
+        self.validity
+    }
+
+    /// Returns `true` if the transaction can be committed at the specified height.
+    /// Transactions without validity bounds can be committed at any height.
+    pub fn is_valid_at(&self, height: Height) -> bool {
+        self.validity.map_or(true, |bounds| bounds.contains(height))
+    }
+
     pub(crate) fn transaction_id(&self) -> u16 {
         self.service_transaction.transaction_id()
     }
 }
 
+// Validity bounds, if present, are placed between the service identifier and the service
+// transaction, so the binary representation of transactions without bounds is unchanged.
 impl BinaryValue for RawTransaction {
     fn to_bytes(&self) -> Vec<u8> {
         let mut buffer = vec![0; mem::size_of::<u16>()];
-        LittleEndian::write_u16(&mut buffer[0..2], self.service_id);
+        if let Some(validity) = self.validity {
+            LittleEndian::write_u16(&mut buffer[0..2], self.service_id | VALIDITY_BOUNDS_FLAG);
+            buffer.resize(2 + VALIDITY_BOUNDS_SIZE, 0);
+            LittleEndian::write_u64(&mut buffer[2..10], validity.min_height.0);
+            LittleEndian::write_u64(&mut buffer[10..18], validity.max_height.0);
+        } else {
+            LittleEndian::write_u16(&mut buffer[0..2], self.service_id);
+        }
         let value = self.service_transaction.to_bytes();
         buffer.extend_from_slice(&value);
         buffer
@@ -131,10 +218,24 @@ impl BinaryValue for RawTransaction {
             "Buffer too short in RawTransaction deserialization."
         );
         let service_id = LittleEndian::read_u16(&bytes[0..2]);
-        let service_transaction = ServiceTransaction::from_bytes(Cow::from(&bytes[2..]))?;
+        let (validity, rest) = if service_id & VALIDITY_BOUNDS_FLAG == 0 {
+            (None, &bytes[2..])
+        } else {
+            ensure!(
+                bytes.len() >= 2 + VALIDITY_BOUNDS_SIZE,
+                "Buffer too short in RawTransaction deserialization."
+            );
+            let validity = ValidityBounds::new(
+                Height(LittleEndian::read_u64(&bytes[2..10])),
+                Height(LittleEndian::read_u64(&bytes[10..18])),
+            );
+            (Some(validity), &bytes[2 + VALIDITY_BOUNDS_SIZE..])
+        };
+        let service_transaction = ServiceTransaction::from_bytes(Cow::from(rest))?;
         Ok(RawTransaction {
-            service_id,
+            service_id: service_id & !VALIDITY_BOUNDS_FLAG,
             service_transaction,
+            validity,
         })
     }
 }
@@ -188,6 +289,11 @@ impl<T: ProtocolMessage> Signed<T> {
         hash(self.message.raw())
     }
 
+    /// Returns the identifier of the network the message is bound to, if any.
+    pub fn network_id(&self) -> Option<Hash> {
+        self.message.network_id()
+    }
+
     /// Returns a serialized buffer.
     pub fn serialize(self) -> Vec<u8> {
         self.message.raw
@@ -31,7 +31,7 @@ use chrono::{DateTime, Utc};
 
 use std::{borrow::Cow, fmt::Debug, mem};
 
-use super::{RawTransaction, ServiceTransaction, Signed, SignedMessage};
+use super::{RawTransaction, ServiceTransaction, Signed, SignedMessage, ValidityBounds};
 use crate::blockchain;
 use crate::crypto::{CryptoHash, Hash, PublicKey, SecretKey, PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
 use crate::helpers::{Height, Round, ValidatorId};
@@ -904,10 +904,34 @@ impl Message {
         message: T,
         author: PublicKey,
         secret_key: &SecretKey,
+    ) -> Signed<T> {
+        Self::concrete_for_network(message, None, author, secret_key)
+    }
+
+    /// Creates new protocol message bound to the specified network identifier,
+    /// so that its signature is not valid in other networks. If the identifier is `None`,
+    /// the message is not bound to any network.
+    /// Return concrete `Signed<T>`
+    ///
+    /// # Panics
+    ///
+    /// This method can panic on serialization failure.
+    pub fn concrete_for_network<T: ProtocolMessage>(
+        message: T,
+        network_id: Option<Hash>,
+        author: PublicKey,
+        secret_key: &SecretKey,
     ) -> Signed<T> {
         let value = message.to_bytes();
         let (cls, typ) = T::message_type();
-        let signed = SignedMessage::new(cls, typ, &value, author, secret_key);
+        let signed = SignedMessage::new_for_network(
+            cls,
+            typ,
+            &value,
+            network_id.as_ref(),
+            author,
+            secret_key,
+        );
         T::into_message_from_parts(message, signed)
     }
 
@@ -935,6 +959,48 @@ impl Message {
         let raw_tx = RawTransaction::new(service_id, set);
         Self::concrete(raw_tx, public_key, secret_key)
     }
+
+    /// Creates a new raw transaction message bound to the specified network identifier.
+    /// If the identifier is `None`, the transaction is not bound to any network.
+    ///
+    /// # Panics
+    ///
+    /// This method can panic on serialization failure.
+    pub fn sign_transaction_for_network<T>(
+        transaction: T,
+        service_id: u16,
+        network_id: Option<Hash>,
+        public_key: PublicKey,
+        secret_key: &SecretKey,
+    ) -> Signed<RawTransaction>
+    where
+        T: Into<ServiceTransaction>,
+    {
+        let set: ServiceTransaction = transaction.into();
+        let raw_tx = RawTransaction::new(service_id, set);
+        Self::concrete_for_network(raw_tx, network_id, public_key, secret_key)
+    }
+
+    /// Creates a new raw transaction message which can be committed only within
+    /// the specified validity bounds.
+    ///
+    /// # Panics
+    ///
+    /// This method can panic on serialization failure.
+    pub fn sign_transaction_with_validity<T>(
+        transaction: T,
+        service_id: u16,
+        validity: ValidityBounds,
+        public_key: PublicKey,
+        secret_key: &SecretKey,
+    ) -> Signed<RawTransaction>
+    where
+        T: Into<ServiceTransaction>,
+    {
+        let set: ServiceTransaction = transaction.into();
+        let raw_tx = RawTransaction::with_validity(service_id, set, validity);
+        Self::concrete(raw_tx, public_key, secret_key)
+    }
 }
 
 impl Requests {
@@ -5,7 +5,7 @@ use hex::FromHex;
 
 use super::{
     BinaryValue, BlockResponse, Message, Precommit, ProtocolMessage, RawTransaction,
-    ServiceTransaction, Signed, SignedMessage, Status, TransactionsResponse,
+    ServiceTransaction, Signed, SignedMessage, Status, TransactionsResponse, ValidityBounds,
     RAW_TRANSACTION_EMPTY_SIZE, TRANSACTION_RESPONSE_EMPTY_SIZE,
 };
 use crate::blockchain::{Block, BlockProof};
@@ -233,3 +233,55 @@ fn test_raw_transaction_small_size() {
     assert_eq!(tx.service_id, 0);
     assert_eq!(tx.service_transaction.transaction_id, 0);
 }
+
+#[test]
+fn test_raw_transaction_with_validity() {
+    let set = ServiceTransaction::from_raw_unchecked(1, vec![1, 2, 3]);
+    let plain_tx = RawTransaction::new(128, set.clone());
+    let tx = RawTransaction::with_validity(128, set, ValidityBounds::new(Height(5), Height(10)));
+
+    let bytes = tx.to_bytes();
+    assert_eq!(bytes.len(), plain_tx.to_bytes().len() + 16);
+    let parsed = RawTransaction::from_bytes(Cow::from(&bytes)).unwrap();
+    assert_eq!(parsed, tx);
+    assert_eq!(parsed.service_id(), 128);
+    assert_eq!(
+        parsed.validity(),
+        Some(ValidityBounds::new(Height(5), Height(10)))
+    );
+    assert!(RawTransaction::from_bytes(Cow::from(&bytes[..10])).is_err());
+
+    assert!(!tx.is_valid_at(Height(4)));
+    assert!(tx.is_valid_at(Height(5)));
+    assert!(tx.is_valid_at(Height(10)));
+    assert!(!tx.is_valid_at(Height(11)));
+    assert!(plain_tx.is_valid_at(Height(u64::max_value())));
+}
+
+#[test]
+fn test_message_bound_to_network() {
+    let (pub_key, secret_key) = gen_keypair();
+    let network_id = hash(&[1]);
+    let status = Status::new(Height(2), &hash(&[]));
+
+    let unbound = Message::concrete(status.clone(), pub_key, &secret_key);
+    let bound = Message::concrete_for_network(status, Some(network_id), pub_key, &secret_key);
+    assert_eq!(unbound.network_id(), None);
+    assert_eq!(bound.network_id(), Some(network_id));
+    assert_ne!(unbound.hash(), bound.hash());
+
+    let raw = bound.signed_message().raw().to_vec();
+    let parsed = Message::from_raw_buffer(raw.clone()).unwrap();
+    let parsed = Status::try_from(parsed).unwrap();
+    assert_eq!(parsed, bound);
+    assert_eq!(parsed.height(), Height(2));
+
+    // The network identifier is covered by the signature.
+    let mut tampered = raw.clone();
+    tampered[34] ^= 1;
+    assert!(SignedMessage::from_raw_buffer(tampered).is_err());
+    // Messages bound to a network cannot be truncated to its identifier.
+    let mut truncated = raw[..66].to_vec();
+    truncated.extend_from_slice(&raw[raw.len() - 64..]);
+    assert!(SignedMessage::from_raw_buffer(truncated).is_err());
+}
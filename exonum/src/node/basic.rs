@@ -24,6 +24,18 @@ use crate::messages::{Connect, Message, PeersRequest, Responses, Service, Signed
 impl NodeHandler {
     /// Redirects message to the corresponding `handle_...` function.
     pub fn handle_message(&mut self, msg: Message) {
+        // The network of transactions is checked in `handle_tx`, since the node
+        // accepts the transactions it awaits regardless of their network.
+        let is_transaction = match msg {
+            Message::Service(Service::RawTransaction(_)) => true,
+            _ => false,
+        };
+        if !is_transaction {
+            if let Err(e) = self.check_network_id(msg.signed_message()) {
+                warn!("Received message from another network: {}", e);
+                return;
+            }
+        }
         match msg {
             Message::Consensus(msg) => self.handle_consensus(msg),
             Message::Requests(ref msg) => self.handle_request(msg),
@@ -45,6 +57,10 @@ impl NodeHandler {
     /// if received `Connect` message is correct.
     pub fn handle_connected(&mut self, address: &ConnectedPeerAddr, connect: Signed<Connect>) {
         info!("Received Connect message from peer: {:?}", address);
+        if let Err(e) = self.check_network_id(connect.signed_message()) {
+            warn!("Rejected Connect message from peer {:?}: {}", address, e);
+            return;
+        }
         // TODO: use `ConnectInfo` instead of connect-messages. (ECR-1452)
         self.state.add_connection(connect.author(), address.clone());
         self.handle_connect(connect);
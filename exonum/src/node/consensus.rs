@@ -14,7 +14,7 @@
 
 use std::collections::HashSet;
 
-use crate::blockchain::Schema;
+use crate::blockchain::{run_check, Schema};
 use crate::crypto::{CryptoHash, Hash, PublicKey};
 use crate::events::InternalRequest;
 use crate::helpers::{Height, Round, ValidatorId};
@@ -24,7 +24,7 @@ use crate::messages::{
     TransactionsRequest, TransactionsResponse,
 };
 use crate::node::{NodeHandler, RequestData};
-use exonum_merkledb::Patch;
+use exonum_merkledb::{Fork, Patch};
 
 // TODO Reduce view invocations. (ECR-171)
 impl NodeHandler {
@@ -531,21 +531,56 @@ impl NodeHandler {
     /// `Propose` timeout on this node if transaction count in the pool goes over the threshold.
     pub fn handle_tx(&mut self, msg: Signed<RawTransaction>) -> Result<(), failure::Error> {
         let hash = msg.hash();
+        // Transactions included into proposes or blocks are accepted anyway,
+        // otherwise the node would be unable to process them.
+        let is_awaited = self.state.is_awaited_transaction(&hash);
+        // Blocks committed before the network identifier was introduced contain
+        // transactions which are not bound to the network.
+        if !is_awaited {
+            self.check_network_id(msg.signed_message())?;
+        }
 
         let snapshot = self.blockchain.snapshot();
         if Schema::new(&snapshot).transactions().contains(&hash) {
             bail!("Received already processed transaction, hash {:?}", hash)
         }
 
-        if let Err(e) = self.blockchain.tx_from_raw(msg.payload().clone()) {
-            error!("Received invalid transaction {:?}, result: {}", msg, e);
-            bail!("Received malicious transaction.")
+        let tx = match self.blockchain.tx_from_raw(msg.payload().clone()) {
+            Ok(tx) => tx,
+            Err(e) => {
+                error!("Received invalid transaction {:?}, result: {}", msg, e);
+                bail!("Received malicious transaction.")
+            }
+        };
+
+        let height = self.state.height();
+        if !msg.payload().is_valid_at(height) {
+            if !is_awaited {
+                bail!("Transaction {:?} is not valid at height {}", hash, height)
+            }
+            warn!(
+                "Accepting awaited transaction {:?} which is not valid at height {}",
+                hash, height
+            );
+        }
+        if let Err(e) = run_check(tx.as_ref(), snapshot.as_ref()) {
+            if !is_awaited {
+                bail!("Transaction {:?} rejected: {}", hash, e)
+            }
+            warn!(
+                "Accepting awaited transaction {:?} despite failed check: {}",
+                hash, e
+            );
         }
 
+        let priority = tx.priority();
         let fork = self.blockchain.fork();
         {
+            if !is_awaited {
+                self.ensure_pool_capacity(&fork, &msg.author(), priority)?;
+            }
             let mut schema = Schema::new(&fork);
-            schema.add_transaction_into_pool(msg);
+            schema.add_transaction_into_pool_with_priority(msg, priority);
         }
         self.blockchain
             .merge(fork.into_patch())
@@ -571,6 +606,49 @@ impl NodeHandler {
         Ok(())
     }
 
+    // Checks that the transaction of the specified author with the specified priority fits
+    // into the pool limits. If the pool is full, transactions with lower priorities are
+    // evicted from it.
+    fn ensure_pool_capacity(
+        &self,
+        fork: &Fork,
+        author: &PublicKey,
+        priority: u64,
+    ) -> Result<(), failure::Error> {
+        let mut schema = Schema::new(fork);
+        if let Some(limit) = self.mempool_config.max_transactions_per_author {
+            if schema.author_transactions_in_pool(author, limit) >= limit {
+                bail!(
+                    "Transaction pool already contains {} transactions of author {:?}",
+                    limit,
+                    author
+                )
+            }
+        }
+
+        if let Some(limit) = self.mempool_config.max_transactions {
+            while schema.transactions_pool_len() >= limit {
+                // Transactions included into known proposes cannot be evicted,
+                // since they are needed to execute the proposes.
+                let evicted = schema
+                    .transactions_pool_queue()
+                    .iter_rev()
+                    .take_while(|key| key.priority() < priority)
+                    .map(|key| key.tx_hash())
+                    .find(|tx_hash| !self.state.is_referenced_transaction(tx_hash));
+                match evicted {
+                    Some(tx_hash) => {
+                        debug!("Evicting transaction {:?} from the pool", tx_hash);
+                        schema.remove_transaction_from_pool(&tx_hash);
+                        schema.transactions().remove(&tx_hash);
+                    }
+                    None => bail!("Transaction pool is full"),
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Handles raw transactions.
     pub fn handle_txs_batch(
         &mut self,
@@ -684,7 +762,6 @@ impl NodeHandler {
             }
             let snapshot = self.blockchain.snapshot();
             let schema = Schema::new(&snapshot);
-            let pool = schema.transactions_pool();
             let pool_len = schema.transactions_pool_len();
 
             info!("LEADER: pool = {}", pool_len);
@@ -692,7 +769,7 @@ impl NodeHandler {
             let round = self.state.round();
             let max_count = ::std::cmp::min(u64::from(self.txs_block_limit()), pool_len);
 
-            let txs: Vec<Hash> = pool.iter().take(max_count as usize).collect();
+            let txs = schema.prioritized_transactions_pool(max_count as usize);
             let propose = self.sign_message(Propose::new(
                 validator_id,
                 self.state.height(),
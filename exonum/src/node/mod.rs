@@ -46,7 +46,8 @@ use crate::api::{
     ApiAccess, ApiAggregator,
 };
 use crate::blockchain::{
-    Blockchain, ConsensusConfig, GenesisConfig, Schema, Service, SharedNodeState, ValidatorKeys,
+    Blockchain, ConsensusConfig, GenesisConfig, ParallelExecutionConfig, Schema, Service,
+    SharedNodeState, StateHistoryConfig, ValidatorKeys,
 };
 use crate::crypto::{self, read_keys_from_file, CryptoHash, Hash, PublicKey, SecretKey};
 use crate::events::{
@@ -137,6 +138,8 @@ pub struct NodeHandler {
     config_manager: Option<ConfigManager>,
     /// Can we speed up Propose with transaction pressure?
     allow_expedited_propose: bool,
+    /// Memory pool configuration.
+    mempool_config: MemoryPoolConfig,
 }
 
 /// Service configuration.
@@ -224,12 +227,75 @@ pub struct MemoryPoolConfig {
     /// Sets the maximum number of messages that can be buffered on the event loop's
     /// notification channel before a send will fail.
     pub events_pool_capacity: EventsPoolCapacity,
+    /// Maximum number of transactions in the pool of unconfirmed transactions.
+    /// If the pool is full, a new transaction evicts the transaction with the lowest
+    /// priority if its own priority is higher, and is rejected otherwise.
+    /// The pool is unbounded if the limit is not specified.
+    #[serde(default)]
+    pub max_transactions: Option<u64>,
+    /// Maximum number of transactions of a single author in the pool of unconfirmed
+    /// transactions. The number is unbounded if the limit is not specified.
+    #[serde(default)]
+    pub max_transactions_per_author: Option<u64>,
 }
 
 impl Default for MemoryPoolConfig {
     fn default() -> Self {
         Self {
             events_pool_capacity: EventsPoolCapacity::default(),
+            max_transactions: None,
+            max_transactions_per_author: None,
+        }
+    }
+}
+
+/// Identifier of the network which the messages signed by the node are bound to.
+///
+/// Binding protects against replaying messages signed for one network in another one,
+/// e.g., when several networks share validator or service keys. All nodes in the network
+/// should use the same identifier: the node rejects messages which are not bound to it.
+///
+/// # Examples
+///
+/// The identifier is set in the `network_id` section of the node configuration.
+/// The hash of the genesis block can be used as the identifier:
+///
+/// ```toml
+/// [network_id]
+/// type = "genesis_hash"
+/// ```
+///
+/// Alternatively, the identifier can be specified explicitly:
+///
+/// ```toml
+/// [network_id]
+/// type = "custom"
+/// id = "a8e2e8d6fba8b5cfd4cb8ca3b1acb0b5d1d3b0c9ce2e4cd1bd81ef3f4e3f7d63"
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NetworkIdConfig {
+    /// The hash of the genesis block.
+    GenesisHash,
+    /// An explicitly specified identifier.
+    Custom {
+        /// Network identifier.
+        id: Hash,
+    },
+}
+
+impl NetworkIdConfig {
+    /// Returns the network identifier for the given blockchain.
+    ///
+    /// # Panics
+    ///
+    /// If the genesis block of the blockchain was not committed.
+    pub fn resolve(self, blockchain: &Blockchain) -> Hash {
+        match self {
+            NetworkIdConfig::GenesisHash => Schema::new(&blockchain.snapshot())
+                .block_hash_by_height(Height::zero())
+                .expect("Genesis block was not committed"),
+            NetworkIdConfig::Custom { id } => id,
         }
     }
 }
@@ -263,6 +329,15 @@ pub struct NodeConfig<T = SecretKey> {
     /// Optional database configuration.
     #[serde(default)]
     pub database: DbOptions,
+    /// Optional configuration of the blockchain state history.
+    #[serde(default)]
+    pub state_history: StateHistoryConfig,
+    /// Optional configuration of the parallel execution of transactions.
+    #[serde(default)]
+    pub parallel_execution: ParallelExecutionConfig,
+    /// Optional identifier of the network which the messages signed by the node are bound to.
+    #[serde(default)]
+    pub network_id: Option<NetworkIdConfig>,
     /// Node's ConnectList.
     pub connect_list: ConnectListConfig,
     /// Transaction Verification Thread Pool size.
@@ -308,6 +383,9 @@ impl NodeConfig<PathBuf> {
             mempool: self.mempool,
             services_configs: self.services_configs,
             database: self.database,
+            state_history: self.state_history,
+            parallel_execution: self.parallel_execution,
+            network_id: self.network_id,
             connect_list: self.connect_list,
             thread_pool_size: self.thread_pool_size,
         }
@@ -457,12 +535,13 @@ impl NodeHandler {
             .position(|pk| pk.consensus_key == config.listener.consensus_public_key)
             .map(|id| ValidatorId(id as u16));
         info!("Validator id = '{:?}'", validator_id);
-        let connect = Message::concrete(
+        let connect = Message::concrete_for_network(
             Connect::new(
                 external_address,
                 system_state.current_time().into(),
                 &user_agent::get(),
             ),
+            blockchain.network_id(),
             config.listener.consensus_public_key,
             &config.listener.consensus_secret_key,
         );
@@ -503,17 +582,31 @@ impl NodeHandler {
             node_role,
             config_manager,
             allow_expedited_propose: true,
+            mempool_config: config.mempool,
         }
     }
 
     fn sign_message<T: ProtocolMessage>(&self, message: T) -> Signed<T> {
-        Message::concrete(
+        Message::concrete_for_network(
             message,
+            self.blockchain.network_id(),
             *self.state.consensus_public_key(),
             self.state.consensus_secret_key(),
         )
     }
 
+    /// Checks that the message is bound to the network identifier of the node, if any.
+    fn check_network_id(&self, message: &SignedMessage) -> Result<(), failure::Error> {
+        let expected = self.blockchain.network_id();
+        ensure!(
+            expected.is_none() || message.network_id() == expected,
+            "Message is bound to network {:?}, while the node belongs to network {:?}",
+            message.network_id(),
+            expected
+        );
+        Ok(())
+    }
+
     /// Return internal `SharedNodeState`
     pub fn api_state(&self) -> &SharedNodeState {
         &self.api_state
@@ -905,7 +998,13 @@ impl Node {
             node_cfg.service_secret_key.clone(),
             ApiSender::new(channel.api_requests.0.clone()),
         );
+        blockchain.set_state_history_config(node_cfg.state_history);
+        blockchain.set_parallel_execution_config(node_cfg.parallel_execution);
         blockchain.initialize(node_cfg.genesis.clone()).unwrap();
+        let network_id = node_cfg
+            .network_id
+            .map(|config| config.resolve(&blockchain));
+        blockchain.set_network_id(network_id);
 
         let peers = node_cfg.connect_list.addresses();
 
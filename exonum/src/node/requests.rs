@@ -14,6 +14,7 @@
 
 use super::NodeHandler;
 use crate::blockchain::Schema;
+use crate::crypto::HASH_SIZE;
 use crate::messages::{
     BlockRequest, BlockResponse, PrevotesRequest, ProposeRequest, Requests, Signed,
     TransactionsRequest, TransactionsResponse, RAW_TRANSACTION_HEADER,
@@ -77,8 +78,15 @@ impl NodeHandler {
         let schema = Schema::new(&snapshot);
         let mut txs = Vec::new();
         let mut txs_size = 0;
+        // Responses bound to the network also contain the network identifier.
+        let network_id_size = if self.blockchain.network_id().is_some() {
+            HASH_SIZE
+        } else {
+            0
+        };
         let unoccupied_message_size = self.state.config().consensus.max_message_len as usize
-            - TRANSACTION_RESPONSE_EMPTY_SIZE;
+            - TRANSACTION_RESPONSE_EMPTY_SIZE
+            - network_id_size;
 
         for hash in msg.txs() {
             let tx = schema.transactions().get(hash);
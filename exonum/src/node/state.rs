@@ -868,6 +868,29 @@ impl State {
         None
     }
 
+    /// Returns `true` if the transaction is awaited by a known propose or the incomplete block.
+    pub fn is_awaited_transaction(&self, tx_hash: &Hash) -> bool {
+        self.proposes
+            .values()
+            .any(|propose_state| propose_state.unknown_txs.contains(tx_hash))
+            || self
+                .incomplete_block
+                .as_ref()
+                .map_or(false, |block| block.unknown_txs.contains(tx_hash))
+    }
+
+    /// Returns `true` if the transaction is included into a known propose or the incomplete
+    /// block, so it must not be removed from the pool.
+    pub fn is_referenced_transaction(&self, tx_hash: &Hash) -> bool {
+        self.proposes
+            .values()
+            .any(|propose_state| propose_state.message().transactions().contains(tx_hash))
+            || self
+                .incomplete_block
+                .as_ref()
+                .map_or(false, |block| block.msg.transactions().contains(tx_hash))
+    }
+
     /// Returns pre-votes for the specified round and propose hash.
     pub fn prevotes(&self, round: Round, propose_hash: Hash) -> &[Signed<Prevote>] {
         self.prevotes
@@ -41,13 +41,24 @@ impl TxConfig {
         config: &[u8],
         actual_from: Height,
         signer: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Self::create_signed_for_network(from, config, actual_from, None, signer)
+    }
+
+    pub fn create_signed_for_network(
+        from: &PublicKey,
+        config: &[u8],
+        actual_from: Height,
+        network_id: Option<Hash>,
+        signer: &SecretKey,
     ) -> Signed<RawTransaction> {
         let mut msg = TxConfig::new();
         msg.set_from(from.to_pb());
         msg.set_config(config.to_vec());
         msg.set_actual_from(actual_from.0);
 
-        Message::sign_transaction(msg, CONFIG_SERVICE, *from, signer)
+        let raw_tx = RawTransaction::new(CONFIG_SERVICE, msg.into());
+        Message::concrete_for_network(raw_tx, network_id, *from, signer)
     }
 }
 #[derive(Default)]
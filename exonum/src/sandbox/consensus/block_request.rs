@@ -16,10 +16,12 @@
 
 use std::time::Duration;
 
-use crate::crypto::CryptoHash;
+use crate::crypto::{hash, CryptoHash};
 use crate::helpers::{Height, Round, ValidatorId};
 use crate::node::state::{BLOCK_REQUEST_TIMEOUT, TRANSACTIONS_REQUEST_TIMEOUT};
-use crate::sandbox::{compute_tx_hash, sandbox_tests_helper::*, timestamping_sandbox};
+use crate::sandbox::{
+    compute_tx_hash, sandbox_tests_helper::*, timestamping_sandbox, timestamping_sandbox_builder,
+};
 
 /// HANDLE block response
 
@@ -213,6 +215,101 @@ fn handle_block_response_with_unknown_tx() {
 
 /// HANDLE block response
 
+/// - should process block with transactions which are not bound to the network
+/// idea of test is:
+/// - the node is bound to a network, while the block at height 1 was committed
+///   before the network identifier was introduced
+/// - getting Status from other node with later height, send BlockRequest to this node
+/// - receive BlockResponse with unknown tx A not bound to the network
+/// - send TransactionsRequest with unknown tx A
+/// - receive TransactionsResponse with tx A
+/// - Block should be executed and committed
+#[test]
+fn handle_block_response_with_tx_from_before_network_id() {
+    let sandbox = timestamping_sandbox_builder()
+        .with_network_id(hash(&[1]))
+        .build();
+
+    let tx = gen_timestamping_tx();
+    assert_eq!(tx.signed_message().network_id(), None);
+
+    let propose = ProposeBuilder::new(&sandbox).build();
+
+    let block = BlockBuilder::new(&sandbox)
+        .with_tx_hash(&compute_tx_hash(&[tx.clone()]))
+        .with_state_hash(&sandbox.compute_state_hash(&[tx.clone()]))
+        .build();
+
+    let precommits: Vec<_> = (1..4)
+        .map(|id| {
+            sandbox.create_precommit(
+                ValidatorId(id),
+                Height(1),
+                Round(1),
+                &propose.hash(),
+                &block.hash(),
+                sandbox.time().into(),
+                sandbox.secret_key(ValidatorId(id)),
+            )
+        })
+        .collect();
+
+    sandbox.recv(&sandbox.create_status(
+        &sandbox.public_key(ValidatorId(3)),
+        Height(2),
+        &block.hash(),
+        sandbox.secret_key(ValidatorId(3)),
+    ));
+
+    sandbox.add_time(Duration::from_millis(BLOCK_REQUEST_TIMEOUT));
+    sandbox.send(
+        sandbox.public_key(ValidatorId(3)),
+        &sandbox.create_block_request(
+            &sandbox.public_key(ValidatorId(0)),
+            &sandbox.public_key(ValidatorId(3)),
+            Height(1),
+            sandbox.secret_key(ValidatorId(0)),
+        ),
+    );
+
+    sandbox.recv(&sandbox.create_block_response(
+        &sandbox.public_key(ValidatorId(3)),
+        &sandbox.public_key(ValidatorId(0)),
+        block.clone(),
+        precommits,
+        &[tx.hash()],
+        sandbox.secret_key(ValidatorId(3)),
+    ));
+
+    sandbox.add_time(Duration::from_millis(TRANSACTIONS_REQUEST_TIMEOUT));
+    sandbox.send(
+        sandbox.public_key(ValidatorId(3)),
+        &sandbox.create_transactions_request(
+            &sandbox.public_key(ValidatorId(0)),
+            &sandbox.public_key(ValidatorId(3)),
+            &[tx.hash()],
+            sandbox.secret_key(ValidatorId(0)),
+        ),
+    );
+
+    sandbox.recv(&sandbox.create_transactions_response(
+        &sandbox.public_key(ValidatorId(3)),
+        &sandbox.public_key(ValidatorId(0)),
+        vec![tx.clone()],
+        sandbox.secret_key(ValidatorId(3)),
+    ));
+
+    sandbox.assert_state(Height(2), Round(1));
+    sandbox.broadcast(&sandbox.create_status(
+        &sandbox.public_key(ValidatorId(0)),
+        Height(2),
+        &block.hash(),
+        sandbox.secret_key(ValidatorId(0)),
+    ));
+}
+
+/// HANDLE block response
+
 /// - A block with an incorrect transactions order should not be processed
 /// idea of test is:
 /// - getting Status from other node with later height, send BlockRequest to this node
@@ -18,9 +18,9 @@ use bit_vec::BitVec;
 
 use std::time::Duration;
 
-use crate::crypto::{gen_keypair, CryptoHash, Hash};
+use crate::crypto::{gen_keypair, hash, CryptoHash, Hash};
 use crate::helpers::{Height, Milliseconds, Round, ValidatorId};
-use crate::messages::{RawTransaction, Signed};
+use crate::messages::{RawTransaction, Signed, ValidityBounds};
 use crate::node::state::TRANSACTIONS_REQUEST_TIMEOUT;
 use crate::sandbox::{
     compute_tx_hash,
@@ -244,6 +244,148 @@ fn duplicate_tx_in_pool() {
     ));
 }
 
+#[test]
+fn rejected_tx_not_added_to_pool() {
+    let sandbox = timestamping_sandbox();
+
+    let valid_tx = gen_timestamping_tx();
+    // Timestamping transactions with empty data are rejected by `Transaction::check`.
+    let invalid_tx = TimestampingTxGenerator::new(0).next().unwrap();
+
+    sandbox.recv(&valid_tx);
+    sandbox.recv(&invalid_tx);
+
+    // Only the transaction which has passed the check is stored in the pool.
+    sandbox.recv_rebroadcast();
+    sandbox.broadcast(&valid_tx);
+}
+
+#[test]
+fn rejected_tx_accepted_if_awaited_by_propose() {
+    let sandbox = timestamping_sandbox();
+
+    let tx = TimestampingTxGenerator::new(0).next().unwrap();
+    let propose = ProposeBuilder::new(&sandbox)
+        .with_tx_hashes(&[tx.hash()])
+        .build();
+
+    sandbox.recv(&propose);
+    sandbox.add_time(Duration::from_millis(TRANSACTIONS_REQUEST_TIMEOUT));
+    sandbox.send(
+        sandbox.public_key(ValidatorId(2)),
+        &sandbox.create_transactions_request(
+            &sandbox.public_key(ValidatorId(0)),
+            &sandbox.public_key(ValidatorId(2)),
+            &[tx.hash()],
+            &sandbox.secret_key(ValidatorId(0)),
+        ),
+    );
+
+    // The transaction fails the check, but it is accepted since the propose cannot
+    // be processed without it.
+    sandbox.recv(&tx);
+    sandbox.broadcast(&make_prevote_from_propose(&sandbox, &propose));
+}
+
+#[test]
+fn tx_rejected_if_pool_is_full() {
+    let sandbox = timestamping_sandbox_builder()
+        .with_mempool(|config| config.max_transactions = Some(2))
+        .build();
+
+    let transactions: Vec<_> = TimestampingTxGenerator::new(DATA_SIZE).take(3).collect();
+    for tx in &transactions {
+        sandbox.recv(tx);
+    }
+
+    // All transactions have the same priority, so the last one does not fit into the pool.
+    let mut accepted = transactions[..2].to_vec();
+    accepted.sort_by_key(Signed::hash);
+    sandbox.recv_rebroadcast();
+    for tx in &accepted {
+        sandbox.broadcast(tx);
+    }
+}
+
+#[test]
+fn tx_rejected_if_author_limit_is_reached() {
+    let sandbox = timestamping_sandbox_builder()
+        .with_mempool(|config| config.max_transactions_per_author = Some(1))
+        .build();
+
+    let mut author_txs = TimestampingTxGenerator::with_keypair(DATA_SIZE, gen_keypair());
+    let accepted_tx = author_txs.next().unwrap();
+    let rejected_tx = author_txs.next().unwrap();
+    let other_tx = gen_timestamping_tx();
+
+    sandbox.recv(&accepted_tx);
+    sandbox.recv(&rejected_tx);
+    sandbox.recv(&other_tx);
+
+    let mut accepted = vec![accepted_tx, other_tx];
+    accepted.sort_by_key(Signed::hash);
+    sandbox.recv_rebroadcast();
+    for tx in &accepted {
+        sandbox.broadcast(tx);
+    }
+}
+
+#[test]
+fn tx_outside_validity_bounds_rejected() {
+    let sandbox = timestamping_sandbox();
+    let height = sandbox.current_height();
+
+    let valid_tx = TimestampingTxGenerator::new(DATA_SIZE)
+        .with_validity(ValidityBounds::until(height))
+        .next()
+        .unwrap();
+    let expired_tx = TimestampingTxGenerator::new(DATA_SIZE)
+        .with_validity(ValidityBounds::until(height.previous()))
+        .next()
+        .unwrap();
+    let future_tx = TimestampingTxGenerator::new(DATA_SIZE)
+        .with_validity(ValidityBounds::new(height.next(), Height(10)))
+        .next()
+        .unwrap();
+
+    sandbox.recv(&valid_tx);
+    sandbox.recv(&expired_tx);
+    sandbox.recv(&future_tx);
+    sandbox.assert_pool_len(1);
+
+    sandbox.recv_rebroadcast();
+    sandbox.broadcast(&valid_tx);
+}
+
+#[test]
+fn tx_from_another_network_rejected() {
+    let network_id = hash(&[1]);
+    let sandbox = timestamping_sandbox_builder()
+        .with_network_id(network_id)
+        .do_not_initialize_connections()
+        .build();
+    assert_eq!(
+        sandbox.node_state().our_connect_message().network_id(),
+        Some(network_id)
+    );
+
+    let unbound_tx = TimestampingTxGenerator::new(DATA_SIZE).next().unwrap();
+    let foreign_tx = TimestampingTxGenerator::new(DATA_SIZE)
+        .with_network_id(hash(&[2]))
+        .next()
+        .unwrap();
+    let tx = TimestampingTxGenerator::new(DATA_SIZE)
+        .with_network_id(network_id)
+        .next()
+        .unwrap();
+
+    sandbox.recv(&unbound_tx);
+    sandbox.recv(&foreign_tx);
+    sandbox.assert_pool_len(0);
+    sandbox.recv(&tx);
+    sandbox.assert_pool_len(1);
+}
+
 #[test]
 fn rebroadcast_transactions() {
     let sandbox = timestamping_sandbox();
@@ -319,17 +461,38 @@ fn incorrect_tx_in_request() {
 
 #[test]
 fn response_size_larger_than_max_message_len() {
+    check_response_size_larger_than_max_message_len(None);
+}
+
+#[test]
+fn response_size_larger_than_max_message_len_with_network_id() {
+    check_response_size_larger_than_max_message_len(Some(hash(&[1])));
+}
+
+fn check_response_size_larger_than_max_message_len(network_id: Option<Hash>) {
+    use crate::crypto::HASH_SIZE;
     use crate::messages::{RAW_TRANSACTION_HEADER, TRANSACTION_RESPONSE_EMPTY_SIZE};
     use exonum_merkledb::BinaryValue;
 
-    let sandbox = timestamping_sandbox();
+    let mut builder = timestamping_sandbox_builder();
+    if let Some(network_id) = network_id {
+        builder = builder.with_network_id(network_id);
+    }
+    let sandbox = builder.build();
     let sandbox_state = SandboxState::new();
+    let tx_generator = |data_size| {
+        let generator = TimestampingTxGenerator::new(data_size);
+        match network_id {
+            Some(network_id) => generator.with_network_id(network_id),
+            None => generator,
+        }
+    };
     // Create 4 transactions.
     // The size of the fourth transactions is 1 more than size of the first three.
-    let tx1 = gen_timestamping_tx();
-    let tx2 = gen_timestamping_tx();
-    let tx3 = gen_timestamping_tx();
-    let tx4 = TimestampingTxGenerator::new(DATA_SIZE + 1).next().unwrap();
+    let tx1 = tx_generator(DATA_SIZE).next().unwrap();
+    let tx2 = tx_generator(DATA_SIZE).next().unwrap();
+    let tx3 = tx_generator(DATA_SIZE).next().unwrap();
+    let tx4 = tx_generator(DATA_SIZE + 1).next().unwrap();
 
     assert_eq!(
         tx1.signed_message().raw().len() + tx2.signed_message().raw().len() + 1,
@@ -338,9 +501,12 @@ fn response_size_larger_than_max_message_len() {
 
     // Create new config. Set the size of the message to a size
     // that is exactly equal to the message to send the first two transactions.
+    // A response bound to the network also contains the network identifier.
+    let network_id_size = if network_id.is_some() { HASH_SIZE } else { 0 };
     let tx_cfg = {
         let mut consensus_cfg = sandbox.cfg();
         consensus_cfg.consensus.max_message_len = (TRANSACTION_RESPONSE_EMPTY_SIZE
+            + network_id_size
             + tx1.signed_message().raw().len()
             + RAW_TRANSACTION_HEADER
             + tx2.signed_message().raw().len()
@@ -348,10 +514,11 @@ fn response_size_larger_than_max_message_len() {
         consensus_cfg.actual_from = sandbox.current_height().next();
         consensus_cfg.previous_cfg_hash = sandbox.cfg().hash();
 
-        TxConfig::create_signed(
+        TxConfig::create_signed_for_network(
             &sandbox.public_key(ValidatorId(0)),
             &consensus_cfg.clone().into_bytes(),
             consensus_cfg.actual_from,
+            network_id,
             sandbox.secret_key(ValidatorId(0)),
         )
     };
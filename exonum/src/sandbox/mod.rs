@@ -25,7 +25,7 @@ use std::{
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
-use exonum_merkledb::{HashTag, MapProof, ObjectHash, TemporaryDB};
+use exonum_merkledb::{HashTag, MapProof, MemoryDB, ObjectHash};
 
 use crate::{
     blockchain::{
@@ -45,8 +45,8 @@ use crate::{
     },
     node::{
         ApiSender, Configuration, ConnectInfo, ConnectList, ConnectListConfig, ExternalMessage,
-        ListenerConfig, NodeHandler, NodeSender, PeerAddress, ServiceConfig, State,
-        SystemStateProvider,
+        ListenerConfig, MemoryPoolConfig, NodeHandler, NodeSender, PeerAddress, ServiceConfig,
+        State, SystemStateProvider,
     },
     sandbox::{
         config_updater::ConfigUpdateService, sandbox_tests_helper::PROPOSE_TIMEOUT,
@@ -217,6 +217,18 @@ impl Sandbox {
         self.addresses[id].address.clone()
     }
 
+    /// Signs the message, binding it to the network of the node if the network
+    /// identifier is set.
+    fn sign<T: ProtocolMessage>(
+        &self,
+        message: T,
+        author: PublicKey,
+        secret_key: &SecretKey,
+    ) -> Signed<T> {
+        let network_id = self.blockchain_ref().network_id();
+        Message::concrete_for_network(message, network_id, author, secret_key)
+    }
+
     /// Creates a `BlockRequest` message signed by this validator.
     pub fn create_block_request(
         &self,
@@ -225,7 +237,7 @@ impl Sandbox {
         height: Height,
         secret_key: &SecretKey,
     ) -> Signed<BlockRequest> {
-        Message::concrete(BlockRequest::new(to, height), *author, secret_key)
+        self.sign(BlockRequest::new(to, height), *author, secret_key)
     }
 
     /// Creates a `Status` message signed by this validator.
@@ -236,7 +248,7 @@ impl Sandbox {
         last_hash: &Hash,
         secret_key: &SecretKey,
     ) -> Signed<Status> {
-        Message::concrete(Status::new(height, last_hash), *author, secret_key)
+        self.sign(Status::new(height, last_hash), *author, secret_key)
     }
 
     /// Creates a `BlockResponse` message signed by this validator.
@@ -249,7 +261,7 @@ impl Sandbox {
         tx_hashes: &[Hash],
         secret_key: &SecretKey,
     ) -> Signed<BlockResponse> {
-        Message::concrete(
+        self.sign(
             BlockResponse::new(
                 to,
                 block,
@@ -270,7 +282,7 @@ impl Sandbox {
         user_agent: &str,
         secret_key: &SecretKey,
     ) -> Signed<Connect> {
-        Message::concrete(
+        self.sign(
             Connect::new(&addr, time, user_agent),
             *public_key,
             secret_key,
@@ -284,7 +296,7 @@ impl Sandbox {
         to: &PublicKey,
         secret_key: &SecretKey,
     ) -> Signed<PeersRequest> {
-        Message::concrete(PeersRequest::new(to), *public_key, secret_key)
+        self.sign(PeersRequest::new(to), *public_key, secret_key)
     }
 
     /// Creates a `Propose` message signed by this validator.
@@ -297,7 +309,7 @@ impl Sandbox {
         tx_hashes: &[Hash],
         secret_key: &SecretKey,
     ) -> Signed<Propose> {
-        Message::concrete(
+        self.sign(
             Propose::new(validator_id, height, round, last_hash, tx_hashes),
             self.public_key(validator_id),
             secret_key,
@@ -316,7 +328,7 @@ impl Sandbox {
         system_time: chrono::DateTime<chrono::Utc>,
         secret_key: &SecretKey,
     ) -> Signed<Precommit> {
-        Message::concrete(
+        self.sign(
             Precommit::new(
                 validator_id,
                 propose_height,
@@ -340,7 +352,7 @@ impl Sandbox {
         locked_round: Round,
         secret_key: &SecretKey,
     ) -> Signed<Prevote> {
-        Message::concrete(
+        self.sign(
             Prevote::new(
                 validator_id,
                 propose_height,
@@ -365,7 +377,7 @@ impl Sandbox {
         validators: BitVec,
         secret_key: &SecretKey,
     ) -> Signed<PrevotesRequest> {
-        Message::concrete(
+        self.sign(
             PrevotesRequest::new(to, height, round, propose_hash, validators),
             *from,
             secret_key,
@@ -381,7 +393,7 @@ impl Sandbox {
         propose_hash: &Hash,
         secret_key: &SecretKey,
     ) -> Signed<ProposeRequest> {
-        Message::concrete(
+        self.sign(
             ProposeRequest::new(to, height, propose_hash),
             *author,
             secret_key,
@@ -396,7 +408,7 @@ impl Sandbox {
         txs: &[Hash],
         secret_key: &SecretKey,
     ) -> Signed<TransactionsRequest> {
-        Message::concrete(TransactionsRequest::new(to, txs), *author, secret_key)
+        self.sign(TransactionsRequest::new(to, txs), *author, secret_key)
     }
 
     /// Creates a `TransactionsResponse` message signed by this validator.
@@ -410,7 +422,7 @@ impl Sandbox {
     where
         I: IntoIterator<Item = Signed<RawTransaction>>,
     {
-        Message::concrete(
+        self.sign(
             TransactionsResponse::new(to, txs.into_iter().map(Signed::serialize).collect()),
             *author,
             secret_key,
@@ -860,7 +872,7 @@ impl Sandbox {
             },
             network: NetworkConfiguration::default(),
             peer_discovery: Vec::new(),
-            mempool: Default::default(),
+            mempool: inner.handler.mempool_config.clone(),
         };
 
         let system_state = SandboxSystemStateProvider {
@@ -960,6 +972,8 @@ pub struct SandboxBuilder {
     services: Vec<Box<dyn Service>>,
     validators_count: u8,
     consensus_config: ConsensusConfig,
+    mempool_config: MemoryPoolConfig,
+    network_id: Option<Hash>,
 }
 
 impl SandboxBuilder {
@@ -978,6 +992,8 @@ impl SandboxBuilder {
                 max_propose_timeout: PROPOSE_TIMEOUT,
                 propose_timeout_threshold: std::u32::MAX,
             },
+            mempool_config: MemoryPoolConfig::default(),
+            network_id: None,
         }
     }
 
@@ -996,6 +1012,16 @@ impl SandboxBuilder {
         self
     }
 
+    pub fn with_mempool<F: FnOnce(&mut MemoryPoolConfig)>(mut self, update: F) -> Self {
+        update(&mut self.mempool_config);
+        self
+    }
+
+    pub fn with_network_id(mut self, network_id: Hash) -> Self {
+        self.network_id = Some(network_id);
+        self
+    }
+
     pub fn with_validators(mut self, n: u8) -> Self {
         self.validators_count = n;
         self
@@ -1009,6 +1035,8 @@ impl SandboxBuilder {
         let mut sandbox = sandbox_with_services_uninitialized(
             self.services,
             self.consensus_config,
+            self.mempool_config,
+            self.network_id,
             self.validators_count,
         );
 
@@ -1031,6 +1059,8 @@ fn gen_primitive_socket_addr(idx: u8) -> SocketAddr {
 fn sandbox_with_services_uninitialized(
     services: Vec<Box<dyn Service>>,
     consensus: ConsensusConfig,
+    mempool: MemoryPoolConfig,
+    network_id: Option<Hash>,
     validators_count: u8,
 ) -> Sandbox {
     let validators = (0..validators_count)
@@ -1058,7 +1088,7 @@ fn sandbox_with_services_uninitialized(
         .collect();
 
     let api_channel = mpsc::unbounded();
-    let db = TemporaryDB::new();
+    let db = MemoryDB::new();
     let mut blockchain = Blockchain::new(
         db,
         services,
@@ -1082,6 +1112,7 @@ fn sandbox_with_services_uninitialized(
         ConnectListConfig::from_validator_keys(&genesis.validator_keys, &str_addresses);
 
     blockchain.initialize(genesis).unwrap();
+    blockchain.set_network_id(network_id);
 
     let config = Configuration {
         listener: ListenerConfig {
@@ -1096,7 +1127,7 @@ fn sandbox_with_services_uninitialized(
         },
         network: NetworkConfiguration::default(),
         peer_discovery: Vec::new(),
-        mempool: Default::default(),
+        mempool,
     };
 
     let system_state = SandboxSystemStateProvider {
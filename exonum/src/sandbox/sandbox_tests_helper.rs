@@ -25,7 +25,7 @@ use crate::helpers::{Height, Milliseconds, Round, ValidatorId};
 use crate::messages::{
     Precommit, Prevote, PrevotesRequest, Propose, ProposeRequest, RawTransaction, Signed,
 };
-use exonum_merkledb::{Database, HashTag, ObjectHash, ProofListIndex, TemporaryDB};
+use exonum_merkledb::{Database, HashTag, MemoryDB, ObjectHash, ProofListIndex};
 
 pub type TimestampingSandbox = Sandbox;
 
@@ -203,7 +203,7 @@ pub fn empty_hash() -> Hash {
 }
 
 pub fn compute_txs_merkle_root(txs: &[Hash]) -> Hash {
-    let fork = TemporaryDB::new().fork();
+    let fork = MemoryDB::new().fork();
     let mut hashes = ProofListIndex::new("name", &fork);
     hashes.extend(txs.iter().cloned());
     hashes.object_hash()
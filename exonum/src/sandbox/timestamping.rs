@@ -22,7 +22,7 @@ use crate::blockchain::{
     ExecutionResult, Service, Transaction, TransactionContext, TransactionSet,
 };
 use crate::crypto::{gen_keypair, Hash, PublicKey, SecretKey, HASH_SIZE};
-use crate::messages::{Message, RawTransaction, Signed};
+use crate::messages::{Message, RawTransaction, ServiceTransaction, Signed, ValidityBounds};
 use exonum_merkledb::{impl_binary_value_for_message, BinaryValue, Snapshot};
 use protobuf::Message as PbMessage;
 
@@ -39,6 +39,11 @@ impl Transaction for TimestampTx {
     fn execute(&self, _: TransactionContext) -> ExecutionResult {
         Ok(())
     }
+
+    fn check(&self, _: &dyn Snapshot) -> Result<(), failure::Error> {
+        ensure!(!self.get_data().is_empty(), "Timestamped data is empty");
+        Ok(())
+    }
 }
 
 impl_binary_value_for_message! { TimestampTx }
@@ -51,6 +56,8 @@ pub struct TimestampingTxGenerator {
     data_size: usize,
     public_key: PublicKey,
     secret_key: SecretKey,
+    validity: Option<ValidityBounds>,
+    network_id: Option<Hash>,
 }
 
 impl TimestampingTxGenerator {
@@ -70,8 +77,20 @@ impl TimestampingTxGenerator {
             data_size,
             public_key: keypair.0,
             secret_key: keypair.1,
+            validity: None,
+            network_id: None,
         }
     }
+
+    pub fn with_validity(mut self, validity: ValidityBounds) -> TimestampingTxGenerator {
+        self.validity = Some(validity);
+        self
+    }
+
+    pub fn with_network_id(mut self, network_id: Hash) -> TimestampingTxGenerator {
+        self.network_id = Some(network_id);
+        self
+    }
 }
 
 impl Iterator for TimestampingTxGenerator {
@@ -82,9 +101,14 @@ impl Iterator for TimestampingTxGenerator {
         self.rand.fill_bytes(&mut data);
         let mut buf = TimestampTx::new();
         buf.set_data(data);
-        Some(Message::sign_transaction(
-            buf,
-            TIMESTAMPING_SERVICE,
+        let tx: ServiceTransaction = buf.into();
+        let raw_tx = match self.validity {
+            Some(validity) => RawTransaction::with_validity(TIMESTAMPING_SERVICE, tx, validity),
+            None => RawTransaction::new(TIMESTAMPING_SERVICE, tx),
+        };
+        Some(Message::concrete_for_network(
+            raw_tx,
+            self.network_id,
             self.public_key,
             &self.secret_key,
         ))
@@ -239,9 +239,10 @@ impl PrivateApi {
         config.consensus.warn_if_nonoptimal();
 
         let cfg_hash = config.hash();
-        let propose = Propose::sign(
+        let propose = Propose::sign_for_network(
             state.public_key(),
             ::std::str::from_utf8(config.into_bytes().as_slice()).unwrap(),
+            state.blockchain().network_id(),
             state.secret_key(),
         );
         let tx_hash = propose.hash();
@@ -252,7 +253,12 @@ impl PrivateApi {
     }
 
     fn handle_vote(state: &ServiceApiState, query: HashQuery) -> api::Result<VoteResponse> {
-        let vote = Vote::sign(state.public_key(), &query.hash, state.secret_key());
+        let vote = Vote::sign_for_network(
+            state.public_key(),
+            &query.hash,
+            state.blockchain().network_id(),
+            state.secret_key(),
+        );
         let tx_hash = vote.hash();
 
         state.sender().broadcast_transaction(vote)?;
@@ -261,7 +267,12 @@ impl PrivateApi {
     }
 
     fn handle_vote_against(state: &ServiceApiState, query: HashQuery) -> api::Result<VoteResponse> {
-        let vote_against = VoteAgainst::sign(state.public_key(), &query.hash, state.secret_key());
+        let vote_against = VoteAgainst::sign_for_network(
+            state.public_key(),
+            &query.hash,
+            state.blockchain().network_id(),
+            state.secret_key(),
+        );
         let tx_hash = vote_against.hash();
 
         state.sender().broadcast_transaction(vote_against)?;
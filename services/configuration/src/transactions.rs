@@ -116,26 +116,72 @@ impl ConfigurationTransactions {
 
 impl VoteAgainst {
     /// Create `Signed` for `VoteAgainst` transaction, signed by provided keys.
-    pub fn sign(author: &PublicKey, &cfg_hash: &Hash, key: &SecretKey) -> Signed<RawTransaction> {
-        Message::sign_transaction(Self { cfg_hash }, SERVICE_ID, *author, key)
+    pub fn sign(author: &PublicKey, cfg_hash: &Hash, key: &SecretKey) -> Signed<RawTransaction> {
+        Self::sign_for_network(author, cfg_hash, None, key)
+    }
+
+    /// Create `Signed` for `VoteAgainst` transaction, signed by provided keys
+    /// and bound to the specified network.
+    pub fn sign_for_network(
+        author: &PublicKey,
+        &cfg_hash: &Hash,
+        network_id: Option<Hash>,
+        key: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction_for_network(
+            Self { cfg_hash },
+            SERVICE_ID,
+            network_id,
+            *author,
+            key,
+        )
     }
 }
 
 impl Vote {
     /// Create `Signed` for `Vote` transaction, signed by provided keys.
-    pub fn sign(author: &PublicKey, &cfg_hash: &Hash, key: &SecretKey) -> Signed<RawTransaction> {
-        Message::sign_transaction(Self { cfg_hash }, SERVICE_ID, *author, key)
+    pub fn sign(author: &PublicKey, cfg_hash: &Hash, key: &SecretKey) -> Signed<RawTransaction> {
+        Self::sign_for_network(author, cfg_hash, None, key)
+    }
+
+    /// Create `Signed` for `Vote` transaction, signed by provided keys
+    /// and bound to the specified network.
+    pub fn sign_for_network(
+        author: &PublicKey,
+        &cfg_hash: &Hash,
+        network_id: Option<Hash>,
+        key: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction_for_network(
+            Self { cfg_hash },
+            SERVICE_ID,
+            network_id,
+            *author,
+            key,
+        )
     }
 }
 
 impl Propose {
     /// Create `Signed` for `Propose` transaction, signed by provided keys.
     pub fn sign(author: &PublicKey, cfg: &str, key: &SecretKey) -> Signed<RawTransaction> {
-        Message::sign_transaction(
+        Self::sign_for_network(author, cfg, None, key)
+    }
+
+    /// Create `Signed` for `Propose` transaction, signed by provided keys
+    /// and bound to the specified network.
+    pub fn sign_for_network(
+        author: &PublicKey,
+        cfg: &str,
+        network_id: Option<Hash>,
+        key: &SecretKey,
+    ) -> Signed<RawTransaction> {
+        Message::sign_transaction_for_network(
             Self {
                 cfg: cfg.to_owned(),
             },
             SERVICE_ID,
+            network_id,
             *author,
             key,
         )
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use exonum_merkledb::{Database, Patch, Result as StorageResult, Snapshot, View};
+use exonum_merkledb::{Database, DbStats, Patch, Result as StorageResult, Snapshot, View};
 
 use std::sync::{Arc, RwLock};
 
@@ -61,6 +61,14 @@ impl<T: Database> Database for CheckpointDb<T> {
     fn merge_sync(&self, patch: Patch) -> StorageResult<()> {
         self.merge(patch)
     }
+
+    fn storage_stats(&self) -> StorageResult<DbStats> {
+        self.inner
+            .read()
+            .expect("Cannot lock CheckpointDb for stats")
+            .db
+            .storage_stats()
+    }
 }
 
 impl<T: Database> From<CheckpointDb<T>> for Arc<dyn Database> {
@@ -184,7 +192,7 @@ impl<T: Database> CheckpointDbInner<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use exonum_merkledb::{Change, TemporaryDB};
+    use exonum_merkledb::{Change, MemoryDB};
 
     // Same as `Change`, but with trait implementations required for `Patch` comparison.
     #[derive(Debug, PartialOrd, Ord, PartialEq, Eq)]
@@ -240,7 +248,7 @@ mod tests {
 
     #[test]
     fn test_backup_stack() {
-        let db = CheckpointDb::new(TemporaryDB::new());
+        let db = CheckpointDb::new(MemoryDB::new());
         let handler = db.handler();
 
         assert_eq!(stack_len(&db), 0);
@@ -258,7 +266,7 @@ mod tests {
 
     #[test]
     fn test_backup() {
-        let db = CheckpointDb::new(TemporaryDB::new());
+        let db = CheckpointDb::new(MemoryDB::new());
         let handler = db.handler();
         handler.checkpoint();
         let fork = db.fork();
@@ -314,7 +322,7 @@ mod tests {
     #[test]
     #[allow(clippy::cognitive_complexity)]
     fn test_rollback() {
-        let db = CheckpointDb::new(TemporaryDB::new());
+        let db = CheckpointDb::new(MemoryDB::new());
         let handler = db.handler();
         let fork = db.fork();
         {
@@ -420,7 +428,7 @@ mod tests {
 
     #[test]
     fn test_handler() {
-        let db = CheckpointDb::new(TemporaryDB::new());
+        let db = CheckpointDb::new(MemoryDB::new());
         let handler = db.handler();
 
         handler.checkpoint();
@@ -441,7 +449,7 @@ mod tests {
     #[test]
     #[should_panic]
     fn test_extra_rollback() {
-        let db = CheckpointDb::new(TemporaryDB::new());
+        let db = CheckpointDb::new(MemoryDB::new());
         let handler = db.handler();
 
         handler.checkpoint();
@@ -174,7 +174,7 @@ use tokio_core::reactor::Core;
 use std::sync::{Arc, Mutex, RwLock};
 use std::{fmt, net::SocketAddr};
 
-use exonum_merkledb::{Database, Patch, Snapshot, TemporaryDB};
+use exonum_merkledb::{Database, MemoryDB, Patch, Snapshot};
 
 use exonum::{
     api::{
@@ -369,7 +369,7 @@ impl TestKitBuilder {
         let network =
             TestNetwork::with_our_role(self.our_validator_id, self.validator_count.unwrap_or(1));
         let genesis = network.genesis_config();
-        TestKit::assemble(TemporaryDB::new(), self.services, network, genesis)
+        TestKit::assemble(MemoryDB::new(), self.services, network, genesis)
     }
 
     /// Starts a testkit web server, which listens to public and private APIs exposed by
@@ -389,7 +389,7 @@ impl TestKitBuilder {
 /// (with no real network setup).
 pub struct TestKit {
     blockchain: Blockchain,
-    db_handler: CheckpointDbHandler<TemporaryDB>,
+    db_handler: CheckpointDbHandler<MemoryDB>,
     events_stream: Box<dyn Stream<Item = (), Error = ()> + Send + Sync>,
     processing_lock: Arc<Mutex<()>>,
     network: TestNetwork,
@@ -417,7 +417,7 @@ impl TestKit {
     }
 
     fn assemble(
-        database: TemporaryDB,
+        database: MemoryDB,
         services: Vec<Box<dyn Service>>,
         network: TestNetwork,
         genesis: GenesisConfig,
@@ -1106,7 +1106,7 @@ impl TestKit {
 /// ```
 #[derive(Debug)]
 pub struct StoppedTestKit {
-    db: TemporaryDB,
+    db: MemoryDB,
     network: TestNetwork,
     cfg_proposal: Option<ConfigurationProposalState>,
 }
@@ -17,13 +17,15 @@ extern crate pretty_assertions;
 
 use exonum::{
     api::node::{
-        private::NodeInfo,
+        private::{IndexInfo, NodeInfo},
         public::system::{ConsensusStatus, HealthCheckInfo, StatsInfo},
     },
     helpers::user_agent,
     messages::PROTOCOL_MAJOR_VERSION,
 };
+use exonum_merkledb::{CacheStats, DbStats, IndexType};
 use exonum_testkit::{ApiKind, TestKitBuilder};
+use serde_json::json;
 
 #[test]
 fn healthcheck() {
@@ -102,3 +104,80 @@ fn rebroadcast() {
         ()
     )
 }
+
+#[test]
+fn indexes() {
+    let mut testkit = TestKitBuilder::validator().with_validators(2).create();
+    testkit.create_block();
+    let api = testkit.api();
+
+    let indexes: Vec<IndexInfo> = api
+        .private(ApiKind::System)
+        .query(&json!({ "count_entries": true }))
+        .get("v1/indexes")
+        .unwrap();
+    let block_hashes = indexes
+        .iter()
+        .find(|info| info.name == "core.block_hashes_by_height")
+        .unwrap();
+    assert_eq!(block_hashes.index_type, IndexType::List);
+    assert_eq!(block_hashes.family_id, None);
+    assert_eq!(block_hashes.entries_count, Some(2));
+
+    // Transactions of each block are stored in a separate index of the family.
+    let block_transactions = indexes
+        .iter()
+        .filter(|info| info.name == "core.block_transactions")
+        .collect::<Vec<_>>();
+    assert!(!block_transactions.is_empty());
+    assert!(block_transactions
+        .iter()
+        .all(|info| info.index_type == IndexType::ProofList && info.family_id.is_some()));
+
+    // Entries are not counted by default.
+    let indexes: Vec<IndexInfo> = api.private(ApiKind::System).get("v1/indexes").unwrap();
+    assert!(indexes.iter().all(|info| info.entries_count.is_none()));
+}
+
+#[test]
+fn database_stats() {
+    let mut testkit = TestKitBuilder::validator().with_validators(2).create();
+    testkit.create_block();
+    let api = testkit.api();
+
+    let stats: DbStats = api
+        .private(ApiKind::System)
+        .get("v1/database_stats")
+        .unwrap();
+    // Testkit uses an in-memory database, so all the data is reported as stored in memtables.
+    assert!(!stats.column_families.is_empty());
+    assert!(stats.total_memtables_size() > 0);
+    assert_eq!(stats.total_sst_files_size(), 0);
+}
+
+#[test]
+fn cache_stats_of_uncached_database() {
+    let testkit = TestKitBuilder::validator().with_validators(2).create();
+    let api = testkit.api();
+
+    // Testkit does not cache the values read from its database.
+    let error = api
+        .private(ApiKind::System)
+        .get::<CacheStats>("v1/cache_stats")
+        .unwrap_err();
+    assert!(error.to_string().contains("Values are not cached"));
+}
+
+#[test]
+fn backup_of_in_memory_database() {
+    let testkit = TestKitBuilder::validator().with_validators(2).create();
+    let api = testkit.api();
+
+    // Testkit uses an in-memory database, which cannot be backed up.
+    let error = api
+        .private(ApiKind::System)
+        .query(&json!({ "path": "backup" }))
+        .post::<()>("v1/backup")
+        .unwrap_err();
+    assert!(error.to_string().contains("Checkpoints are not supported"));
+}